@@ -0,0 +1,231 @@
+//! Saving probed page bodies to disk and reading them back for offline re-analysis.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use tokio::fs;
+use tracing::warn;
+
+use crate::error::RadarError;
+use crate::types::{ProbeResult, SuccessJson};
+
+/// Write the body of every successful probe (status < 400) into `dir`.
+///
+/// Files are named after the probed URL via [`body_filename`]; failed probes are
+/// skipped. The directory is created if needed. A file that can't be written is
+/// logged and skipped, so one bad page doesn't lose the rest.
+///
+/// # Returns
+/// The number of files written, or an error if `dir` can't be created
+pub async fn dump_bodies(dir: &Path, probes: &[ProbeResult]) -> std::io::Result<usize> {
+    fs::create_dir_all(dir).await?;
+
+    let mut written = 0;
+    for p in probes {
+        if let (Some(s), Some(c)) = (p.status, p.content.as_ref()) {
+            if s < 400 {
+                let path = dir.join(body_filename(&p.subdomain));
+                match fs::write(&path, c).await {
+                    Ok(()) => written += 1,
+                    Err(e) => warn!(
+                        "⚠ Couldn't save the body of {} to {}: {}",
+                        p.subdomain,
+                        path.display(),
+                        e
+                    ),
+                }
+            }
+        }
+    }
+
+    Ok(written)
+}
+
+/// Read the `.html` files in `dir` back as successful probes, for offline runs.
+///
+/// Each file's URL is recovered with [`url_from_filename`]; other files are
+/// ignored. Results are sorted by URL so runs over the same directory match.
+///
+/// # Returns
+/// One probe result (status 200) per HTML file
+pub async fn load_bodies(dir: &Path) -> std::io::Result<Vec<ProbeResult>> {
+    let mut entries = fs::read_dir(dir).await?;
+    let mut probes = Vec::new();
+
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name();
+        let Some(url) = name.to_str().and_then(url_from_filename) else {
+            continue;
+        };
+        let bytes = fs::read(entry.path()).await?;
+        probes.push(ProbeResult::loaded(url, String::from_utf8_lossy(&bytes)));
+    }
+
+    probes.sort_by(|a, b| a.subdomain.cmp(&b.subdomain));
+    Ok(probes)
+}
+
+/// Read a `successes.json` written by an earlier run back as successful probes.
+///
+/// # Returns
+/// One probe result (status 200) per saved page, in file order
+pub async fn load_successes(path: &Path) -> Result<Vec<ProbeResult>, RadarError> {
+    let saved: Vec<SuccessJson> = serde_json::from_str(&fs::read_to_string(path).await?)?;
+    Ok(saved
+        .into_iter()
+        .map(|s| ProbeResult {
+            zone: s.zone,
+            final_url: s.final_url,
+            headers: s
+                .content_type
+                .map(|ct| HashMap::from([("content-type".to_string(), ct)]))
+                .unwrap_or_default(),
+            ..ProbeResult::loaded(s.url, s.content)
+        })
+        .collect())
+}
+
+/// Inverse of [`body_filename`]: `foo.hackclub.com_events.html` becomes
+/// `http://foo.hackclub.com/events`.
+///
+/// Returns `None` for names not ending in `.html` or whose escapes don't decode.
+pub fn url_from_filename(name: &str) -> Option<String> {
+    let stem = name.strip_suffix(".html")?;
+    if stem.is_empty() {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(stem.len());
+    let mut rest = stem.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        rest = tail;
+        match b {
+            b'_' => bytes.push(b'/'),
+            b'%' => {
+                let hex = std::str::from_utf8(rest.get(..2)?).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &rest[2..];
+            }
+            b => bytes.push(b),
+        }
+    }
+    let url = String::from_utf8(bytes).ok()?;
+    if url.contains("://") {
+        Some(url)
+    } else {
+        Some(format!("http://{}", url))
+    }
+}
+
+/// Filesystem-safe file name for a probed URL, which [`url_from_filename`]
+/// turns back into the URL.
+///
+/// `/` becomes `_`, `[A-Za-z0-9.-]` is kept, and every other byte (`_`, `:`,
+/// `?`, ...) is written as `%XX`, so distinct URLs never share a file. A leading
+/// `http://` is dropped; other schemes are kept. `http://foo.hackclub.com/events`
+/// is saved as `foo.hackclub.com_events.html`, `https://foo.hackclub.com:8443/a_b`
+/// as `https%3A__foo.hackclub.com%3A8443_a%5Fb.html`. A trailing slash is
+/// dropped, since `/events/` and `/events` are the same page.
+pub fn body_filename(url: &str) -> String {
+    let url = url.strip_prefix("http://").unwrap_or(url);
+    let mut name = String::with_capacity(url.len() + 5);
+    for b in url.trim_end_matches('/').bytes() {
+        match b {
+            b'/' => name.push('_'),
+            b if b.is_ascii_alphanumeric() || b == b'.' || b == b'-' => name.push(b as char),
+            b => name.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    name.push_str(".html");
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(url: &str, status: Option<u16>, content: Option<&str>) -> ProbeResult {
+        ProbeResult {
+            status,
+            content: content.map(str::to_string),
+            ..ProbeResult::new(url)
+        }
+    }
+
+    #[test]
+    fn filenames_round_trip() {
+        for url in [
+            "http://foo.hackclub.com",
+            "http://foo.hackclub.com/events",
+            "http://foo.hackclub.com:8080/events",
+            "http://foo.hackclub.com/spring_2026",
+            "https://foo.hackclub.com/events",
+            "http://foo.hackclub.com/events?page=2",
+        ] {
+            let name = body_filename(url);
+            assert!(
+                name.bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"._-%".contains(&b)),
+                "{}",
+                name
+            );
+            assert_eq!(url_from_filename(&name).as_deref(), Some(url), "{}", name);
+        }
+    }
+
+    #[test]
+    fn similar_urls_get_distinct_filenames() {
+        let names: Vec<String> = [
+            "http://foo.hackclub.com/a/b",
+            "http://foo.hackclub.com/a_b",
+            "http://foo.hackclub.com/a?b",
+            "http://foo.hackclub.com/a:b",
+            "https://foo.hackclub.com/a/b",
+        ]
+        .iter()
+        .map(|u| body_filename(u))
+        .collect();
+        let mut unique = names.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), names.len(), "{:?}", names);
+    }
+
+    #[test]
+    fn plain_names_decode_as_http() {
+        assert_eq!(
+            url_from_filename("foo.hackclub.com_events.html").as_deref(),
+            Some("http://foo.hackclub.com/events")
+        );
+        assert_eq!(url_from_filename("notes.txt"), None);
+        assert_eq!(url_from_filename("bad%zz.html"), None);
+    }
+
+    #[tokio::test]
+    async fn dumps_successes_only_and_reads_them_back() {
+        let dir = std::env::temp_dir().join(format!("radar-bodies-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let probes = [
+            probe("http://a.hackclub.com", Some(200), Some("<p>a</p>")),
+            probe("https://b.hackclub.com/x_y", Some(200), Some("<p>b</p>")),
+            probe("http://c.hackclub.com", Some(404), Some("gone")),
+            probe("http://d.hackclub.com", None, None),
+        ];
+
+        assert_eq!(dump_bodies(&dir, &probes).await.unwrap(), 2);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        let loaded = load_bodies(&dir).await.unwrap();
+        let pages: Vec<(&str, Option<&str>)> = loaded
+            .iter()
+            .map(|p| (p.subdomain.as_str(), p.content.as_deref()))
+            .collect();
+        assert_eq!(
+            pages,
+            [
+                ("http://a.hackclub.com", Some("<p>a</p>")),
+                ("https://b.hackclub.com/x_y", Some("<p>b</p>")),
+            ]
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
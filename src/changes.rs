@@ -0,0 +1,156 @@
+//! Change tracking for `--only-changed`: fingerprints of each subdomain's zone
+//! entry and page content from the previous run.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::error::RadarError;
+
+/// Fingerprints recorded at the end of a run.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ChangeState {
+    /// Subdomain base URL → fingerprint of its zone YAML entry.
+    #[serde(default)]
+    pub entries: BTreeMap<String, String>,
+    /// Page URL → fingerprint of its body.
+    #[serde(default)]
+    pub pages: BTreeMap<String, String>,
+}
+
+impl ChangeState {
+    /// Read the state saved at `path`; a missing file is an empty state.
+    pub async fn load(path: &Path) -> Result<Self, RadarError> {
+        match fs::read_to_string(path).await {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the state to `path`, replacing any previous one.
+    pub async fn save(&self, path: &Path) -> Result<(), RadarError> {
+        fs::write(path, serde_json::to_string_pretty(self)?).await?;
+        Ok(())
+    }
+
+    /// Whether a page must be extracted again.
+    ///
+    /// True when its subdomain is new, its zone entry changed, or its content
+    /// changed since this state was recorded. A page loaded without zone data
+    /// (`entry` is `None`) is judged on its content alone.
+    ///
+    /// # Arguments
+    /// * `base` - Subdomain base URL the page was reached from
+    /// * `entry` - Fingerprint of that subdomain's current zone entry
+    /// * `page` - URL the page was fetched from
+    /// * `content` - Fingerprint of the page's current body
+    pub fn changed(&self, base: &str, entry: Option<&str>, page: &str, content: &str) -> bool {
+        let entry_changed =
+            entry.is_some_and(|fp| self.entries.get(base).map(String::as_str) != Some(fp));
+        let content_changed = self.pages.get(page).map(String::as_str) != Some(content);
+        entry_changed || content_changed
+    }
+}
+
+/// Stable 64-bit FNV-1a fingerprint of `text`, as 16 hex digits.
+///
+/// Saved between runs, so it must not depend on the Rust version the way
+/// `std`'s `DefaultHasher` may.
+pub fn fingerprint(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn previous() -> ChangeState {
+        let seen = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), fingerprint(v)))
+                .collect()
+        };
+        ChangeState {
+            entries: seen(&[
+                ("http://yaml.hackclub.com", "type: A"),
+                ("http://content.hackclub.com", "type: A"),
+                ("http://stable.hackclub.com", "type: A"),
+            ]),
+            pages: seen(&[
+                ("http://yaml.hackclub.com", "<p>same</p>"),
+                ("http://content.hackclub.com", "<p>old</p>"),
+                ("http://stable.hackclub.com", "<p>same</p>"),
+            ]),
+        }
+    }
+
+    #[test]
+    fn only_new_or_changed_subdomains_are_processed() {
+        let state = previous();
+        let cases = [
+            ("http://yaml.hackclub.com", "type: CNAME", "<p>same</p>"),
+            ("http://content.hackclub.com", "type: A", "<p>new</p>"),
+            ("http://stable.hackclub.com", "type: A", "<p>same</p>"),
+            ("http://new.hackclub.com", "type: A", "<p>same</p>"),
+        ];
+
+        let processed: Vec<&str> = cases
+            .iter()
+            .filter(|(url, entry, body)| {
+                state.changed(url, Some(&fingerprint(entry)), url, &fingerprint(body))
+            })
+            .map(|(url, _, _)| *url)
+            .collect();
+
+        assert_eq!(
+            processed,
+            [
+                "http://yaml.hackclub.com",
+                "http://content.hackclub.com",
+                "http://new.hackclub.com",
+            ]
+        );
+    }
+
+    #[test]
+    fn pages_without_zone_data_are_judged_on_content() {
+        let state = previous();
+        let same = fingerprint("<p>same</p>");
+
+        assert!(!state.changed(
+            "http://stable.hackclub.com",
+            None,
+            "http://stable.hackclub.com",
+            &same
+        ));
+        assert!(state.changed(
+            "http://new.hackclub.com",
+            None,
+            "http://new.hackclub.com",
+            &same
+        ));
+    }
+
+    #[tokio::test]
+    async fn saved_state_loads_back_and_a_missing_file_is_empty() {
+        let dir = std::env::temp_dir().join(format!("radar-changes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.json");
+        let _ = std::fs::remove_file(&path);
+
+        assert!(ChangeState::load(&path).await.unwrap().pages.is_empty());
+        previous().save(&path).await.unwrap();
+        let loaded = ChangeState::load(&path).await.unwrap();
+
+        assert_eq!(loaded.entries, previous().entries);
+        assert_eq!(loaded.pages, previous().pages);
+        assert_eq!(fingerprint("abc"), "e71fa2190541574b");
+    }
+}
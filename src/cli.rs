@@ -0,0 +1,480 @@
+//! Command-line argument parsing.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use tracing::level_filters::LevelFilter;
+
+use hackclub_dns_fetcher::config::{Config, Preset};
+use hackclub_dns_fetcher::ratelimit::MAX_RATE_PER_MINUTE;
+use hackclub_dns_fetcher::sources::Source;
+use hackclub_dns_fetcher::types::Confidence;
+
+pub const USAGE: &str = "\
+Usage: hackclub-dns-fetcher [config | reextract --file <FILE> | eval [--file <FILE>]] [OPTIONS]
+
+Commands:
+  config                     Print the effective configuration as JSON and exit
+  reextract --file <FILE>    Run only the LLM stage over a saved successes.json
+  eval [--file <FILE>]       Score the LLM against JSON-LD events on probed (or saved) pages
+
+Options:
+  -v, --verbose              Print per-request progress
+  -q, --quiet                Print nothing but errors (output files are still written)
+      --log-level <LEVEL>    Log verbosity: error, warn, info, debug, trace, or off
+                             (overrides RUST_LOG, --verbose, and --quiet)
+      --no-progress          Don't draw the progress counters
+      --preset <NAME>        Start from aggressive, balanced, or polite settings
+      --yaml-url <URL>       DNS YAML to scan
+      --zones <LIST>         Scan these zones together, e.g. hackclub.com,hackclub.io
+                             (each read from <zone>.yaml beside --yaml-url)
+      --limit <N>            Probe only the first N subdomains in the zone
+      --api-json <URL>       Also import events from a JSON API (repeatable)
+      --yaml-cache <FILE>    Keep the DNS YAML here; refetch only when it changed
+      --max-yaml-bytes <N>   Abort if the DNS YAML is larger than this
+      --http-concurrency <N> Parallel probe requests
+      --concurrency <N>      Same as --http-concurrency
+      --llm-concurrency <N>  Parallel LLM requests
+      --rate-limit <RPM>     LLM requests per minute
+      --rate-burst <N>       LLM requests allowed back to back before the rate applies
+      --timeout <SECS>       HTTP request timeout
+      --context-window <TOKENS>
+                             Size page content to the model's context window
+      --llm-param <KEY=VALUE>
+                             Extra LLM request field, e.g. top_p=0.9 (repeatable)
+      --max-input-tokens <N> Estimated tokens of page content sent to the LLM (default 3000)
+      --temperature <T>      LLM sampling temperature (default 0.1)
+      --max-tokens <N>       Most tokens in each LLM reply (default 1024)
+      --prompt-template <FILE>
+                             Extraction prompt with {url} and {html} placeholders
+      --no-truncate          Send whole pages to the LLM (capped at ~100,000 tokens)
+      --raw-html             Send pages to the LLM without stripping scripts, styles,
+                             and navigation first
+      --head-max-chars <N>   Keep up to N chars of <head> when truncating pages
+      --max-total-bytes <N>  Stop probing after downloading N body bytes in total
+      --max-body-bytes <N>   Read at most N bytes of each page (default 262144, 0 = all)
+      --probe-retries <N>    Retries after a failed probe
+      --http-only            Probe subdomains over plain HTTP (skip the HTTPS attempt)
+      --host-failure-limit <N>
+                             Stop requesting a host after N straight failures (0 = never)
+      --host-rate-limit <RPM>
+                             Probe requests per minute to one origin (resolved address)
+      --yaml-retries <N>     Retries after a failed DNS YAML fetch
+      --llm-retries <N>      Retries after a failed (non-context-length) LLM request
+      --retry-statuses <CODES>
+                             Extra statuses to retry probes on, e.g. 408 (429/5xx always)
+      --timeout-overrides <FILE>
+                             YAML map of subdomain to timeout seconds
+      --probe-delay <MS>     Pause between requests to the same host
+      --respect-robots       Skip URLs the host's robots.txt disallows
+      --ignore-robots        Probe regardless of robots.txt (overrides --preset polite)
+      --path <PATH>          Extra path to probe on every subdomain (repeatable)
+      --keywords <LIST>      Comma-separated words a page needs to reach the LLM
+                             (default: hackathon,hack night,devpost,register)
+      --no-keyword-filter    Send every successful page to the LLM
+      --only-changed <FILE>  Only extract pages whose zone entry or content changed since
+                             the run that last wrote FILE
+      --no-meta-fallback     Don't guess an entry from a page's title and description when the
+                             LLM finds no events there
+      --no-json-ld           Ask the LLM even about pages whose JSON-LD fully describes
+                             their events
+      --include-content-in-summary
+                             Embed a short source-page snippet with each hackathon
+      --between <START> <END>
+                             Keep only events overlapping YYYY-MM-DD..YYYY-MM-DD
+      --stats-only           Probe and write stats.json; skip the LLM and summary
+      --no-llm, --dry-run    Probe and write results.json/successes.json, report how many
+                             pages would go to the LLM, and stop (no API key needed)
+      --merge-series         Collapse recurring same-name events on a page into one
+      --dedupe-by-url        Merge hackathons from different pages with the same URL
+      --verify-urls          Check that every extracted hackathon URL returns 2xx
+      --chapters             Write chapters.json: organizers and how many events each ran
+      --csv                  Also write summary.csv (name, dates, url, summary)
+      --skipped              Write skipped.json: pages left out of extraction and why
+      --dump-bodies <DIR>    Save the body of every successful probe into DIR
+      --from-dir <DIR>       Skip probing and extract from the .html files in DIR
+      --no-cache             Probe every subdomain, ignoring the saved probe cache
+      --probe-cache <FILE>   Where reachable probes are cached between runs
+                             (default .probe-cache.json)
+      --cache-ttl <HOURS>    Reuse cached probes younger than this (default 24)
+      --history <FILE>       Append this run's reachability to a JSON-lines history
+      --stale-after <N>      With --history, warn about URLs down for the last N runs
+      --with-git-history     Clone hackclub/dns, date each subdomain from its history, and
+                             list the newest subdomains first
+      --metrics-file <FILE>  Write Prometheus text-format metrics for the run
+      --timestamped-output   Add the run's start time to output file names
+      --output-dir <DIR>     Write output files into DIR (created if missing)
+      --min-confidence <LEVEL>
+                             Print only hackathons rated at least low, medium, or high
+                             (summary.json keeps them all)
+      --format <FORMAT>      Summary output: text (default, full detail), table,
+                             or ics (also writes hackathons.ics)
+      --skip-private         Don't probe subdomains resolving to private/loopback IPs
+      --dns-cache-size <N>   Hosts whose DNS answers and robots.txt rules are reused
+                             within a run (0 = off)
+      --redirect-allowlist <LIST>
+                             Only follow redirects off the zone to these hosts
+                             (comma-separated; `*.mlh.io` matches subdomains)
+      --proxies <FILE>       Rotate probes across the proxy URLs in FILE (one per line)
+      --resolve <HOST:IP>    Pin a host to an address, like curl (repeatable)
+  -h, --help                 Print this help";
+
+/// What the invocation should do.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Run the full scan.
+    Run,
+    /// Print the resolved configuration and exit.
+    PrintConfig,
+    /// Re-run extraction over a saved `successes.json`, skipping DNS and probing.
+    Reextract(PathBuf),
+    /// Compare LLM output with pages' JSON-LD events, optionally over a saved
+    /// `successes.json`.
+    Eval(Option<PathBuf>),
+    /// Print usage and exit.
+    Help,
+}
+
+/// Parsed command-line options that are not part of `Config`.
+#[derive(Debug)]
+pub struct Cli {
+    pub command: Command,
+    pub verbose: bool,
+    pub quiet: bool,
+    /// `--log-level`, when given.
+    pub log_level: Option<LevelFilter>,
+    pub no_progress: bool,
+    /// `--min-confidence`: hide lower-rated hackathons from the printed summary.
+    pub min_confidence: Option<Confidence>,
+}
+
+impl Cli {
+    /// Parse `args` (without the program name), applying flag overrides to `config`.
+    pub fn parse<I>(args: I, config: &mut Config) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut cli = Cli {
+            command: Command::Run,
+            verbose: false,
+            quiet: false,
+            log_level: None,
+            no_progress: false,
+            min_confidence: None,
+        };
+        let mut args = args.into_iter();
+        let mut reextract = false;
+        let mut eval = false;
+        let mut file: Option<PathBuf> = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "config" if cli.command == Command::Run => cli.command = Command::PrintConfig,
+                "reextract" if cli.command == Command::Run => reextract = true,
+                "eval" if cli.command == Command::Run => eval = true,
+                "--file" => file = Some(value(&arg, args.next())?),
+                "-h" | "--help" => cli.command = Command::Help,
+                "-v" | "--verbose" => cli.verbose = true,
+                "-q" | "--quiet" => cli.quiet = true,
+                "--log-level" => cli.log_level = Some(value(&arg, args.next())?),
+                "--no-progress" => cli.no_progress = true,
+                "--min-confidence" => cli.min_confidence = Some(value(&arg, args.next())?),
+                // Applied up front by `find_preset`; just consume the value here.
+                "--preset" => {
+                    value::<Preset>(&arg, args.next())?;
+                }
+                "--yaml-url" => config.yaml_url = value(&arg, args.next())?,
+                "--limit" => config.limit = Some(value(&arg, args.next())?),
+                "--api-json" => config.extra_sources.push(Source::ApiJson {
+                    url: value(&arg, args.next())?,
+                }),
+                "--yaml-cache" => config.yaml_cache = Some(value(&arg, args.next())?),
+                "--max-yaml-bytes" => config.max_yaml_bytes = value(&arg, args.next())?,
+                "--http-concurrency" | "--concurrency" => {
+                    config.http_concurrency = value(&arg, args.next())?
+                }
+                "--llm-concurrency" => config.llm_concurrency = value(&arg, args.next())?,
+                "--rate-limit" => config.llm_rate_limit_per_minute = rate(&arg, args.next(), 1)?,
+                "--rate-burst" => config.llm_rate_burst = value(&arg, args.next())?,
+                "--timeout" => config.request_timeout_secs = value(&arg, args.next())?,
+                "--context-window" => {
+                    config.llm.context_window_tokens = Some(value(&arg, args.next())?)
+                }
+                "--llm-param" => {
+                    let raw: String = value(&arg, args.next())?;
+                    let (key, val) = raw
+                        .split_once('=')
+                        .ok_or_else(|| format!("--llm-param expects KEY=VALUE, got '{}'", raw))?;
+                    // JSON values (numbers, booleans, objects) pass through; anything else is a string.
+                    let val = serde_json::from_str(val)
+                        .unwrap_or_else(|_| serde_json::Value::String(val.to_string()));
+                    config.llm.extra_params.insert(key.to_string(), val);
+                }
+                "--max-input-tokens" => config.llm.max_input_tokens = value(&arg, args.next())?,
+                "--no-truncate" => config.llm.max_input_tokens = 0,
+                "--temperature" => config.llm.temperature = value(&arg, args.next())?,
+                "--max-tokens" => config.llm.max_tokens = value(&arg, args.next())?,
+                "--prompt-template" => {
+                    config.llm.prompt_template_file = Some(value(&arg, args.next())?)
+                }
+                "--raw-html" => config.llm.strip_boilerplate = false,
+                "--head-max-chars" => config.llm.head_max_chars = value(&arg, args.next())?,
+                "--max-total-bytes" => config.max_total_bytes = Some(value(&arg, args.next())?),
+                "--max-body-bytes" => config.max_body_bytes = value(&arg, args.next())?,
+                "--probe-retries" => config.probe_retry.max_retries = value(&arg, args.next())?,
+                "--http-only" => config.https_first = false,
+                "--host-failure-limit" => config.host_failure_limit = value(&arg, args.next())?,
+                "--host-rate-limit" => {
+                    config.host_rate_limit_per_minute = rate(&arg, args.next(), 0)?
+                }
+                "--yaml-retries" => config.yaml_retry.max_retries = value(&arg, args.next())?,
+                "--llm-retries" => config.llm.retry.max_retries = value(&arg, args.next())?,
+                "--retry-statuses" => {
+                    let raw: String = value(&arg, args.next())?;
+                    for code in raw.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+                        let code = value(&arg, Some(code.to_string()))?;
+                        config.probe_retry.retry_statuses.insert(code);
+                    }
+                }
+                "--timeout-overrides" => {
+                    config.timeout_overrides_file = Some(value(&arg, args.next())?)
+                }
+                "--probe-delay" => config.probe_delay_ms = value(&arg, args.next())?,
+                "--respect-robots" => config.respect_robots = true,
+                "--ignore-robots" => config.respect_robots = false,
+                "--path" => config.extra_paths.push(value(&arg, args.next())?),
+                "--keywords" => {
+                    let raw: String = value(&arg, args.next())?;
+                    config.keywords = raw
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|k| !k.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                }
+                "--no-keyword-filter" => config.keyword_filter = false,
+                "--only-changed" => config.only_changed = Some(value(&arg, args.next())?),
+                // The default now; still accepted for existing scripts.
+                "--prefer-json-ld" => config.prefer_json_ld = true,
+                "--no-json-ld" => config.prefer_json_ld = false,
+                "--no-meta-fallback" => config.meta_fallback = false,
+                "--include-content-in-summary" => config.include_content_in_summary = true,
+                "--between" => {
+                    let start: NaiveDate = value(&arg, args.next())?;
+                    let end: NaiveDate = value(&arg, args.next())?;
+                    if end < start {
+                        return Err(format!("--between: {} is before {}", end, start));
+                    }
+                    config.between = Some((start, end));
+                }
+                "--stats-only" => config.stats_only = true,
+                "--no-llm" | "--dry-run" => config.dry_run = true,
+                "--merge-series" => config.merge_series = true,
+                "--dedupe-by-url" => config.dedupe_by_url = true,
+                "--verify-urls" => config.verify_urls = true,
+                "--chapters" => config.chapters = true,
+                "--csv" => config.csv = true,
+                "--skipped" => config.write_skipped = true,
+                "--dump-bodies" => config.dump_bodies = Some(value(&arg, args.next())?),
+                "--from-dir" => config.from_dir = Some(value(&arg, args.next())?),
+                "--no-cache" => config.probe_cache = None,
+                "--probe-cache" => config.probe_cache = Some(value(&arg, args.next())?),
+                "--cache-ttl" => config.probe_cache_ttl_hours = value(&arg, args.next())?,
+                "--history" => config.history_file = Some(value(&arg, args.next())?),
+                "--stale-after" => config.stale_after_runs = Some(value(&arg, args.next())?),
+                "--with-git-history" => config.with_git_history = true,
+                "--metrics-file" => config.metrics_file = Some(value(&arg, args.next())?),
+                "--timestamped-output" => config.timestamped_output = true,
+                "--output-dir" => config.output_dir = Some(value(&arg, args.next())?),
+                "--format" => config.format = value(&arg, args.next())?,
+                "--skip-private" => config.skip_private = true,
+                "--dns-cache-size" => config.dns_cache_size = value(&arg, args.next())?,
+                "--zones" => {
+                    let raw: String = value(&arg, args.next())?;
+                    config.zones = raw
+                        .split(',')
+                        .map(|z| z.trim().to_ascii_lowercase())
+                        .filter(|z| !z.is_empty())
+                        .collect();
+                }
+                "--redirect-allowlist" => {
+                    let raw: String = value(&arg, args.next())?;
+                    config.redirect_allowlist = raw
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|h| !h.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                }
+                "--proxies" => config.proxies_file = Some(value(&arg, args.next())?),
+                "--resolve" => config.resolve.push(value(&arg, args.next())?),
+                _ => return Err(format!("unexpected argument '{}'\n\n{}", arg, USAGE)),
+            }
+        }
+
+        if cli.command == Command::Run && reextract {
+            let file = file.ok_or("reextract requires --file <FILE>")?;
+            cli.command = Command::Reextract(file);
+        } else if cli.command == Command::Run && eval {
+            cli.command = Command::Eval(file);
+        } else if file.is_some() {
+            return Err("--file is only valid with reextract or eval".to_string());
+        }
+
+        if cli.verbose && cli.quiet {
+            return Err("--quiet and --verbose cannot be used together".to_string());
+        }
+        if config.dry_run && matches!(cli.command, Command::Eval(_)) {
+            return Err("eval needs the LLM; it can't be combined with --no-llm".to_string());
+        }
+
+        Ok(cli)
+    }
+}
+
+/// Find the `--preset` in `args`, if any.
+///
+/// Presets sit below environment variables and other flags, so they must be
+/// applied before either regardless of where `--preset` appears.
+pub fn find_preset(args: &[String]) -> Result<Option<Preset>, String> {
+    match args.iter().position(|a| a == "--preset") {
+        Some(i) => value("--preset", args.get(i + 1).cloned()).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Parse a requests-per-minute value following `flag`, between `min` and
+/// [`MAX_RATE_PER_MINUTE`].
+fn rate(flag: &str, raw: Option<String>, min: u32) -> Result<u32, String> {
+    let rpm = value(flag, raw)?;
+    if !(min..=MAX_RATE_PER_MINUTE).contains(&rpm) {
+        return Err(format!(
+            "{} must be between {} and {} requests per minute, got {}",
+            flag, min, MAX_RATE_PER_MINUTE, rpm
+        ));
+    }
+    Ok(rpm)
+}
+
+/// Parse the value following `flag`.
+fn value<T: FromStr>(flag: &str, raw: Option<String>) -> Result<T, String> {
+    let raw = raw.ok_or_else(|| format!("{} requires a value", flag))?;
+    raw.parse()
+        .map_err(|_| format!("invalid value '{}' for {}", raw, flag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse `args` over the default config, applying any `--preset` first as
+    /// `main` does.
+    fn parse(args: &[&str]) -> Result<(Cli, Config), String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let mut config = Config::default();
+        if let Some(preset) = find_preset(&args)? {
+            preset.apply(&mut config);
+        }
+        let cli = Cli::parse(args, &mut config)?;
+        Ok((cli, config))
+    }
+
+    #[test]
+    fn rate_limits_are_range_checked() {
+        let (_, config) = parse(&["--rate-limit", "120", "--host-rate-limit", "0"]).unwrap();
+        assert_eq!(config.llm_rate_limit_per_minute, 120);
+        assert_eq!(config.host_rate_limit_per_minute, 0);
+
+        assert!(parse(&["--rate-limit", "0"]).is_err());
+        assert!(parse(&["--rate-limit", "60001"]).is_err());
+        assert!(parse(&["--host-rate-limit", "4000000000"]).is_err());
+    }
+
+    #[test]
+    fn explicit_flags_override_the_preset_wherever_it_appears() {
+        let (_, config) = parse(&[
+            "--http-concurrency",
+            "8",
+            "--preset",
+            "polite",
+            "--ignore-robots",
+        ])
+        .unwrap();
+        assert_eq!(config.preset, Some(Preset::Polite));
+        assert_eq!(config.http_concurrency, 8);
+        assert!(!config.respect_robots);
+        // Fields without a flag keep the preset's value.
+        assert_eq!(config.probe_delay_ms, 1000);
+        assert_eq!(config.host_rate_limit_per_minute, 60);
+    }
+
+    #[test]
+    fn unknown_preset_is_rejected() {
+        assert!(parse(&["--preset", "reckless"]).is_err());
+    }
+
+    #[test]
+    fn printed_config_reflects_an_overriding_flag() {
+        let (cli, mut config) = parse(&["config", "--http-concurrency", "50"]).unwrap();
+        config.llm.api_key = "secret".to_string();
+
+        assert_eq!(cli.command, Command::PrintConfig);
+        let printed = serde_json::to_value(&config).unwrap();
+        assert_eq!(printed["http_concurrency"], 50);
+        assert!(!printed.to_string().contains("secret"));
+    }
+
+    #[test]
+    fn retry_statuses_add_to_the_probe_set() {
+        let (_, config) = parse(&["--retry-statuses", "408, 425"]).unwrap();
+        let statuses = &config.probe_retry.retry_statuses;
+        assert!(statuses.contains(&408) && statuses.contains(&425));
+        assert!(statuses.contains(&429));
+
+        assert!(parse(&["--retry-statuses", "abc"]).is_err());
+    }
+
+    #[test]
+    fn each_retry_flag_sets_only_its_own_endpoint() {
+        let (_, defaults) = parse(&[]).unwrap();
+        let (_, config) = parse(&["--probe-retries", "5"]).unwrap();
+        assert_eq!(config.probe_retry.max_retries, 5);
+        assert_eq!(
+            config.yaml_retry.max_retries,
+            defaults.yaml_retry.max_retries
+        );
+        assert_eq!(config.llm.retry.max_retries, defaults.llm.retry.max_retries);
+
+        let (_, config) = parse(&["--yaml-retries", "6", "--llm-retries", "0"]).unwrap();
+        assert_eq!(config.yaml_retry.max_retries, 6);
+        assert_eq!(config.llm.retry.max_retries, 0);
+        assert_eq!(
+            config.probe_retry.max_retries,
+            defaults.probe_retry.max_retries
+        );
+    }
+
+    #[test]
+    fn between_takes_an_ordered_pair_of_dates() {
+        let (_, config) = parse(&["--between", "2025-06-01", "2025-08-31"]).unwrap();
+        let (start, end) = config.between.unwrap();
+        assert_eq!(start.to_string(), "2025-06-01");
+        assert_eq!(end.to_string(), "2025-08-31");
+
+        assert!(parse(&["--between", "2025-08-31", "2025-06-01"]).is_err());
+        assert!(parse(&["--between", "2025-06-01"]).is_err());
+    }
+
+    #[test]
+    fn reextract_needs_a_file() {
+        let (cli, _) = parse(&["reextract", "--file", "out/successes.json"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Command::Reextract(PathBuf::from("out/successes.json"))
+        );
+
+        assert!(parse(&["reextract"]).is_err());
+        assert!(parse(&["--file", "out/successes.json"]).is_err());
+    }
+}
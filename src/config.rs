@@ -1,5 +1,20 @@
 //! Configuration constants for the HackClub Events Radar.
 
+use std::collections::BTreeSet;
+use std::env;
+use std::fmt;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use reqwest::Url;
+use serde::Serialize;
+
+use crate::dns::zone_name;
+use crate::retry::RetryPolicy;
+use crate::sources::Source;
+
 /// Concurrency level for parallel HTTP requests (DNS probing).
 pub const HTTP_CONCURRENCY: usize = 20;
 
@@ -11,7 +26,11 @@ pub const LLM_CONCURRENCY: usize = 4;
 /// Ensure concurrency * ~(60 / requests_per_minute) >= 1
 pub const LLM_RATE_LIMIT_PER_MINUTE: u32 = 40;
 
-/// NVIDIA NIM API endpoint for chat completions.
+/// LLM requests allowed back to back before the per-minute rate applies.
+pub const LLM_RATE_BURST: u32 = 4;
+
+/// NVIDIA NIM API endpoint for chat completions. Any OpenAI-compatible endpoint
+/// works.
 pub const NIM_API_URL: &str = "https://integrate.api.nvidia.com/v1/chat/completions";
 
 /// LLM model identifier (GPT OSS via NVIDIA NIM).
@@ -20,11 +39,793 @@ pub const NIM_MODEL: &str = "openai/gpt-oss-120b";
 /// HTTP request timeout duration in seconds.
 pub const REQUEST_TIMEOUT_SECS: u64 = 15;
 
-/// Maximum characters from HTML to send to the LLM (to avoid context window limits).
-pub const HTML_TRUNCATE_CHARS: usize = 12_000;
+/// Estimated tokens of page content sent to the LLM (to avoid context window
+/// limits); see [`crate::tokens`].
+pub const LLM_MAX_INPUT_TOKENS: usize = 3_000;
+
+/// Smallest page-content budget, in tokens, tried when shrinking after a
+/// context-length error.
+pub const MIN_CONTENT_TOKENS: usize = 250;
+
+/// Hosts whose resolved addresses are kept for the rest of a run.
+pub const DNS_CACHE_SIZE: usize = 256;
+
+/// Most characters of a page's `<head>` kept when truncating HTML for the LLM.
+pub const HEAD_MAX_CHARS: usize = 4_000;
+
+/// Hard cap, in estimated tokens, on page content sent with truncation
+/// disabled (`--no-truncate`).
+pub const UNTRUNCATED_MAX_TOKENS: usize = 100_000;
 
 /// Maximum tokens to request from the LLM.
 pub const LLM_MAX_TOKENS: u32 = 1024;
 
 /// Temperature parameter for LLM sampling (lower = more deterministic).
 pub const LLM_TEMPERATURE: f32 = 0.1;
+
+/// Retries after a failed probe.
+pub const PROBE_RETRIES: u32 = 2;
+
+/// Delay before the first probe retry, in milliseconds (doubles per retry).
+pub const PROBE_RETRY_BASE_MS: u64 = 250;
+
+/// Longest wait between probe retries, in milliseconds.
+pub const PROBE_RETRY_MAX_MS: u64 = 5_000;
+
+/// Consecutive failed requests after which a host is skipped for the rest of a run.
+pub const HOST_FAILURE_LIMIT: usize = 3;
+
+/// Probe requests per minute allowed to one origin (resolved address); 0 = unlimited.
+pub const HOST_RATE_LIMIT_PER_MINUTE: u32 = 0;
+
+/// Where probe results are kept between runs (`--no-cache` skips it).
+pub const PROBE_CACHE_FILE: &str = ".probe-cache.json";
+
+/// Hours a reachable subdomain's cached probe is reused before it is probed again.
+pub const PROBE_CACHE_TTL_HOURS: u64 = 24;
+
+/// Retries after a failed DNS YAML fetch.
+pub const YAML_RETRIES: u32 = 3;
+
+/// Delay before the first DNS YAML retry, in milliseconds (doubles per retry).
+pub const YAML_RETRY_BASE_MS: u64 = 1_000;
+
+/// Longest wait between DNS YAML retries, in milliseconds.
+pub const YAML_RETRY_MAX_MS: u64 = 10_000;
+
+/// Retries after a failed LLM request. Kept low: each attempt costs quota.
+pub const LLM_RETRIES: u32 = 1;
+
+/// Delay before the first LLM retry, in milliseconds (doubles per retry).
+pub const LLM_RETRY_BASE_MS: u64 = 2_000;
+
+/// Longest wait between LLM retries, in milliseconds.
+pub const LLM_RETRY_MAX_MS: u64 = 30_000;
+
+/// Statuses retried by default: rate limiting and transient server errors.
+const SERVER_RETRY_STATUSES: [u16; 5] = [429, 500, 502, 503, 504];
+
+/// Tokens reserved for the fixed prompt instructions around the page content.
+pub const PROMPT_OVERHEAD_TOKENS: usize = 400;
+
+/// Rough characters-per-token ratio, used to read the character budget of the
+/// older `HTML_TRUNCATE_CHARS` setting as tokens.
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// Keywords that suggest a page is about an event.
+pub const EVENT_KEYWORDS: &[&str] = &["hackathon", "hack night", "devpost", "register"];
+
+/// Maximum characters of page text embedded with `--include-content-in-summary`.
+pub const SNIPPET_CHARS: usize = 280;
+
+/// Largest DNS YAML response accepted before aborting (16 MiB).
+pub const MAX_YAML_BYTES: usize = 16 * 1024 * 1024;
+
+/// Most bytes of a page body read per probe; 0 reads whole bodies.
+pub const MAX_BODY_BYTES: usize = 256 * 1024;
+
+/// Default location of the HackClub DNS zone file.
+pub const DNS_YAML_URL: &str =
+    "https://raw.githubusercontent.com/hackclub/dns/refs/heads/main/hackclub.com.yaml";
+
+/// Git repository the DNS zone file lives in, cloned for `--with-git-history`.
+pub const DNS_REPO_URL: &str = "https://github.com/hackclub/dns";
+
+/// Where the DNS repository is cloned (a bare clone, reused between runs).
+pub const DNS_REPO_DIR: &str = ".hackclub-dns.git";
+
+/// Effective runtime configuration.
+///
+/// Resolved in layers: the constants above provide defaults, an optional
+/// [`Preset`] adjusts them, environment variables named after each constant
+/// override those, and CLI flags override everything.
+#[derive(Debug, Clone, Serialize)]
+pub struct Config {
+    /// Preset the values below started from, if any.
+    pub preset: Option<Preset>,
+    /// URL of the DNS YAML to scan.
+    pub yaml_url: String,
+    /// Zones to scan together (`--zones`), each read from `<zone>.yaml` next to
+    /// `yaml_url`; empty scans `yaml_url` alone.
+    pub zones: Vec<String>,
+    /// Probe only the first this many subdomains of the zone (`--limit`).
+    pub limit: Option<usize>,
+    /// Structured sources scanned alongside the DNS zone (`--api-json`).
+    pub extra_sources: Vec<Source>,
+    /// Local copy of the DNS YAML reused on 304s and fetch failures (`--yaml-cache`).
+    pub yaml_cache: Option<PathBuf>,
+    /// Retry behaviour for the DNS YAML fetch.
+    pub yaml_retry: RetryPolicy,
+    /// Largest DNS YAML response accepted.
+    pub max_yaml_bytes: usize,
+    /// Parallel HTTP requests during probing.
+    pub http_concurrency: usize,
+    /// Parallel LLM requests.
+    pub llm_concurrency: usize,
+    /// LLM requests allowed per minute.
+    pub llm_rate_limit_per_minute: u32,
+    /// LLM requests allowed back to back before the rate applies.
+    pub llm_rate_burst: u32,
+    /// HTTP request timeout in seconds.
+    pub request_timeout_secs: u64,
+    /// Stop probing once this many body bytes have been downloaded in total.
+    pub max_total_bytes: Option<u64>,
+    /// Most bytes read from each probed body (0 = no cap).
+    pub max_body_bytes: usize,
+    /// Retry behaviour for probes.
+    pub probe_retry: RetryPolicy,
+    /// YAML map of subdomain → timeout seconds for hosts that need longer (or shorter)
+    /// than `request_timeout_secs`.
+    pub timeout_overrides_file: Option<PathBuf>,
+    /// Probe subdomains over HTTPS first, falling back to HTTP (off with `--http-only`).
+    pub https_first: bool,
+    /// Consecutive failures before a host's remaining requests are short-circuited;
+    /// 0 never gives up on a host.
+    pub host_failure_limit: usize,
+    /// Probe requests per minute allowed to one origin, shared by the subdomains
+    /// resolving to it; 0 leaves origins unthrottled.
+    pub host_rate_limit_per_minute: u32,
+    /// Pause between successive requests to the same host, in milliseconds.
+    pub probe_delay_ms: u64,
+    /// Skip URLs the host's `robots.txt` disallows (`--respect-robots`).
+    pub respect_robots: bool,
+    /// Extra paths probed on each subdomain after its root (e.g. `/events`).
+    pub extra_paths: Vec<String>,
+    /// Only send pages mentioning one of `keywords` to the LLM.
+    pub keyword_filter: bool,
+    /// Words that mark a page as possibly about an event (`--keywords`).
+    pub keywords: Vec<String>,
+    /// State file of zone-entry and content fingerprints; pages whose entry and content
+    /// are unchanged since the last run skip extraction (`--only-changed`).
+    pub only_changed: Option<PathBuf>,
+    /// When the LLM finds nothing on a page whose title or description mentions
+    /// an event keyword, add a low-confidence entry built from those tags.
+    pub meta_fallback: bool,
+    /// Use a page's JSON-LD events instead of the LLM when they are complete
+    /// (on by default; `--no-json-ld` turns it off).
+    pub prefer_json_ld: bool,
+    /// Attach a short page-text snippet to each extracted hackathon.
+    pub include_content_in_summary: bool,
+    /// Keep only events overlapping this inclusive date window (`--between`).
+    pub between: Option<(NaiveDate, NaiveDate)>,
+    /// Probe and write zone statistics only, skipping LLM extraction.
+    pub stats_only: bool,
+    /// Run everything up to the LLM pass, report how many pages it would be
+    /// sent, and stop (`--no-llm`); no API key is needed.
+    pub dry_run: bool,
+    /// Collapse same-name entries on one page into a single dated series.
+    pub merge_series: bool,
+    /// Also merge hackathons from different pages that share an event URL.
+    pub dedupe_by_url: bool,
+    /// Write `skipped.json`: each page left out of extraction and why (`--skipped`).
+    pub write_skipped: bool,
+    /// Write `chapters.json`: each organizer and the events it ran (`--chapters`).
+    pub chapters: bool,
+    /// Also write `summary.csv`: name, dates, URL, and summary per hackathon (`--csv`).
+    pub csv: bool,
+    /// Probe each extracted hackathon URL and record whether it is reachable.
+    pub verify_urls: bool,
+    /// Directory to save every successful page body in (`--dump-bodies`).
+    pub dump_bodies: Option<PathBuf>,
+    /// Read page bodies from this directory instead of probing (`--from-dir`).
+    pub from_dir: Option<PathBuf>,
+    /// Reachable probes saved here are reused by later runs; `None` probes
+    /// everything (`--no-cache`).
+    pub probe_cache: Option<PathBuf>,
+    /// Hours a cached probe stays fresh; 0 never reuses one.
+    pub probe_cache_ttl_hours: u64,
+    /// JSON-lines file recording each run's reachability (`--history`).
+    pub history_file: Option<PathBuf>,
+    /// Warn about URLs unreachable in this many consecutive recorded runs.
+    pub stale_after_runs: Option<usize>,
+    /// Clone the DNS repository and date each hackathon's subdomain from its history.
+    pub with_git_history: bool,
+    /// Clone location used by `with_git_history`.
+    pub dns_repo_dir: PathBuf,
+    /// Write Prometheus text-format metrics here after the run (`--metrics-file`).
+    pub metrics_file: Option<PathBuf>,
+    /// Suffix output file names with the run's start time.
+    pub timestamped_output: bool,
+    /// Directory output files are written to (`--output-dir`); the working directory if unset.
+    pub output_dir: Option<PathBuf>,
+    /// How the summary is printed to the terminal.
+    pub format: OutputFormat,
+    /// Skip subdomains that only resolve to loopback, private, or link-local addresses.
+    pub skip_private: bool,
+    /// Hosts whose DNS answers (and, with `respect_robots`, origins whose
+    /// `robots.txt` rules) are cached for the run; 0 disables the caches.
+    pub dns_cache_size: usize,
+    /// External hosts probes may be redirected to (`devpost.com`, `*.mlh.io`); empty
+    /// follows every redirect. Redirects elsewhere are recorded but not fetched.
+    pub redirect_allowlist: Vec<String>,
+    /// File of egress proxy URLs the probes rotate through (`--proxies`).
+    pub proxies_file: Option<PathBuf>,
+    /// DNS overrides applied to the HTTP client (`--resolve host:ip`).
+    pub resolve: Vec<ResolveOverride>,
+    /// LLM request settings.
+    pub llm: LlmConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            preset: None,
+            yaml_url: DNS_YAML_URL.to_string(),
+            zones: Vec::new(),
+            limit: None,
+            extra_sources: Vec::new(),
+            yaml_cache: None,
+            yaml_retry: RetryPolicy {
+                max_retries: YAML_RETRIES,
+                base_delay_ms: YAML_RETRY_BASE_MS,
+                max_delay_ms: YAML_RETRY_MAX_MS,
+                retry_statuses: BTreeSet::from(SERVER_RETRY_STATUSES),
+            },
+            max_yaml_bytes: MAX_YAML_BYTES,
+            http_concurrency: HTTP_CONCURRENCY,
+            llm_concurrency: LLM_CONCURRENCY,
+            llm_rate_limit_per_minute: LLM_RATE_LIMIT_PER_MINUTE,
+            llm_rate_burst: LLM_RATE_BURST,
+            request_timeout_secs: REQUEST_TIMEOUT_SECS,
+            max_total_bytes: None,
+            max_body_bytes: MAX_BODY_BYTES,
+            probe_retry: RetryPolicy {
+                max_retries: PROBE_RETRIES,
+                base_delay_ms: PROBE_RETRY_BASE_MS,
+                max_delay_ms: PROBE_RETRY_MAX_MS,
+                retry_statuses: BTreeSet::from(SERVER_RETRY_STATUSES),
+            },
+            timeout_overrides_file: None,
+            https_first: true,
+            host_failure_limit: HOST_FAILURE_LIMIT,
+            host_rate_limit_per_minute: HOST_RATE_LIMIT_PER_MINUTE,
+            probe_delay_ms: 0,
+            respect_robots: false,
+            extra_paths: Vec::new(),
+            keyword_filter: true,
+            keywords: EVENT_KEYWORDS.iter().map(|k| k.to_string()).collect(),
+            only_changed: None,
+            meta_fallback: true,
+            prefer_json_ld: true,
+            include_content_in_summary: false,
+            between: None,
+            stats_only: false,
+            dry_run: false,
+            merge_series: false,
+            dedupe_by_url: false,
+            write_skipped: false,
+            chapters: false,
+            csv: false,
+            verify_urls: false,
+            dump_bodies: None,
+            from_dir: None,
+            probe_cache: Some(PathBuf::from(PROBE_CACHE_FILE)),
+            probe_cache_ttl_hours: PROBE_CACHE_TTL_HOURS,
+            history_file: None,
+            stale_after_runs: None,
+            with_git_history: false,
+            dns_repo_dir: PathBuf::from(DNS_REPO_DIR),
+            metrics_file: None,
+            timestamped_output: false,
+            output_dir: None,
+            format: OutputFormat::Text,
+            skip_private: false,
+            dns_cache_size: DNS_CACHE_SIZE,
+            redirect_allowlist: Vec::new(),
+            proxies_file: None,
+            resolve: Vec::new(),
+            llm: LlmConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Apply overrides from environment variables (e.g. `HTTP_CONCURRENCY=50`).
+    ///
+    /// Unset or unparseable variables leave the current value untouched.
+    pub fn with_env(mut self) -> Self {
+        if let Ok(url) = env::var("DNS_YAML_URL") {
+            self.yaml_url = url;
+        }
+        env_override("MAX_YAML_BYTES", &mut self.max_yaml_bytes);
+        env_override("HTTP_CONCURRENCY", &mut self.http_concurrency);
+        env_override("LLM_CONCURRENCY", &mut self.llm_concurrency);
+        env_override(
+            "LLM_RATE_LIMIT_PER_MINUTE",
+            &mut self.llm_rate_limit_per_minute,
+        );
+        env_override("LLM_RATE_BURST", &mut self.llm_rate_burst);
+        env_override("REQUEST_TIMEOUT_SECS", &mut self.request_timeout_secs);
+        env_override("MAX_BODY_BYTES", &mut self.max_body_bytes);
+        env_override("PROBE_RETRIES", &mut self.probe_retry.max_retries);
+        env_override("PROBE_RETRY_MAX_MS", &mut self.probe_retry.max_delay_ms);
+        env_override("YAML_RETRIES", &mut self.yaml_retry.max_retries);
+        env_override("YAML_RETRY_MAX_MS", &mut self.yaml_retry.max_delay_ms);
+        env_override("LLM_RETRIES", &mut self.llm.retry.max_retries);
+        env_override("LLM_MAX_TOKENS", &mut self.llm.max_tokens);
+        env_override("LLM_TEMPERATURE", &mut self.llm.temperature);
+        if let Some(path) = env_parse("LLM_PROMPT_TEMPLATE_FILE") {
+            self.llm.prompt_template_file = Some(path);
+        }
+        env_override("LLM_RETRY_MAX_MS", &mut self.llm.retry.max_delay_ms);
+        env_override("PROBE_DELAY_MS", &mut self.probe_delay_ms);
+        env_override("RESPECT_ROBOTS", &mut self.respect_robots);
+        env_override("HOST_FAILURE_LIMIT", &mut self.host_failure_limit);
+        env_override(
+            "HOST_RATE_LIMIT_PER_MINUTE",
+            &mut self.host_rate_limit_per_minute,
+        );
+        env_override("DNS_CACHE_SIZE", &mut self.dns_cache_size);
+        env_override("PROBE_CACHE_TTL_HOURS", &mut self.probe_cache_ttl_hours);
+        env_override("DNS_REPO_DIR", &mut self.dns_repo_dir);
+        if let Ok(key) = env::var("NVIDIA_API_KEY") {
+            self.llm.api_key = key;
+        }
+        if let Ok(url) = env::var("NIM_API_URL") {
+            self.llm.api_url = url;
+        }
+        if let Ok(model) = env::var("NIM_MODEL") {
+            self.llm.model = model;
+        }
+        if let Some(chars) = env_parse::<usize>("HTML_TRUNCATE_CHARS") {
+            self.llm.max_input_tokens = chars.div_ceil(CHARS_PER_TOKEN);
+        }
+        env_override("LLM_MAX_INPUT_TOKENS", &mut self.llm.max_input_tokens);
+        env_override("HEAD_MAX_CHARS", &mut self.llm.head_max_chars);
+        if let Ok(raw) = env::var("LLM_EXTRA_PARAMS") {
+            if let Ok(serde_json::Value::Object(params)) = serde_json::from_str(&raw) {
+                self.llm.extra_params.extend(params);
+            }
+        }
+        if let Some(tokens) = env_parse("CONTEXT_WINDOW_TOKENS") {
+            self.llm.context_window_tokens = Some(tokens);
+        }
+        self
+    }
+
+    /// Each zone to scan with the URL of its DNS YAML.
+    ///
+    /// With `zones` set, `hackclub.io` is read from `hackclub.io.yaml` in the same
+    /// directory as `yaml_url`; otherwise `yaml_url` is the only zone.
+    pub fn zone_urls(&self) -> Vec<(String, String)> {
+        if self.zones.is_empty() {
+            return vec![(zone_name(&self.yaml_url).to_string(), self.yaml_url.clone())];
+        }
+        let dir = self.yaml_url.rsplit_once('/').map_or("", |(dir, _)| dir);
+        self.zones
+            .iter()
+            .map(|zone| (zone.clone(), format!("{}/{}.yaml", dir, zone)))
+            .collect()
+    }
+
+    /// The configured zone `url`'s host belongs to, falling back to the first one.
+    pub fn zone_of(&self, url: &str) -> String {
+        let zones = self.zone_urls();
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+            .unwrap_or_default();
+        zones
+            .iter()
+            .map(|(zone, _)| zone)
+            .find(|zone| host == **zone || host.ends_with(&format!(".{}", zone)))
+            .unwrap_or(&zones[0].0)
+            .clone()
+    }
+}
+
+/// Named bundles of scan-speed settings for users who don't want to tune each one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    /// High concurrency, few retries, short timeouts.
+    Aggressive,
+    /// The built-in defaults.
+    Balanced,
+    /// Low concurrency, one request a second per host, and `robots.txt` respected.
+    Polite,
+}
+
+impl Preset {
+    /// The settings this preset changes.
+    pub fn partial(self) -> PartialConfig {
+        match self {
+            Preset::Aggressive => PartialConfig {
+                http_concurrency: Some(64),
+                probe_delay_ms: Some(0),
+                probe_retries: Some(1),
+                request_timeout_secs: Some(10),
+                host_rate_limit_per_minute: Some(0),
+                respect_robots: Some(false),
+            },
+            Preset::Balanced => PartialConfig {
+                http_concurrency: Some(HTTP_CONCURRENCY),
+                probe_delay_ms: Some(0),
+                probe_retries: Some(PROBE_RETRIES),
+                request_timeout_secs: Some(REQUEST_TIMEOUT_SECS),
+                host_rate_limit_per_minute: Some(HOST_RATE_LIMIT_PER_MINUTE),
+                respect_robots: Some(false),
+            },
+            Preset::Polite => PartialConfig {
+                http_concurrency: Some(5),
+                probe_delay_ms: Some(1000),
+                probe_retries: Some(PROBE_RETRIES),
+                request_timeout_secs: Some(20),
+                host_rate_limit_per_minute: Some(60),
+                respect_robots: Some(true),
+            },
+        }
+    }
+
+    /// Apply this preset to `config` and record it.
+    pub fn apply(self, config: &mut Config) {
+        self.partial().apply(config);
+        config.preset = Some(self);
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "aggressive" => Ok(Preset::Aggressive),
+            "balanced" => Ok(Preset::Balanced),
+            "polite" => Ok(Preset::Polite),
+            _ => Err(format!(
+                "unknown preset '{}' (expected aggressive, balanced, or polite)",
+                s
+            )),
+        }
+    }
+}
+
+/// A subset of `Config` fields; `None` leaves the existing value alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartialConfig {
+    pub http_concurrency: Option<usize>,
+    pub probe_delay_ms: Option<u64>,
+    pub probe_retries: Option<u32>,
+    pub request_timeout_secs: Option<u64>,
+    pub host_rate_limit_per_minute: Option<u32>,
+    pub respect_robots: Option<bool>,
+}
+
+impl PartialConfig {
+    /// Overwrite the fields of `config` that are set here.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(v) = self.http_concurrency {
+            config.http_concurrency = v;
+        }
+        if let Some(v) = self.probe_delay_ms {
+            config.probe_delay_ms = v;
+        }
+        if let Some(v) = self.probe_retries {
+            config.probe_retry.max_retries = v;
+        }
+        if let Some(v) = self.request_timeout_secs {
+            config.request_timeout_secs = v;
+        }
+        if let Some(v) = self.host_rate_limit_per_minute {
+            config.host_rate_limit_per_minute = v;
+        }
+        if let Some(v) = self.respect_robots {
+            config.respect_robots = v;
+        }
+    }
+}
+
+/// Settings for the chat-completions request made per page.
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmConfig {
+    /// API key sent as a bearer token (from `NVIDIA_API_KEY`; never serialized).
+    #[serde(skip)]
+    pub api_key: String,
+    /// Chat completions endpoint.
+    pub api_url: String,
+    /// Model identifier.
+    pub model: String,
+    /// Maximum tokens in the response.
+    pub max_tokens: u32,
+    /// Sampling temperature.
+    pub temperature: f32,
+    /// File holding a custom prompt template (`--prompt-template`), read into
+    /// `prompt_template` by [`LlmConfig::load_prompt_template`].
+    pub prompt_template_file: Option<PathBuf>,
+    /// Prompt sent for each page, with `{url}` and `{html}` placeholders; `None`
+    /// uses [`crate::llm::DEFAULT_PROMPT_TEMPLATE`].
+    #[serde(skip)]
+    pub prompt_template: Option<String>,
+    /// The model's context window. When set, the page-content budget is derived
+    /// from it instead of `max_input_tokens`.
+    pub context_window_tokens: Option<usize>,
+    /// Estimated tokens of page content sent when no context window is set; 0
+    /// disables truncation (up to `UNTRUNCATED_MAX_TOKENS`).
+    pub max_input_tokens: usize,
+    /// Characters of `<head>` always kept when truncating (title, meta, JSON-LD).
+    pub head_max_chars: usize,
+    /// Strip scripts, styles, and navigation from pages before truncating them,
+    /// so the budget goes to their content (off with `--raw-html`).
+    pub strip_boilerplate: bool,
+    /// Extra request-body fields such as `top_p` or `frequency_penalty`. They never
+    /// replace fields the request already sets (`model`, `messages`, ...).
+    pub extra_params: serde_json::Map<String, serde_json::Value>,
+    /// Retry behaviour for transient request failures (not context-length errors,
+    /// which shrink the content instead).
+    pub retry: RetryPolicy,
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        LlmConfig {
+            api_key: String::new(),
+            api_url: NIM_API_URL.to_string(),
+            model: NIM_MODEL.to_string(),
+            max_tokens: LLM_MAX_TOKENS,
+            temperature: LLM_TEMPERATURE,
+            prompt_template_file: None,
+            prompt_template: None,
+            context_window_tokens: None,
+            max_input_tokens: LLM_MAX_INPUT_TOKENS,
+            head_max_chars: HEAD_MAX_CHARS,
+            strip_boilerplate: true,
+            extra_params: serde_json::Map::new(),
+            retry: RetryPolicy {
+                max_retries: LLM_RETRIES,
+                base_delay_ms: LLM_RETRY_BASE_MS,
+                max_delay_ms: LLM_RETRY_MAX_MS,
+                retry_statuses: BTreeSet::from(SERVER_RETRY_STATUSES),
+            },
+        }
+    }
+}
+
+impl LlmConfig {
+    /// Read `prompt_template_file`, if set, into `prompt_template`, and check
+    /// that the template in use has both placeholders.
+    ///
+    /// # Returns
+    /// `Err` describing an unreadable file or a missing placeholder
+    pub fn load_prompt_template(&mut self) -> Result<(), String> {
+        if let Some(path) = &self.prompt_template_file {
+            let template = std::fs::read_to_string(path)
+                .map_err(|e| format!("can't read prompt template {}: {}", path.display(), e))?;
+            crate::llm::validate_prompt_template(&template)
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            self.prompt_template = Some(template);
+        } else if let Some(template) = &self.prompt_template {
+            crate::llm::validate_prompt_template(template)?;
+        }
+        Ok(())
+    }
+
+    /// Estimated tokens of page content to send: what the context window leaves
+    /// once the response and prompt are accounted for, or `max_input_tokens`
+    /// when no window is configured.
+    pub fn content_budget_tokens(&self) -> usize {
+        match self.context_window_tokens {
+            Some(context) => {
+                context.saturating_sub(self.max_tokens as usize + PROMPT_OVERHEAD_TOKENS)
+            }
+            None if self.max_input_tokens == 0 => UNTRUNCATED_MAX_TOKENS,
+            None => self.max_input_tokens,
+        }
+    }
+}
+
+fn env_override<T: FromStr>(name: &str, slot: &mut T) {
+    if let Some(v) = env_parse(name) {
+        *slot = v;
+    }
+}
+
+fn env_parse<T: FromStr>(name: &str) -> Option<T> {
+    env::var(name).ok().and_then(|v| v.parse().ok())
+}
+
+/// Terminal rendering of the hackathon summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// One block per hackathon with every field in full.
+    Text,
+    /// Compact aligned columns (name, dates, truncated URL).
+    Table,
+    /// Full-detail text, plus the dated events written to `hackathons.ics`.
+    Ics,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "table" => Ok(OutputFormat::Table),
+            "ics" => Ok(OutputFormat::Ics),
+            _ => Err(format!(
+                "unknown format '{}' (expected text, table, or ics)",
+                s
+            )),
+        }
+    }
+}
+
+/// A curl-style DNS override pinning a host to a specific address.
+///
+/// Parsed from `host:ip` or `host:port:ip`; the port, if given, is ignored because
+/// the URL's own port always wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolveOverride {
+    pub host: String,
+    pub addr: IpAddr,
+}
+
+impl FromStr for ResolveOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (host, rest) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected host:ip, got '{}'", s))?;
+        let addr = rest
+            .parse()
+            .or_else(|_| match rest.split_once(':') {
+                Some((port, ip)) if port.parse::<u16>().is_ok() => ip.parse(),
+                _ => rest.parse(),
+            })
+            .map_err(|_| format!("invalid address in '{}'", s))?;
+
+        if host.is_empty() {
+            return Err(format!("missing host in '{}'", s));
+        }
+        Ok(ResolveOverride {
+            host: host.to_string(),
+            addr,
+        })
+    }
+}
+
+impl fmt::Display for ResolveOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.host, self.addr)
+    }
+}
+
+impl Serialize for ResolveOverride {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// The fields a preset sets, read back from a config it was applied to.
+    fn preset_values(preset: Preset) -> (usize, u64, u32, u64, u32, bool) {
+        let mut config = Config::default();
+        preset.apply(&mut config);
+        assert_eq!(config.preset, Some(preset));
+        (
+            config.http_concurrency,
+            config.probe_delay_ms,
+            config.probe_retry.max_retries,
+            config.request_timeout_secs,
+            config.host_rate_limit_per_minute,
+            config.respect_robots,
+        )
+    }
+
+    #[test]
+    fn presets_yield_their_documented_values() {
+        assert_eq!(preset_values(Preset::Aggressive), (64, 0, 1, 10, 0, false));
+        assert_eq!(
+            preset_values(Preset::Balanced),
+            (
+                HTTP_CONCURRENCY,
+                0,
+                PROBE_RETRIES,
+                REQUEST_TIMEOUT_SECS,
+                HOST_RATE_LIMIT_PER_MINUTE,
+                false
+            )
+        );
+        assert_eq!(
+            preset_values(Preset::Polite),
+            (5, 1000, PROBE_RETRIES, 20, 60, true)
+        );
+    }
+
+    #[test]
+    fn each_endpoint_has_its_own_retry_budget_and_cap() {
+        let config = Config::default();
+        let policies = [
+            (&config.yaml_retry, YAML_RETRIES, YAML_RETRY_MAX_MS),
+            (&config.probe_retry, PROBE_RETRIES, PROBE_RETRY_MAX_MS),
+            (&config.llm.retry, LLM_RETRIES, LLM_RETRY_MAX_MS),
+        ];
+        for (policy, retries, cap_ms) in policies {
+            assert_eq!(policy.max_retries, retries);
+            assert_eq!(policy.max_delay_ms, cap_ms);
+            // However many retries in, the wait never passes the endpoint's cap.
+            assert_eq!(policy.delay(20), Duration::from_millis(cap_ms));
+        }
+        assert!(config.llm.retry.max_retries < config.probe_retry.max_retries);
+    }
+
+    #[test]
+    fn empty_partial_config_changes_nothing() {
+        let mut config = Config {
+            http_concurrency: 3,
+            ..Config::default()
+        };
+        PartialConfig::default().apply(&mut config);
+        assert_eq!(config.http_concurrency, 3);
+    }
+
+    #[test]
+    fn resolve_overrides_parse_with_and_without_a_port() {
+        let plain: ResolveOverride = "foo.hackclub.com:127.0.0.1".parse().unwrap();
+        assert_eq!(plain.host, "foo.hackclub.com");
+        assert_eq!(plain.addr, "127.0.0.1".parse::<IpAddr>().unwrap());
+
+        let curl: ResolveOverride = "foo.hackclub.com:443:10.0.0.2".parse().unwrap();
+        assert_eq!(curl.addr, "10.0.0.2".parse::<IpAddr>().unwrap());
+
+        let v6: ResolveOverride = "foo.hackclub.com:::1".parse().unwrap();
+        assert_eq!(v6.addr, "::1".parse::<IpAddr>().unwrap());
+
+        assert!("foo.hackclub.com".parse::<ResolveOverride>().is_err());
+        assert!(":127.0.0.1".parse::<ResolveOverride>().is_err());
+        assert!("foo:not-an-ip".parse::<ResolveOverride>().is_err());
+    }
+
+    #[test]
+    fn smaller_context_window_gives_a_smaller_content_budget() {
+        let budget = |context| {
+            LlmConfig {
+                context_window_tokens: Some(context),
+                ..LlmConfig::default()
+            }
+            .content_budget_tokens()
+        };
+
+        assert!(budget(8_000) < budget(128_000));
+        assert_eq!(
+            budget(8_000),
+            8_000 - LLM_MAX_TOKENS as usize - PROMPT_OVERHEAD_TOKENS
+        );
+        // A window smaller than the reserved space leaves nothing for content.
+        assert_eq!(budget(1_000), 0);
+    }
+
+    #[test]
+    fn content_budget_without_a_context_window() {
+        let mut llm = LlmConfig::default();
+        assert_eq!(llm.content_budget_tokens(), LLM_MAX_INPUT_TOKENS);
+        llm.max_input_tokens = 0;
+        assert_eq!(llm.content_budget_tokens(), UNTRUNCATED_MAX_TOKENS);
+    }
+}
@@ -0,0 +1,189 @@
+//! Parsing of the free-form date strings the LLM returns.
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::types::Hackathon;
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Parse a date or date range such as `"March 15–17, 2025"`, `"Mar 30 - Apr 2 2025"`,
+/// `"15 March 2025"`, or `"2025-03-15 – 2025-03-17"`.
+///
+/// Returns `(start, end)`; a single date yields the same value for both. Only the
+/// first two dates form the range, so a trailing note like "(apply by Feb 1, 2025)"
+/// is ignored. Strings without a recognizable day, month, and year (e.g.
+/// `"Unknown"`, `"Spring 2025"`) yield `(None, None)`.
+pub fn parse_date_range(text: &str) -> (Option<NaiveDate>, Option<NaiveDate>) {
+    let iso = iso_dates(text);
+    // Whether the first two dates were completed by one written year, as in
+    // "Dec 30 – Jan 2, 2026"; ISO dates always carry their own.
+    let (dates, shared_year) = if iso.is_empty() {
+        let groups = written_dates(text);
+        let shared = groups.first().is_some_and(|g| g.len() > 1);
+        (groups.concat(), shared)
+    } else {
+        (iso, false)
+    };
+    match dates.as_slice() {
+        [] => (None, None),
+        [start, end, ..] if end >= start => (Some(*start), Some(*end)),
+        // "Dec 30 – Jan 2, 2026": the trailing year belongs to the end date.
+        [start, end, ..] if shared_year && end.month() < start.month() => {
+            (start.with_year(start.year() - 1), Some(*end))
+        }
+        [start, ..] => (Some(*start), Some(*start)),
+    }
+}
+
+/// Set a hackathon's `start_date`/`end_date` from its free-form `dates` string.
+///
+/// Strings that don't parse (e.g. `"Unknown"`) leave both `None`; `dates` itself is
+/// kept as written for display.
+pub fn fill_dates(h: &mut Hackathon) {
+    (h.start_date, h.end_date) = parse_date_range(&h.dates);
+}
+
+/// All `YYYY-MM-DD` dates in `text`, in order.
+fn iso_dates(text: &str) -> Vec<NaiveDate> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '-'))
+        .flat_map(|chunk| {
+            // A chunk like "2025-03-15-2025-03-17" holds two dates.
+            let parts: Vec<&str> = chunk.split('-').collect();
+            parts
+                .chunks(3)
+                .filter_map(|p| match p {
+                    [y, m, d] if y.len() == 4 => {
+                        NaiveDate::from_ymd_opt(y.parse().ok()?, m.parse().ok()?, d.parse().ok()?)
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Dates written with month names, grouped by the year that completes them, e.g.
+/// "March 15–17, 2025 (apply by Feb 1, 2025)" → [[Mar 15, Mar 17], [Feb 1]].
+fn written_dates(text: &str) -> Vec<Vec<NaiveDate>> {
+    let mut month: Option<u32> = None;
+    // Days seen before any month ("15–17 March 2025").
+    let mut loose_days: Vec<u32> = Vec::new();
+    // (month, day) pairs waiting for a year.
+    let mut pending: Vec<(u32, u32)> = Vec::new();
+    let mut dates = Vec::new();
+
+    for token in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+    {
+        let lower = token.to_lowercase();
+        if let Some(m) = month_number(&lower) {
+            month = Some(m);
+            pending.extend(loose_days.drain(..).map(|d| (m, d)));
+            continue;
+        }
+
+        let digits = lower.trim_end_matches(['s', 't', 'n', 'd', 'r', 'h']);
+        let Ok(n) = digits.parse::<u32>() else {
+            continue;
+        };
+        match (digits.len(), month) {
+            (4, _) => {
+                let year = n as i32;
+                let group: Vec<NaiveDate> = pending
+                    .drain(..)
+                    .filter_map(|(m, d)| NaiveDate::from_ymd_opt(year, m, d))
+                    .collect();
+                if !group.is_empty() {
+                    dates.push(group);
+                }
+            }
+            (1 | 2, Some(m)) => pending.push((m, n)),
+            (1 | 2, None) => loose_days.push(n),
+            _ => {}
+        }
+    }
+
+    dates
+}
+
+/// Month number for a full or abbreviated (at least three letters) month name.
+fn month_number(word: &str) -> Option<u32> {
+    if word.len() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|m| m.starts_with(word))
+        .map(|i| i as u32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    #[test]
+    fn documented_forms_parse_to_their_ranges() {
+        let cases = [
+            ("March 15–17, 2025", ymd(2025, 3, 15), ymd(2025, 3, 17)),
+            ("Mar 30 - Apr 2 2025", ymd(2025, 3, 30), ymd(2025, 4, 2)),
+            ("15 March 2025", ymd(2025, 3, 15), ymd(2025, 3, 15)),
+            ("15–17 March 2025", ymd(2025, 3, 15), ymd(2025, 3, 17)),
+            (
+                "2025-03-15 – 2025-03-17",
+                ymd(2025, 3, 15),
+                ymd(2025, 3, 17),
+            ),
+            ("2025-03-15", ymd(2025, 3, 15), ymd(2025, 3, 15)),
+            ("Dec 30 – Jan 2, 2026", ymd(2025, 12, 30), ymd(2026, 1, 2)),
+            (
+                "Dec 30, 2025 – Jan 2, 2026",
+                ymd(2025, 12, 30),
+                ymd(2026, 1, 2),
+            ),
+        ];
+        for (text, start, end) in cases {
+            assert_eq!(parse_date_range(text), (start, end), "{}", text);
+        }
+    }
+
+    #[test]
+    fn vague_dates_do_not_parse() {
+        for text in ["Unknown", "Spring 2025", "", "March 2025", "TBA"] {
+            assert_eq!(parse_date_range(text), (None, None), "{}", text);
+        }
+    }
+
+    #[test]
+    fn a_trailing_unrelated_date_is_not_part_of_the_range() {
+        assert_eq!(
+            parse_date_range("March 15–17, 2025 (apply by Feb 1, 2025)"),
+            (ymd(2025, 3, 15), ymd(2025, 3, 17))
+        );
+        assert_eq!(
+            parse_date_range("March 15, 2025 (apply by Feb 1, 2025)"),
+            (ymd(2025, 3, 15), ymd(2025, 3, 15))
+        );
+        assert_eq!(
+            parse_date_range("2025-03-15 (deadline 2025-02-01)"),
+            (ymd(2025, 3, 15), ymd(2025, 3, 15))
+        );
+    }
+}
@@ -0,0 +1,463 @@
+//! Merging of duplicate hackathon entries.
+
+use std::collections::HashSet;
+
+use reqwest::Url;
+
+use crate::dates::parse_date_range;
+use crate::probe::normalize_url;
+use crate::types::{EventFormat, Hackathon};
+
+/// Minimum word overlap (Jaccard index) for two names to count as the same event.
+const NAME_SIMILARITY_THRESHOLD: f64 = 0.6;
+
+/// Merge entries from a single page that describe the same event.
+///
+/// The LLM occasionally splits one event into several entries (e.g. one carrying
+/// the name, another the dates). Entries are merged when their names are similar
+/// or when they share the same known date string.
+///
+/// # Arguments
+/// * `page_url` - URL of the page the entries came from (used to prefer more specific links)
+/// * `entries` - Hackathons extracted from that page
+pub fn merge_page_entries(page_url: &str, entries: Vec<Hackathon>) -> Vec<Hackathon> {
+    let mut merged: Vec<Hackathon> = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        match merged.iter_mut().find(|m| same_event(m, &entry)) {
+            Some(existing) => combine(existing, entry, page_url),
+            None => merged.push(entry),
+        }
+    }
+
+    merged
+}
+
+/// Collapse same-name entries with different dates into one recurring series.
+///
+/// Pages for recurring events often list each occurrence separately. Entries whose
+/// names match once normalized are merged, and their dates become one range from
+/// the earliest start to the latest end (or a `; `-joined list when the dates
+/// can't be parsed). Meant to run on a single page's entries before
+/// [`merge_page_entries`].
+pub fn merge_series(entries: Vec<Hackathon>) -> Vec<Hackathon> {
+    let mut groups: Vec<Vec<Hackathon>> = Vec::new();
+    for entry in entries {
+        let key = normalize_name(&entry.name);
+        match groups
+            .iter_mut()
+            .find(|g| !key.is_empty() && normalize_name(&g[0].name) == key)
+        {
+            Some(group) => group.push(entry),
+            None => groups.push(vec![entry]),
+        }
+    }
+
+    groups.into_iter().map(collapse_series).collect()
+}
+
+/// Merge one group of same-name entries, combining their dates.
+fn collapse_series(mut group: Vec<Hackathon>) -> Hackathon {
+    let mut dates: Vec<String> = Vec::new();
+    for h in &group {
+        if known_dates(&h.dates) && !dates.contains(&h.dates) {
+            dates.push(h.dates.clone());
+        }
+    }
+
+    let mut first = group.remove(0);
+    if dates.len() < 2 {
+        return first;
+    }
+
+    let ranges: Vec<_> = dates.iter().map(|d| parse_date_range(d)).collect();
+    let start = ranges.iter().filter_map(|r| r.0).min();
+    let end = ranges.iter().filter_map(|r| r.1).max();
+    first.dates = match (start, end) {
+        (Some(s), Some(e)) if ranges.iter().all(|r| r.0.is_some()) => {
+            format!("{} – {}", s.format("%B %-d, %Y"), e.format("%B %-d, %Y"))
+        }
+        _ => dates.join("; "),
+    };
+    for other in group {
+        first.register_url = first.register_url.take().or(other.register_url);
+        first.prizes = first.prizes.take().or(other.prizes);
+        if other.summary.len() > first.summary.len() {
+            first.summary = other.summary;
+        }
+    }
+    first
+}
+
+/// Merge entries from any page that point at the same event URL.
+///
+/// Catches duplicates whose names differ too much for [`merge_page_entries`], e.g.
+/// two subdomains both linking to one event site. URLs are compared by host
+/// (ignoring `www.`) and path. Entries whose URL is one of the scanned `pages`
+/// are left alone: that URL is just where they were found (see
+/// [`crate::llm::normalize_links`]), so distinct events on a hub page share it.
+pub fn merge_by_url(entries: Vec<Hackathon>, pages: &HashSet<String>) -> Vec<Hackathon> {
+    let mut merged: Vec<Hackathon> = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        let key = canonical_url(&entry.url).filter(|_| !pages.contains(&normalize_url(&entry.url)));
+        let existing = key.as_ref().and_then(|k| {
+            merged
+                .iter_mut()
+                .find(|m| canonical_url(&m.url).as_ref() == Some(k))
+        });
+        match existing {
+            Some(existing) => {
+                let url = existing.url.clone();
+                combine(existing, entry, &url);
+            }
+            None => merged.push(entry),
+        }
+    }
+
+    merged
+}
+
+/// Merge entries from different pages that name the same event on overlapping dates.
+///
+/// Several subdomains sometimes advertise one event. Names must match once
+/// normalized (case, punctuation, and whitespace ignored), so "HackFoo 2025" and
+/// "hackfoo 2025" merge while events that merely share a word do not. Parsed date
+/// ranges must overlap; dates that don't parse must be written the same. The
+/// merged entry keeps the most specific URL (see [`url_specificity`]).
+pub fn dedupe_hackathons(entries: Vec<Hackathon>) -> Vec<Hackathon> {
+    let mut merged: Vec<Hackathon> = Vec::with_capacity(entries.len());
+
+    for entry in entries {
+        let key = normalize_name(&entry.name);
+        let existing = merged.iter_mut().find(|m| {
+            !key.is_empty()
+                && normalize_name(&m.name) == key
+                && dates_overlap(&m.dates, &entry.dates)
+        });
+        match existing {
+            Some(existing) => {
+                let url = if url_specificity(&entry.url) > url_specificity(&existing.url) {
+                    entry.url.clone()
+                } else {
+                    existing.url.clone()
+                };
+                let current = existing.url.clone();
+                combine(existing, entry, &current);
+                existing.url = url;
+            }
+            None => merged.push(entry),
+        }
+    }
+
+    merged
+}
+
+/// Whether two date strings could describe the same occurrence.
+fn dates_overlap(a: &str, b: &str) -> bool {
+    match (parse_date_range(a), parse_date_range(b)) {
+        ((Some(sa), Some(ea)), (Some(sb), Some(eb))) => sa <= eb && sb <= ea,
+        _ => a.trim().eq_ignore_ascii_case(b.trim()),
+    }
+}
+
+/// How specific a URL is: its number of path segments, then its length. A
+/// subdomain's home page ranks below a dedicated event or registration page.
+fn url_specificity(url: &str) -> (usize, usize) {
+    let segments = Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            u.path_segments()
+                .map(|s| s.filter(|s| !s.is_empty()).count())
+        })
+        .unwrap_or(0);
+    (segments, url.len())
+}
+
+/// `https://www.Example.com/event/` → `example.com/event`; `None` if unparseable.
+fn canonical_url(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    Some(format!("{}{}", host, parsed.path().trim_end_matches('/')))
+}
+
+fn same_event(a: &Hackathon, b: &Hackathon) -> bool {
+    names_similar(&a.name, &b.name) || (known_dates(&a.dates) && a.dates.trim() == b.dates.trim())
+}
+
+/// Whether two names refer to the same event: equal once normalized, one
+/// containing the other, or sharing most of their words.
+pub(crate) fn names_similar(a: &str, b: &str) -> bool {
+    let (na, nb) = (normalize_name(a), normalize_name(b));
+    if na.is_empty() || nb.is_empty() {
+        return false;
+    }
+    if na == nb || na.contains(&nb) || nb.contains(&na) {
+        return true;
+    }
+
+    let wa: HashSet<String> = words(a).collect();
+    let wb: HashSet<String> = words(b).collect();
+    let shared = wa.intersection(&wb).count() as f64;
+    let total = wa.union(&wb).count() as f64;
+    total > 0.0 && shared / total >= NAME_SIMILARITY_THRESHOLD
+}
+
+/// Lowercase a name and drop everything but letters and digits.
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn words(name: &str) -> impl Iterator<Item = String> + '_ {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn known_dates(dates: &str) -> bool {
+    let d = dates.trim();
+    !d.is_empty() && !d.eq_ignore_ascii_case("unknown")
+}
+
+/// Fold `other` into `into`, keeping the most informative value of each field.
+fn combine(into: &mut Hackathon, other: Hackathon, page_url: &str) {
+    if other.name.len() > into.name.len() {
+        into.name = other.name;
+    }
+    if into.url == page_url && other.url != page_url && !other.url.is_empty() {
+        into.url = other.url;
+    }
+    if !known_dates(&into.dates) && known_dates(&other.dates) {
+        into.dates = other.dates;
+    }
+    if other.summary.len() > into.summary.len() {
+        into.summary = other.summary;
+    }
+    if into.format == EventFormat::Unknown {
+        into.format = other.format;
+    }
+    into.register_url = into.register_url.take().or(other.register_url);
+    into.prizes = into.prizes.take().or(other.prizes);
+    into.organizer = into.organizer.take().or(other.organizer);
+    if into.source.is_empty() {
+        into.source = other.source;
+    }
+    if other.confidence > into.confidence {
+        into.confidence = other.confidence;
+        into.extracted_by = other.extracted_by;
+    }
+    let found_via = other.subdomain.into_iter().chain(other.also_from);
+    for subdomain in found_via {
+        if into.subdomain.is_none() {
+            into.subdomain = Some(subdomain);
+        } else if into.subdomain.as_deref() != Some(subdomain.as_str())
+            && !into.also_from.contains(&subdomain)
+        {
+            into.also_from.push(subdomain);
+        }
+    }
+    for contact in other.contacts {
+        if !into.contacts.contains(&contact) {
+            into.contacts.push(contact);
+        }
+    }
+    into.source_snippet = into.source_snippet.take().or(other.source_snippet);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "http://foo.hackclub.com/";
+
+    fn entry(name: &str, url: &str, dates: &str) -> Hackathon {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "url": url,
+            "dates": dates,
+            "summary": "",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn names_differing_in_case_and_punctuation_merge_keeping_the_specific_url() {
+        let merged = dedupe_hackathons(vec![
+            entry(
+                "HackFoo 2025",
+                "http://foo.hackclub.com/",
+                "March 1–2, 2025",
+            ),
+            entry(
+                "hackfoo 2025",
+                "http://events.hackclub.com/hackfoo/2025",
+                "2025-03-02",
+            ),
+            entry("HACKFOO-2025", "http://bar.hackclub.com/", "March 1, 2025"),
+        ]);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].name, "HackFoo 2025");
+        assert_eq!(merged[0].url, "http://events.hackclub.com/hackfoo/2025");
+    }
+
+    #[test]
+    fn events_sharing_a_word_or_a_name_on_other_dates_stay_apart() {
+        let merged = dedupe_hackathons(vec![
+            entry(
+                "Winter Hackathon",
+                "http://a.hackclub.com/",
+                "March 1, 2025",
+            ),
+            entry(
+                "Summer Hackathon",
+                "http://b.hackclub.com/",
+                "March 1, 2025",
+            ),
+            entry("HackFoo", "http://c.hackclub.com/", "March 1, 2025"),
+            entry("HackFoo", "http://d.hackclub.com/", "March 1, 2026"),
+        ]);
+
+        let names: Vec<&str> = merged.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Winter Hackathon", "Summer Hackathon", "HackFoo", "HackFoo"]
+        );
+    }
+
+    #[test]
+    fn split_entries_on_one_page_merge_with_combined_fields() {
+        let named = Hackathon {
+            summary: "A weekend of building.".to_string(),
+            ..entry("HackFoo 2026", PAGE, "Unknown")
+        };
+        let dated = Hackathon {
+            prizes: Some("$1,000".to_string()),
+            ..entry("Hackfoo", "http://foo.hackclub.com/2026", "March 1–2, 2026")
+        };
+        let other = entry("Game Jam", PAGE, "May 5, 2026");
+
+        let merged = merge_page_entries(PAGE, vec![named, dated, other]);
+
+        assert_eq!(merged.len(), 2);
+        let foo = &merged[0];
+        assert_eq!(foo.name, "HackFoo 2026");
+        assert_eq!(foo.url, "http://foo.hackclub.com/2026");
+        assert_eq!(foo.dates, "March 1–2, 2026");
+        assert_eq!(foo.summary, "A weekend of building.");
+        assert_eq!(foo.prizes.as_deref(), Some("$1,000"));
+        assert_eq!(merged[1].name, "Game Jam");
+    }
+
+    #[test]
+    fn entries_sharing_known_dates_merge_but_unknown_dates_do_not() {
+        let same_dates = merge_page_entries(
+            PAGE,
+            vec![
+                entry("Spring Build", PAGE, "April 4, 2026"),
+                entry("Build Night", PAGE, "April 4, 2026"),
+            ],
+        );
+        assert_eq!(same_dates.len(), 1);
+
+        let unknown = merge_page_entries(
+            PAGE,
+            vec![
+                entry("Spring Build", PAGE, "Unknown"),
+                entry("Game Jam", PAGE, "Unknown"),
+            ],
+        );
+        assert_eq!(unknown.len(), 2);
+    }
+
+    #[test]
+    fn same_name_entries_on_consecutive_dates_become_one_series() {
+        let series = merge_series(vec![
+            entry("Hack Night", PAGE, "March 1, 2026"),
+            Hackathon {
+                register_url: Some("https://foo.hackclub.com/join".to_string()),
+                ..entry("hack night", PAGE, "March 2, 2026")
+            },
+            entry("Hack Night!", PAGE, "March 3, 2026"),
+            entry("Game Jam", PAGE, "March 2, 2026"),
+        ]);
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].name, "Hack Night");
+        assert_eq!(series[0].dates, "March 1, 2026 – March 3, 2026");
+        assert_eq!(
+            series[0].register_url.as_deref(),
+            Some("https://foo.hackclub.com/join")
+        );
+        assert_eq!(series[1].name, "Game Jam");
+        assert_eq!(series[1].dates, "March 2, 2026");
+    }
+
+    #[test]
+    fn unparseable_series_dates_are_listed() {
+        let series = merge_series(vec![
+            entry("Hack Night", PAGE, "every other Friday"),
+            entry("Hack Night", PAGE, "Unknown"),
+            entry("Hack Night", PAGE, "first Saturday"),
+        ]);
+
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].dates, "every other Friday; first Saturday");
+    }
+
+    #[test]
+    fn differently_named_entries_sharing_a_canonical_url_merge() {
+        let pages: HashSet<String> = ["http://foo.hackclub.com", "http://bar.hackclub.com"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let from_foo = Hackathon {
+            summary: "Build games over a weekend in Boston.".to_string(),
+            ..entry(
+                "Boston Game Jam",
+                "https://www.jam.example/2026/",
+                "Unknown",
+            )
+        };
+        let from_bar = Hackathon {
+            register_url: Some("https://jam.example/apply".to_string()),
+            ..entry("HC Jam", "https://JAM.example/2026", "April 4–5, 2026")
+        };
+        let elsewhere = entry("Other", "https://jam.example/2025", "Unknown");
+
+        let merged = merge_by_url(vec![from_foo, from_bar, elsewhere], &pages);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].name, "Boston Game Jam");
+        assert_eq!(
+            canonical_url(&merged[0].url).as_deref(),
+            Some("jam.example/2026")
+        );
+        assert_eq!(merged[0].dates, "April 4–5, 2026");
+        assert_eq!(merged[0].summary, "Build games over a weekend in Boston.");
+        assert_eq!(
+            merged[0].register_url.as_deref(),
+            Some("https://jam.example/apply")
+        );
+        assert_eq!(merged[1].name, "Other");
+    }
+
+    #[test]
+    fn entries_pointing_at_a_scanned_page_are_not_merged_by_url() {
+        let pages: HashSet<String> = [normalize_url(PAGE)].into_iter().collect();
+
+        let merged = merge_by_url(
+            vec![
+                entry("Hack Night", PAGE, "Unknown"),
+                entry("Game Jam", PAGE, "Unknown"),
+            ],
+            &pages,
+        );
+
+        assert_eq!(merged.len(), 2);
+    }
+}
@@ -0,0 +1,381 @@
+//! Fetching the HackClub DNS zone file.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::{Client, Response, StatusCode};
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use tokio::fs;
+
+use crate::changes::fingerprint;
+use crate::error::RadarError;
+use crate::retry::{send_with_retries, RetryPolicy};
+
+/// Download the DNS YAML, refusing bodies larger than `max_bytes`.
+///
+/// The size is checked against `Content-Length` up front and enforced again while
+/// streaming, so a huge or mislabelled response is rejected without buffering it.
+///
+/// # Arguments
+/// * `client` - HTTP client for the request
+/// * `url` - URL of the zone YAML
+/// * `max_bytes` - Largest body accepted
+/// * `retry` - Retries for connection errors and transient statuses
+///
+/// # Returns
+/// The YAML text, or an error if the request fails, the server answers with a
+/// non-2xx status, or the body is too large
+pub async fn fetch_zone(
+    client: &Client,
+    url: &str,
+    max_bytes: usize,
+    retry: &RetryPolicy,
+) -> Result<String, RadarError> {
+    let resp = send_with_retries(retry, || client.get(url)).await?;
+    let resp = require_success(resp, url)?;
+    read_capped(resp, url, max_bytes).await
+}
+
+/// How [`fetch_zone_cached`] obtained the YAML.
+#[derive(Debug)]
+pub enum ZoneSource {
+    /// Downloaded; the cache was refreshed.
+    Fresh,
+    /// The server answered 304 Not Modified, so the cached copy was used.
+    NotModified,
+    /// The fetch failed and the cached copy was used instead.
+    Stale(String),
+}
+
+/// Download the DNS YAML with a conditional request, keeping a local copy.
+///
+/// The last good YAML is stored at `cache` and its `ETag` next to it
+/// (`<cache>.etag`). When both exist the request carries `If-None-Match`; a
+/// `304` reuses the cached copy, as does a network error or any other non-2xx
+/// response, so a GitHub outage doesn't stop the run. Only a 2xx body is ever
+/// written to the cache.
+///
+/// # Arguments
+/// * `client` - HTTP client for the request
+/// * `url` - URL of the zone YAML
+/// * `max_bytes` - Largest body accepted
+/// * `cache` - Path of the local copy
+/// * `retry` - Retries tried before falling back to the cached copy
+///
+/// # Returns
+/// The YAML text and where it came from, or an error if the fetch failed or
+/// answered non-2xx with nothing cached
+pub async fn fetch_zone_cached(
+    client: &Client,
+    url: &str,
+    max_bytes: usize,
+    cache: &Path,
+    retry: &RetryPolicy,
+) -> Result<(String, ZoneSource), RadarError> {
+    let etag_path = etag_path(cache);
+    let cached = fs::read_to_string(cache).await.ok();
+    let etag = match cached {
+        Some(_) => fs::read_to_string(&etag_path).await.ok(),
+        None => None,
+    };
+
+    let request = || match &etag {
+        Some(etag) => client.get(url).header(IF_NONE_MATCH, etag.trim()),
+        None => client.get(url),
+    };
+
+    let resp = match send_with_retries(retry, request).await {
+        Ok(resp) if resp.status() == StatusCode::NOT_MODIFIED => {
+            if let Some(yaml) = cached {
+                return Ok((yaml, ZoneSource::NotModified));
+            }
+            return Err(RadarError::Fetch {
+                url: url.to_string(),
+                message: "answered 304 but there is no cached copy".to_string(),
+            });
+        }
+        Ok(resp) => match (require_success(resp, url), cached) {
+            (Ok(resp), _) => resp,
+            (Err(e), Some(yaml)) => return Ok((yaml, ZoneSource::Stale(e.to_string()))),
+            (Err(e), None) => return Err(e),
+        },
+        Err(e) => match cached {
+            Some(yaml) => return Ok((yaml, ZoneSource::Stale(e.to_string()))),
+            None => return Err(e.into()),
+        },
+    };
+
+    let new_etag = resp
+        .headers()
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let yaml = read_capped(resp, url, max_bytes).await?;
+
+    if let Some(dir) = cache.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    fs::write(cache, &yaml).await?;
+    match new_etag {
+        Some(etag) => fs::write(&etag_path, etag).await?,
+        // A stale ETag would wrongly match a later, different file.
+        None => {
+            let _ = fs::remove_file(&etag_path).await;
+        }
+    }
+    Ok((yaml, ZoneSource::Fresh))
+}
+
+/// Turn a non-2xx answer into an error, so an error page is never read as YAML.
+fn require_success(resp: Response, url: &str) -> Result<Response, RadarError> {
+    if resp.status().is_success() {
+        Ok(resp)
+    } else {
+        Err(RadarError::Fetch {
+            url: url.to_string(),
+            message: format!("HTTP {}", resp.status()),
+        })
+    }
+}
+
+/// `zone.yaml` → `zone.yaml.etag`.
+fn etag_path(cache: &Path) -> PathBuf {
+    let mut name = cache.as_os_str().to_owned();
+    name.push(".etag");
+    PathBuf::from(name)
+}
+
+/// Stream a response body, refusing anything larger than `max_bytes`.
+async fn read_capped(
+    mut resp: Response,
+    url: &str,
+    max_bytes: usize,
+) -> Result<String, RadarError> {
+    if let Some(len) = resp.content_length() {
+        if len > max_bytes as u64 {
+            return Err(too_large(url, max_bytes));
+        }
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(too_large(url, max_bytes));
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Top-level record names of a DNS YAML zone, in file order.
+///
+/// The document is walked with a visitor that keeps only the keys and skips
+/// every record body, so a large zone never becomes a full `serde_yaml::Value`
+/// tree. Keys that aren't strings (e.g. `123:`) are left out.
+///
+/// # Returns
+/// The names, or an error if the YAML is invalid or its root isn't a mapping
+pub fn zone_record_names(yaml: &str) -> Result<Vec<String>, RadarError> {
+    let records = serde_yaml::from_str::<Records<IgnoredAny>>(yaml)?;
+    Ok(records.0.into_iter().map(|(name, _)| name).collect())
+}
+
+/// Fingerprint of each top-level record's YAML, keyed by record name.
+///
+/// Records are parsed one at a time, so only a single entry is held as a
+/// `serde_yaml::Value` at once.
+pub fn zone_entry_fingerprints(yaml: &str) -> Result<BTreeMap<String, String>, RadarError> {
+    let records = serde_yaml::from_str::<Records<EntryFingerprint>>(yaml)?;
+    Ok(records
+        .0
+        .into_iter()
+        .map(|(name, fp)| (name, fp.0))
+        .collect())
+}
+
+/// String-keyed top-level records, with each body deserialized as `V`.
+struct Records<V>(Vec<(String, V)>);
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for Records<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RootVisitor<V>(PhantomData<V>);
+
+        impl<'de, V: Deserialize<'de>> Visitor<'de> for RootVisitor<V> {
+            type Value = Records<V>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a YAML mapping at root")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Records<V>, A::Error> {
+                let mut records = Vec::new();
+                while let Some(RecordName(name)) = map.next_key()? {
+                    let value = map.next_value::<V>()?;
+                    if let Some(name) = name {
+                        records.push((name, value));
+                    }
+                }
+                Ok(Records(records))
+            }
+        }
+
+        deserializer.deserialize_map(RootVisitor(PhantomData))
+    }
+}
+
+/// A record body reduced to its [`fingerprint`].
+struct EntryFingerprint(String);
+
+impl<'de> Deserialize<'de> for EntryFingerprint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_yaml::Value::deserialize(deserializer)?;
+        let text = serde_yaml::to_string(&value).map_err(de::Error::custom)?;
+        Ok(EntryFingerprint(fingerprint(&text)))
+    }
+}
+
+/// A mapping key: `Some` for strings, `None` for anything else.
+struct RecordName(Option<String>);
+
+impl<'de> Deserialize<'de> for RecordName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyVisitor;
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = RecordName;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a record name")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<RecordName, E> {
+                Ok(RecordName(Some(v.to_string())))
+            }
+
+            fn visit_string<E: de::Error>(self, v: String) -> Result<RecordName, E> {
+                Ok(RecordName(Some(v)))
+            }
+
+            fn visit_bool<E: de::Error>(self, _: bool) -> Result<RecordName, E> {
+                Ok(RecordName(None))
+            }
+
+            fn visit_i64<E: de::Error>(self, _: i64) -> Result<RecordName, E> {
+                Ok(RecordName(None))
+            }
+
+            fn visit_u64<E: de::Error>(self, _: u64) -> Result<RecordName, E> {
+                Ok(RecordName(None))
+            }
+
+            fn visit_f64<E: de::Error>(self, _: f64) -> Result<RecordName, E> {
+                Ok(RecordName(None))
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<RecordName, E> {
+                Ok(RecordName(None))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<RecordName, A::Error> {
+                while seq.next_element::<IgnoredAny>()?.is_some() {}
+                Ok(RecordName(None))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RecordName, A::Error> {
+                while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+                Ok(RecordName(None))
+            }
+        }
+
+        deserializer.deserialize_any(KeyVisitor)
+    }
+}
+
+/// Name of the zone a YAML URL describes, taken from its file name
+/// (`.../hackclub.com.yaml` → `hackclub.com`).
+pub fn zone_name(url: &str) -> &str {
+    let file = url.rsplit('/').next().unwrap_or(url);
+    file.strip_suffix(".yaml")
+        .or_else(|| file.strip_suffix(".yml"))
+        .unwrap_or(file)
+}
+
+fn too_large(url: &str, max_bytes: usize) -> RadarError {
+    RadarError::Fetch {
+        url: url.to_string(),
+        message: format!(
+            "DNS YAML exceeds the {}-byte limit (raise --max-yaml-bytes if this is expected)",
+            max_bytes
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A zone of `n` records with a mix of record shapes.
+    fn synthetic_zone(n: usize) -> String {
+        let mut yaml = String::with_capacity(n * 80);
+        for i in 0..n {
+            match i % 3 {
+                0 => yaml.push_str(&format!(
+                    "site{}:\n  - ttl: 600\n    type: CNAME\n    value: cname.vercel-dns.com.\n",
+                    i
+                )),
+                1 => yaml.push_str(&format!(
+                    "site{}:\n  ttl: 1\n  type: A\n  value:\n    - 10.0.0.{}\n    - 10.0.1.{}\n",
+                    i,
+                    i % 250,
+                    i % 250
+                )),
+                _ => yaml.push_str(&format!(
+                    "site{}:\n  type: TXT\n  value: \"v=spf1 include:{}.example ~all\"\n",
+                    i, i
+                )),
+            }
+        }
+        yaml
+    }
+
+    #[test]
+    fn large_zone_yields_every_name_in_order() {
+        let yaml = synthetic_zone(20_000);
+
+        let names = zone_record_names(&yaml).unwrap();
+
+        assert_eq!(names.len(), 20_000);
+        assert_eq!(names[0], "site0");
+        assert_eq!(names[12_345], "site12345");
+        assert_eq!(names[19_999], "site19999");
+        assert_eq!(zone_entry_fingerprints(&yaml).unwrap().len(), 20_000);
+    }
+
+    #[test]
+    fn non_string_keys_are_skipped_and_non_mappings_rejected() {
+        let names = zone_record_names("'': {type: A}\nwww:\n  type: A\n123:\n  type: A\n").unwrap();
+        assert_eq!(names, ["", "www"]);
+
+        assert!(zone_record_names("- www\n- api\n").is_err());
+        assert!(zone_record_names("www: [unclosed\n").is_err());
+    }
+
+    #[test]
+    fn fingerprints_change_only_with_the_record() {
+        let before = zone_entry_fingerprints(
+            "a:\n  type: A\n  value: 1.1.1.1\nb:\n  type: A\n  value: 2.2.2.2\n",
+        )
+        .unwrap();
+        let after = zone_entry_fingerprints(
+            "a:\n  type: A\n  value: 1.1.1.1\nb:\n  type: A\n  value: 3.3.3.3\n",
+        )
+        .unwrap();
+
+        assert_eq!(before["a"], after["a"]);
+        assert_ne!(before["b"], after["b"]);
+    }
+}
@@ -0,0 +1,154 @@
+//! Error type shared by the library's fallible operations.
+
+use std::any::Any;
+use std::fmt;
+
+/// Errors produced while fetching the zone and pages, reading the DNS
+/// repository's history, querying the LLM, or reading and writing run files.
+#[derive(Debug)]
+pub enum RadarError {
+    /// The HTTP request itself failed (connection, timeout, body read).
+    Http(reqwest::Error),
+    /// The LLM API answered with a non-2xx status.
+    Llm {
+        /// HTTP status returned by the provider.
+        status: u16,
+        /// Provider's error message, or the raw body if it had none.
+        message: String,
+    },
+    /// The LLM answered, but its reply wasn't the JSON array asked for.
+    Parse {
+        /// Why the reply didn't parse.
+        message: String,
+        /// The start of the model's reply, for tuning the prompt.
+        output: String,
+    },
+    /// A page couldn't be fetched: a network error or a 4xx/5xx answer.
+    Fetch {
+        /// URL that was requested.
+        url: String,
+        /// What went wrong.
+        message: String,
+    },
+    /// A DNS zone or override file isn't valid YAML.
+    YamlParse(serde_yaml::Error),
+    /// A saved file (cache, state, `successes.json`) or an API response isn't
+    /// the JSON expected, or a result couldn't be serialized.
+    Json(serde_json::Error),
+    /// The configuration can't be run as given, e.g. a missing API key.
+    Config(String),
+    /// A `git` command couldn't be run or failed.
+    Git(String),
+    /// Reading or writing a local file (such as a cached zone) failed.
+    Io(std::io::Error),
+    /// The task handling this page panicked; the rest of the run carried on.
+    Panic(String),
+}
+
+impl fmt::Display for RadarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RadarError::Http(e) => write!(f, "HTTP error: {}", e),
+            RadarError::Llm { status, message } => {
+                write!(f, "LLM API error ({}): {}", status, message)
+            }
+            RadarError::Parse { message, output } => {
+                write!(f, "unparseable LLM reply ({}): {}", message, output)
+            }
+            RadarError::Fetch { url, message } => write!(f, "fetching {} failed: {}", url, message),
+            RadarError::YamlParse(e) => write!(f, "invalid YAML: {}", e),
+            RadarError::Json(e) => write!(f, "invalid JSON: {}", e),
+            RadarError::Config(message) => write!(f, "{}", message),
+            RadarError::Git(message) => write!(f, "{}", message),
+            RadarError::Io(e) => write!(f, "I/O error: {}", e),
+            RadarError::Panic(message) => write!(f, "task panicked: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for RadarError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RadarError::Http(e) => Some(e),
+            RadarError::YamlParse(e) => Some(e),
+            RadarError::Json(e) => Some(e),
+            RadarError::Io(e) => Some(e),
+            RadarError::Llm { .. }
+            | RadarError::Parse { .. }
+            | RadarError::Fetch { .. }
+            | RadarError::Config(_)
+            | RadarError::Git(_)
+            | RadarError::Panic(_) => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for RadarError {
+    fn from(e: reqwest::Error) -> Self {
+        RadarError::Http(e)
+    }
+}
+
+impl From<serde_yaml::Error> for RadarError {
+    fn from(e: serde_yaml::Error) -> Self {
+        RadarError::YamlParse(e)
+    }
+}
+
+impl From<serde_json::Error> for RadarError {
+    fn from(e: serde_json::Error) -> Self {
+        RadarError::Json(e)
+    }
+}
+
+impl From<std::io::Error> for RadarError {
+    fn from(e: std::io::Error) -> Self {
+        RadarError::Io(e)
+    }
+}
+
+impl From<tokio::task::JoinError> for RadarError {
+    fn from(e: tokio::task::JoinError) -> Self {
+        match e.try_into_panic() {
+            Ok(payload) => RadarError::Panic(panic_message(&*payload)),
+            Err(e) => RadarError::Panic(e.to_string()),
+        }
+    }
+}
+
+/// Text of a panic payload caught with `catch_unwind`.
+///
+/// Panics carry a `&str` or `String` in practice; anything else is reported generically.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn a_panicked_task_keeps_its_message() {
+        let err = tokio::spawn(async { panic!("deliberate") })
+            .await
+            .unwrap_err();
+
+        let err = RadarError::from(err);
+
+        assert!(
+            matches!(&err, RadarError::Panic(m) if m == "deliberate"),
+            "{:?}",
+            err
+        );
+    }
+
+    #[test]
+    fn bad_json_is_a_json_error() {
+        let err = serde_json::from_str::<Vec<u8>>("{").unwrap_err();
+        assert!(matches!(RadarError::from(err), RadarError::Json(_)));
+    }
+}
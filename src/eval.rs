@@ -0,0 +1,153 @@
+//! Scoring LLM extraction against reference events.
+
+use serde::Serialize;
+
+use crate::dates::parse_date_range;
+use crate::dedupe::names_similar;
+use crate::types::Hackathon;
+
+/// How well extracted hackathons agree with a reference set.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct Agreement {
+    /// Reference events.
+    pub reference: usize,
+    /// Extracted events.
+    pub extracted: usize,
+    /// Extracted events matched to a distinct reference event.
+    pub matched: usize,
+}
+
+impl Agreement {
+    /// Share of extracted events that match a reference event (1.0 when nothing was extracted).
+    pub fn precision(&self) -> f64 {
+        ratio(self.matched, self.extracted)
+    }
+
+    /// Share of reference events that were extracted (1.0 when there were none).
+    pub fn recall(&self) -> f64 {
+        ratio(self.matched, self.reference)
+    }
+
+    /// Add another page's counts to these.
+    pub fn add(&mut self, other: &Agreement) {
+        self.reference += other.reference;
+        self.extracted += other.extracted;
+        self.matched += other.matched;
+    }
+}
+
+fn ratio(n: usize, d: usize) -> f64 {
+    if d == 0 {
+        1.0
+    } else {
+        n as f64 / d as f64
+    }
+}
+
+/// Compare `extracted` with `reference`, pairing each reference event with at
+/// most one extracted event.
+///
+/// Two events match when their names are similar and, if both have parseable
+/// dates, they start on the same day.
+pub fn compare(reference: &[Hackathon], extracted: &[Hackathon]) -> Agreement {
+    let mut used = vec![false; extracted.len()];
+    let mut matched = 0;
+
+    for r in reference {
+        let hit = (0..extracted.len()).find(|&i| !used[i] && same_event(r, &extracted[i]));
+        if let Some(i) = hit {
+            used[i] = true;
+            matched += 1;
+        }
+    }
+
+    Agreement {
+        reference: reference.len(),
+        extracted: extracted.len(),
+        matched,
+    }
+}
+
+fn same_event(a: &Hackathon, b: &Hackathon) -> bool {
+    if !names_similar(&a.name, &b.name) {
+        return false;
+    }
+    match (parse_date_range(&a.dates).0, parse_date_range(&b.dates).0) {
+        (Some(x), Some(y)) => x == y,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, dates: &str) -> Hackathon {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "url": "http://foo.hackclub.com/",
+            "dates": dates,
+            "summary": "",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn events_match_by_name_and_start_date() {
+        let reference = [
+            entry("HackFoo 2026", "2026-03-01"),
+            entry("Game Jam", "2026-05-10"),
+            entry("Hack Night", "Unknown"),
+        ];
+        let extracted = [
+            entry("hackfoo 2026", "March 1–2, 2026"),
+            // Right name, wrong day.
+            entry("Game Jam", "May 11, 2026"),
+            // Undated on one side still matches on name.
+            entry("Hack Night", "every Friday"),
+            entry("Bonus Workshop", "2026-03-01"),
+        ];
+
+        let agreement = compare(&reference, &extracted);
+
+        assert_eq!(
+            agreement,
+            Agreement {
+                reference: 3,
+                extracted: 4,
+                matched: 2,
+            }
+        );
+        assert_eq!(agreement.precision(), 0.5);
+        assert!((agreement.recall() - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn each_extracted_event_matches_at_most_once() {
+        let reference = [entry("HackFoo", "Unknown"), entry("HackFoo", "Unknown")];
+        let extracted = [entry("HackFoo", "Unknown")];
+
+        let agreement = compare(&reference, &extracted);
+
+        assert_eq!(agreement.matched, 1);
+        assert_eq!((agreement.precision(), agreement.recall()), (1.0, 0.5));
+    }
+
+    #[test]
+    fn empty_sides_score_as_perfect_and_totals_add_up() {
+        let none = compare(&[], &[]);
+        assert_eq!((none.precision(), none.recall()), (1.0, 1.0));
+
+        let mut total = Agreement::default();
+        total.add(&compare(&[entry("A", "Unknown")], &[entry("A", "Unknown")]));
+        total.add(&compare(&[entry("B", "Unknown")], &[]));
+        assert_eq!(
+            total,
+            Agreement {
+                reference: 2,
+                extracted: 1,
+                matched: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! A best-effort entry from page metadata, for event pages the LLM found
+//! nothing on.
+
+use crate::html;
+use crate::types::{Confidence, EventFormat, Extraction, Hackathon};
+
+/// Build a low-confidence hackathon from a page's `og:title` (or `<title>`) and
+/// `<meta name="description">`.
+///
+/// Only pages whose title or description mentions one of `keywords` qualify, so
+/// ordinary sites the LLM rightly found nothing on stay empty.
+///
+/// # Arguments
+/// * `html` - Page HTML
+/// * `page_url` - Used as the entry's URL
+/// * `keywords` - Words marking the metadata as about an event
+///
+/// # Returns
+/// One entry with unknown dates and `Confidence::Low`, or `None`
+pub fn meta_entry<S: AsRef<str>>(html: &str, page_url: &str, keywords: &[S]) -> Option<Hackathon> {
+    let name = html::meta_content(html, "property", "og:title")
+        .or_else(|| html::title(html))
+        .filter(|t| !t.is_empty())?;
+    let description = html::meta_content(html, "name", "description")
+        .or_else(|| html::meta_content(html, "property", "og:description"));
+    let about = format!("{} {}", name, description.as_deref().unwrap_or_default());
+    if !html::contains_event_keywords(&about, keywords) {
+        return None;
+    }
+
+    Some(Hackathon {
+        url: page_url.to_string(),
+        register_url: None,
+        dates: "Unknown".to_string(),
+        start_date: None,
+        end_date: None,
+        summary: description.unwrap_or_else(|| name.clone()),
+        name,
+        format: EventFormat::Unknown,
+        prizes: None,
+        organizer: None,
+        source: String::new(),
+        extracted_by: Extraction::Meta,
+        confidence: Confidence::Low,
+        subdomain: None,
+        url_reachable: None,
+        redirected_from: None,
+        final_url: None,
+        also_from: Vec::new(),
+        first_added: None,
+        last_modified: None,
+        contacts: Vec::new(),
+        source_snippet: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EVENT_KEYWORDS;
+
+    const PAGE: &str = "https://foo.hackclub.com/";
+
+    #[test]
+    fn og_title_wins_over_the_title_and_the_entry_is_labelled_a_guess() {
+        let html = r#"<html><head><title>Home | Foo</title>
+            <meta property="og:title" content="HackFoo 2026">
+            <meta name="description" content="A 24-hour hackathon for teens."></head></html>"#;
+
+        let h = meta_entry(html, PAGE, EVENT_KEYWORDS).unwrap();
+
+        assert_eq!(h.name, "HackFoo 2026");
+        assert_eq!(h.summary, "A 24-hour hackathon for teens.");
+        assert_eq!(h.url, PAGE);
+        assert_eq!(h.dates, "Unknown");
+        assert_eq!(h.confidence, Confidence::Low);
+        assert_eq!(h.extracted_by, Extraction::Meta);
+    }
+
+    #[test]
+    fn og_description_stands_in_for_a_missing_description() {
+        let html = r#"<html><head><title>HackFoo</title>
+            <meta property="og:description" content="Join our hackathon in March."></head></html>"#;
+
+        let h = meta_entry(html, PAGE, EVENT_KEYWORDS).unwrap();
+
+        assert_eq!(h.name, "HackFoo");
+        assert_eq!(h.summary, "Join our hackathon in March.");
+    }
+
+    #[test]
+    fn pages_whose_metadata_mentions_no_event_are_left_alone() {
+        let html = r#"<html><head><title>My portfolio</title>
+            <meta name="description" content="Projects and blog posts."></head>
+            <body>I once went to a hackathon.</body></html>"#;
+
+        assert!(meta_entry(html, PAGE, EVENT_KEYWORDS).is_none());
+        assert!(meta_entry("<html><body>hackathon</body></html>", PAGE, EVENT_KEYWORDS).is_none());
+    }
+}
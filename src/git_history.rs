@@ -0,0 +1,466 @@
+//! Subdomain history from a local clone of the hackclub/dns repository.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use chrono::DateTime;
+use serde::Serialize;
+
+use crate::dns::{zone_entry_fingerprints, zone_record_names};
+use crate::error::RadarError;
+
+/// When a subdomain's DNS entry was added and last changed.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitInfo {
+    /// ISO 8601 timestamp of the commit that added the entry.
+    pub first_added: Option<String>,
+    /// ISO 8601 timestamp of the latest commit touching the entry.
+    pub last_modified: Option<String>,
+    /// Hash of the commit that added the entry.
+    pub first_commit_hash: Option<String>,
+    /// Hash of the latest commit touching the entry.
+    pub last_commit_hash: Option<String>,
+    /// Author of the latest commit touching the entry.
+    pub last_author: Option<String>,
+}
+
+/// One commit that touched the zone file.
+struct ZoneCommit {
+    hash: String,
+    time: i64,
+    author: String,
+}
+
+/// Return the subdomain defined by a top-level YAML key line, if any.
+///
+/// Recognizes unindented keys made of letters, digits, `_`, `-`, `.`, and `*`
+/// followed by a colon, bare or in single or double quotes: `foo:`,
+/// `foo: { ... }`, `"foo.bar":`, `'*.events':`. Quotes are not part of the name.
+pub fn parse_subdomain_from_yaml_line(line: &str) -> Option<&str> {
+    let (key, rest) = match line.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = line[1..].find(quote)? + 1;
+            (&line[1..end], &line[end + 1..])
+        }
+        _ => {
+            let end = line.find(':')?;
+            (&line[..end], &line[end..])
+        }
+    };
+    let valid = !key.is_empty()
+        // A YAML key's colon ends the line or is followed by a space.
+        && rest
+            .strip_prefix(':')
+            .is_some_and(|v| v.is_empty() || v.starts_with([' ', '\t']))
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '*'));
+    valid.then_some(key)
+}
+
+/// The top-level key each line of a zone file belongs to.
+///
+/// A key line starts a record; the indented block under it (record lists, nested
+/// maps), and blank or comment lines inside it, belong to that key until the next
+/// unindented line. Lines before the first key map to `None`.
+///
+/// # Returns
+/// One entry per line of `yaml`, in order
+pub fn subdomains_by_line(yaml: &str) -> Vec<Option<&str>> {
+    let mut current = None;
+    yaml.lines()
+        .map(|line| {
+            let continues =
+                line.is_empty() || line.starts_with([' ', '\t']) || line.starts_with('#');
+            if !continues {
+                current = parse_subdomain_from_yaml_line(line);
+            }
+            current
+        })
+        .collect()
+}
+
+/// Make `dir` an up-to-date bare clone of `url`: cloned on first use, fetched on
+/// later runs.
+///
+/// The clone is full rather than shallow, since `first_added` dates come from
+/// the oldest commits. No working tree is checked out; history is read from the
+/// object store.
+///
+/// # Arguments
+/// * `url` - Repository to clone (e.g. `https://github.com/hackclub/dns`)
+/// * `dir` - Where the clone lives between runs
+pub fn sync_dns_repo(url: &str, dir: &Path) -> Result<(), RadarError> {
+    let fetch = dir.join("HEAD").exists();
+    let mut git = Command::new("git");
+    if fetch {
+        git.arg("-C")
+            .arg(dir)
+            .args(["fetch", "--quiet", "origin", "+refs/heads/*:refs/heads/*"]);
+        run_git(&mut git, "fetch")?;
+    } else {
+        git.args(["clone", "--bare", "--quiet", url]).arg(dir);
+        run_git(&mut git, "clone")?;
+    }
+    Ok(())
+}
+
+/// Run a `git` command and return its stdout, turning a failure to start it or
+/// a non-zero exit into [`RadarError::Git`].
+fn run_git(git: &mut Command, what: &str) -> Result<Vec<u8>, RadarError> {
+    let output = git
+        .output()
+        .map_err(|e| RadarError::Git(format!("couldn't run git {}: {}", what, e)))?;
+    if !output.status.success() {
+        return Err(RadarError::Git(format!(
+            "git {} failed: {}",
+            what,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Walk the zone file's history and record when (and in which commits) each
+/// subdomain was added and its entry last changed.
+///
+/// Every revision of the file is read whole (through a single `git cat-file
+/// --batch` process) and its records compared with the previous revision's, so
+/// changes are found by key rather than by parsing diff text, and times come
+/// straight from each commit's timestamp. Revisions that aren't valid YAML are
+/// skipped. A key that was removed and later re-added keeps its first date.
+///
+/// # Arguments
+/// * `yaml_path` - Zone file path relative to the repository root
+/// * `repo_path` - Root of the hackclub/dns checkout
+///
+/// # Returns
+/// History for each subdomain in the latest revision, or an error if `git` fails
+pub fn get_yaml_git_history(
+    yaml_path: &Path,
+    repo_path: &Path,
+) -> Result<HashMap<String, GitInfo>, RadarError> {
+    let log = run_git(
+        Command::new("git")
+            .arg("-C")
+            .arg(repo_path)
+            .args(["log", "--reverse", "--format=%H%x00%ct%x00%an", "--"])
+            .arg(yaml_path),
+        "log",
+    )?;
+    let commits: Vec<ZoneCommit> = String::from_utf8_lossy(&log)
+        .lines()
+        .filter_map(|l| {
+            let mut fields = l.splitn(3, '\0');
+            Some(ZoneCommit {
+                hash: fields.next()?.to_string(),
+                time: fields.next()?.parse().ok()?,
+                author: fields.next()?.to_string(),
+            })
+        })
+        .collect();
+
+    let path = yaml_path.to_string_lossy().replace('\\', "/");
+    let revisions = read_revisions(repo_path, &path, &commits)?;
+
+    let mut history: HashMap<String, GitInfo> = HashMap::new();
+    let mut previous: BTreeMap<String, String> = BTreeMap::new();
+    for (commit, content) in commits.iter().zip(revisions) {
+        let Some(Ok(entries)) = content.map(|c| zone_entry_fingerprints(&c)) else {
+            continue;
+        };
+        let Some(time) = DateTime::from_timestamp(commit.time, 0).map(|t| t.to_rfc3339()) else {
+            continue;
+        };
+        for (name, fingerprint) in &entries {
+            if previous.get(name) != Some(fingerprint) {
+                let info = history.entry(name.clone()).or_default();
+                if info.first_added.is_none() {
+                    info.first_added = Some(time.clone());
+                    info.first_commit_hash = Some(commit.hash.clone());
+                }
+                info.last_modified = Some(time.clone());
+                info.last_commit_hash = Some(commit.hash.clone());
+                info.last_author = Some(commit.author.clone());
+            }
+        }
+        previous = entries;
+    }
+
+    history.retain(|name, _| previous.contains_key(name));
+    Ok(history)
+}
+
+/// The file at `path` in each of `commits`, in order; `None` where it was absent.
+fn read_revisions(
+    repo_path: &Path,
+    path: &str,
+    commits: &[ZoneCommit],
+) -> Result<Vec<Option<String>>, RadarError> {
+    let cat_file = |e: &dyn std::fmt::Display| RadarError::Git(format!("git cat-file: {}", e));
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| cat_file(&e))?;
+
+    // Requests are written from another thread so a full stdout pipe can't
+    // deadlock the two sides.
+    let requests: String = commits
+        .iter()
+        .map(|c| format!("{}:{}\n", c.hash, path))
+        .collect();
+    let mut stdin = child.stdin.take().ok_or_else(|| cat_file(&"no stdin"))?;
+    let writer = std::thread::spawn(move || stdin.write_all(requests.as_bytes()));
+
+    let mut stdout = BufReader::new(child.stdout.take().ok_or_else(|| cat_file(&"no stdout"))?);
+    let mut revisions = Vec::with_capacity(commits.len());
+    let mut header = String::new();
+    for _ in commits {
+        header.clear();
+        stdout.read_line(&mut header).map_err(|e| cat_file(&e))?;
+        // "<sha> blob <size>", or "<spec> missing" where the file didn't exist.
+        let size = match header.trim_end().rsplit_once(' ') {
+            Some((_, "missing")) | None => {
+                revisions.push(None);
+                continue;
+            }
+            Some((_, size)) => size.parse::<usize>().map_err(|e| cat_file(&e))?,
+        };
+        let mut blob = vec![0; size + 1];
+        stdout.read_exact(&mut blob).map_err(|e| cat_file(&e))?;
+        blob.truncate(size);
+        revisions.push(Some(String::from_utf8_lossy(&blob).into_owned()));
+    }
+
+    writer
+        .join()
+        .map_err(|_| cat_file(&"writer panicked"))?
+        .map_err(|e| cat_file(&e))?;
+    child.wait().map_err(|e| cat_file(&e))?;
+    Ok(revisions)
+}
+
+/// Look up when `subdomain`'s entry in the zone file was last changed, using
+/// `git blame` on its key line and the indented block under it.
+///
+/// Slower than walking the log, but robust to renames and complex hunks, so it is
+/// useful for backfilling `GitInfo::last_modified` when other methods found nothing.
+/// The file is read from `HEAD`, so this works on the bare clone made by
+/// [`sync_dns_repo`].
+///
+/// # Arguments
+/// * `yaml_path` - Zone file path relative to the repository root
+/// * `repo_path` - Root of the hackclub/dns repository (bare or not)
+/// * `subdomain` - Top-level key to look up
+///
+/// # Returns
+/// The ISO 8601 commit time, `None` if the key isn't in the file, or an error if
+/// `git` fails
+pub fn get_subdomain_history_via_blame(
+    yaml_path: &Path,
+    repo_path: &Path,
+    subdomain: &str,
+) -> Result<Option<String>, RadarError> {
+    let content = read_head(yaml_path, repo_path)?;
+    blame_last_modified(yaml_path, repo_path, &content, subdomain)
+}
+
+/// Fill in `last_modified` with [`get_subdomain_history_via_blame`] for every
+/// record in the zone file's `HEAD` that `history` has no date for.
+///
+/// # Returns
+/// How many records were backfilled, or an error if `git` fails
+pub fn backfill_with_blame(
+    yaml_path: &Path,
+    repo_path: &Path,
+    history: &mut HashMap<String, GitInfo>,
+) -> Result<usize, RadarError> {
+    let content = read_head(yaml_path, repo_path)?;
+    // A HEAD that doesn't parse has no records to backfill.
+    let Ok(names) = zone_record_names(&content) else {
+        return Ok(0);
+    };
+    let mut filled = 0;
+    for name in names {
+        if history
+            .get(&name)
+            .is_some_and(|i| i.last_modified.is_some())
+        {
+            continue;
+        }
+        if let Some(time) = blame_last_modified(yaml_path, repo_path, &content, &name)? {
+            history.entry(name).or_default().last_modified = Some(time);
+            filled += 1;
+        }
+    }
+    Ok(filled)
+}
+
+/// The zone file as of `HEAD`.
+fn read_head(yaml_path: &Path, repo_path: &Path) -> Result<String, RadarError> {
+    let path = yaml_path.to_string_lossy().replace('\\', "/");
+    let content = run_git(
+        Command::new("git")
+            .arg("-C")
+            .arg(repo_path)
+            .arg("show")
+            .arg(format!("HEAD:{}", path)),
+        "show",
+    )?;
+    Ok(String::from_utf8_lossy(&content).into_owned())
+}
+
+/// Blame `subdomain`'s lines of `content` (the file at `HEAD`).
+fn blame_last_modified(
+    yaml_path: &Path,
+    repo_path: &Path,
+    content: &str,
+    subdomain: &str,
+) -> Result<Option<String>, RadarError> {
+    let owners = subdomains_by_line(content);
+    let Some(first) = owners.iter().position(|&k| k == Some(subdomain)) else {
+        return Ok(None);
+    };
+    let last = owners[first..]
+        .iter()
+        .position(|&k| k != Some(subdomain))
+        .map_or(owners.len(), |n| first + n);
+
+    let porcelain = run_git(
+        Command::new("git")
+            .arg("-C")
+            .arg(repo_path)
+            .args(["blame", "--porcelain", "-L"])
+            .arg(format!("{},{}", first + 1, last))
+            .args(["HEAD", "--"])
+            .arg(yaml_path),
+        "blame",
+    )?;
+
+    Ok(parse_blame_committer_time(&String::from_utf8_lossy(
+        &porcelain,
+    )))
+}
+
+/// Extract the latest `committer-time` header from `git blame --porcelain` output
+/// as ISO 8601.
+fn parse_blame_committer_time(porcelain: &str) -> Option<String> {
+    porcelain
+        .lines()
+        .filter_map(|l| l.strip_prefix("committer-time "))
+        .filter_map(|t| t.trim().parse::<i64>().ok())
+        .max()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(|t| t.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `git blame --porcelain` output for two lines from different commits.
+    const PORCELAIN: &str = "\
+4a1f0c3e9d2b7a6f5e4d3c2b1a0f9e8d7c6b5a4f 1 1 1
+author Zone Owner
+author-mail <owner@example.com>
+author-time 1704067200
+author-tz +0000
+committer Zone Owner
+committer-mail <owner@example.com>
+committer-time 1704067200
+committer-tz +0000
+summary add foo
+filename hackclub.com.yaml
+\tfoo:
+9b8a7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f1a0b 2 2 1
+author Someone Else
+author-mail <else@example.com>
+author-time 1717243200
+author-tz +0000
+committer Someone Else
+committer-mail <else@example.com>
+committer-time 1717243200
+committer-tz +0000
+summary point foo elsewhere
+filename hackclub.com.yaml
+\t  - type: CNAME
+";
+
+    #[test]
+    fn blame_time_is_the_latest_committer_time() {
+        assert_eq!(
+            parse_blame_committer_time(PORCELAIN).as_deref(),
+            Some("2024-06-01T12:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn blame_without_times_has_no_date() {
+        assert_eq!(parse_blame_committer_time(""), None);
+    }
+
+    #[test]
+    fn bare_keys_with_underscores_dots_and_dashes_are_subdomains() {
+        assert_eq!(parse_subdomain_from_yaml_line("foo:"), Some("foo"));
+        assert_eq!(
+            parse_subdomain_from_yaml_line("_dmarc: { type: TXT }"),
+            Some("_dmarc")
+        );
+        assert_eq!(
+            parse_subdomain_from_yaml_line("hack-night.events:"),
+            Some("hack-night.events")
+        );
+    }
+
+    #[test]
+    fn quoted_keys_lose_their_quotes() {
+        assert_eq!(
+            parse_subdomain_from_yaml_line("\"foo.bar\":"),
+            Some("foo.bar")
+        );
+        assert_eq!(
+            parse_subdomain_from_yaml_line("'*.events': { type: CNAME }"),
+            Some("*.events")
+        );
+        assert_eq!(parse_subdomain_from_yaml_line("\"unterminated:"), None);
+    }
+
+    #[test]
+    fn indented_values_comments_and_non_keys_are_not_subdomains() {
+        for line in [
+            "  - type: CNAME",
+            "# foo:",
+            "",
+            "url:http://x",
+            "foo bar:",
+            "\"foo\"x:",
+        ] {
+            assert_eq!(parse_subdomain_from_yaml_line(line), None, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn block_values_belong_to_the_key_above_them() {
+        let yaml = "# zone\nfoo:\n  - type: CNAME\n\n    value: x.github.io\n  # note\n\"*.events\":\n  type: A\nbar: { type: A }\n";
+        assert_eq!(
+            subdomains_by_line(yaml),
+            [
+                None,
+                Some("foo"),
+                Some("foo"),
+                Some("foo"),
+                Some("foo"),
+                Some("foo"),
+                Some("*.events"),
+                Some("*.events"),
+                Some("bar"),
+            ]
+        );
+    }
+}
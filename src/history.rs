@@ -0,0 +1,133 @@
+//! Cross-run probe history, stored as one JSON object per line.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+use crate::run::RunInfo;
+use crate::types::ProbeResult;
+
+/// Reachability of every probed URL in one run.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunRecord {
+    pub run_id: String,
+    pub started_at: String,
+    /// Probed URL → whether it answered with a status below 400.
+    pub reachable: BTreeMap<String, bool>,
+}
+
+impl RunRecord {
+    /// Summarize a run's probes.
+    pub fn from_probes(run: &RunInfo, probes: &[ProbeResult]) -> Self {
+        let reachable = probes
+            .iter()
+            .map(|p| (p.subdomain.clone(), p.status.is_some_and(|s| s < 400)))
+            .collect();
+        RunRecord {
+            run_id: run.run_id.clone(),
+            started_at: run.started_at.to_rfc3339(),
+            reachable,
+        }
+    }
+}
+
+/// Append `record` to the history file at `path`, creating it if needed.
+pub async fn append_run(path: &Path, record: &RunRecord) -> std::io::Result<()> {
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(line.as_bytes()).await
+}
+
+/// Read every run recorded in `path`, oldest first. A missing file is an empty history;
+/// unparseable lines are skipped.
+pub async fn load_runs(path: &Path) -> std::io::Result<Vec<RunRecord>> {
+    match fs::read_to_string(path).await {
+        Ok(text) => Ok(text
+            .lines()
+            .filter_map(|l| serde_json::from_str(l).ok())
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// URLs that were probed and unreachable in each of the last `n` runs.
+///
+/// These are likely stale DNS entries. A URL missing from any of those runs is not
+/// reported, and nothing is reported until at least `n` runs have been recorded.
+pub fn stale_subdomains(runs: &[RunRecord], n: usize) -> Vec<String> {
+    if n == 0 || runs.len() < n {
+        return Vec::new();
+    }
+    let recent = &runs[runs.len() - n..];
+
+    recent[recent.len() - 1]
+        .reachable
+        .keys()
+        .filter(|url| recent.iter().all(|r| r.reachable.get(*url) == Some(&false)))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn run(hour: u32, results: &[(&str, bool)]) -> RunRecord {
+        let probes: Vec<ProbeResult> = results
+            .iter()
+            .map(|(url, up)| {
+                if *up {
+                    ProbeResult::loaded(*url, "ok")
+                } else {
+                    ProbeResult::error(*url, "connection refused")
+                }
+            })
+            .collect();
+        let info = RunInfo::at(Utc.with_ymd_and_hms(2026, 1, 1, hour, 0, 0).unwrap());
+        RunRecord::from_probes(&info, &probes)
+    }
+
+    #[tokio::test]
+    async fn only_urls_down_in_each_of_the_last_n_runs_are_stale() {
+        let dir = std::env::temp_dir().join(format!("radar-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir).await;
+        fs::create_dir_all(&dir).await.unwrap();
+        let path = dir.join("history.jsonl");
+        let (dead, flaky) = ("http://dead.hackclub.com", "http://flaky.hackclub.com");
+        for record in [
+            run(1, &[(dead, false), (flaky, false)]),
+            run(2, &[(dead, false), (flaky, true)]),
+            run(3, &[(dead, false), (flaky, false)]),
+        ] {
+            append_run(&path, &record).await.unwrap();
+        }
+
+        let runs = load_runs(&path).await.unwrap();
+
+        assert_eq!(runs.len(), 3);
+        assert_eq!(stale_subdomains(&runs, 3), [dead]);
+        assert_eq!(stale_subdomains(&runs, 1), [dead, flaky]);
+        // Fewer runs than asked for: nothing is reported yet.
+        assert!(stale_subdomains(&runs, 4).is_empty());
+    }
+
+    #[test]
+    fn a_url_missing_from_a_run_is_not_stale() {
+        let runs = [
+            run(1, &[("http://a.test", false)]),
+            run(2, &[("http://b.test", false)]),
+        ];
+        assert!(stale_subdomains(&runs, 2).is_empty());
+    }
+}
@@ -0,0 +1,434 @@
+//! Lightweight helpers for turning raw HTML into readable text.
+
+use std::collections::HashSet;
+
+use crate::config::EVENT_KEYWORDS;
+use crate::tokens::{estimate_tokens, truncate_to_tokens};
+
+/// Elements whose contents are never visible text.
+const SKIPPED_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Strip tags (and `<script>`/`<style>` contents) from HTML and collapse whitespace.
+///
+/// This is deliberately forgiving: unterminated tags simply end the text.
+pub fn to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len() / 2);
+    let mut rest = html;
+
+    while let Some(open) = rest.find('<') {
+        text.push_str(&rest[..open]);
+        text.push(' ');
+        rest = &rest[open + 1..];
+
+        if let Some(name) = SKIPPED_ELEMENTS
+            .iter()
+            .find(|name| starts_with_ignore_case(rest, name))
+        {
+            let close = format!("</{}", name);
+            match rest.to_ascii_lowercase().find(&close) {
+                Some(i) => rest = &rest[i + close.len()..],
+                None => rest = "",
+            }
+        }
+
+        match rest.find('>') {
+            Some(close) => rest = &rest[close + 1..],
+            None => rest = "",
+        }
+    }
+    text.push_str(rest);
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Extract up to `max_chars` of page text, positioned around the first event keyword.
+///
+/// Falls back to the start of the page when no keyword is present.
+pub fn snippet(html: &str, max_chars: usize) -> String {
+    let text = to_text(html);
+    let lower = text.to_ascii_lowercase();
+
+    let Some(hit) = EVENT_KEYWORDS.iter().filter_map(|k| lower.find(k)).min() else {
+        return text.chars().take(max_chars).collect();
+    };
+
+    // Keep a little leading context so the keyword isn't the first word.
+    let lead = max_chars / 4;
+    let skip = text[..hit].chars().count().saturating_sub(lead);
+    text.chars()
+        .skip(skip)
+        .skip_while(|c| c.is_whitespace())
+        .take(max_chars)
+        .collect()
+}
+
+/// Elements dropped, with their contents, by [`strip_boilerplate`].
+const BOILERPLATE_ELEMENTS: [&str; 6] = ["script", "style", "nav", "noscript", "svg", "template"];
+
+/// Remove markup that carries no event content (`<script>`, `<style>`, `<nav>`,
+/// `<noscript>`, `<svg>`, `<template>`, and comments) and collapse whitespace,
+/// keeping the rest of the HTML as is.
+///
+/// JSON-LD `<script>` blocks are kept, since they describe events. Like
+/// [`to_text`], this is forgiving: an element that is never closed drops the
+/// rest of the page, and a self-closing one (`<svg/>`) only itself.
+pub fn strip_boilerplate(html: &str) -> String {
+    let lower = html.to_ascii_lowercase();
+    let mut out = String::with_capacity(html.len() / 2);
+    let mut pos = 0;
+
+    while let Some(open) = lower[pos..].find('<').map(|i| pos + i) {
+        out.push_str(&html[pos..open]);
+        let rest = &lower[open + 1..];
+        if rest.starts_with("!--") {
+            pos = lower[open..]
+                .find("-->")
+                .map_or(html.len(), |i| open + i + 3);
+            continue;
+        }
+
+        let name_len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len());
+        let name = &rest[..name_len];
+        let tag_end = lower[open..].find('>').map_or(html.len(), |i| open + i + 1);
+        let tag = &lower[open..tag_end];
+        let dropped = BOILERPLATE_ELEMENTS.contains(&name)
+            && !(name == "script" && tag.contains("application/ld+json"));
+        if !dropped {
+            out.push('<');
+            pos = open + 1;
+            continue;
+        }
+
+        pos = if tag.ends_with("/>") {
+            tag_end
+        } else {
+            let close = format!("</{}", name);
+            // `</nav` must not match the end of a `<navbar>` nested inside.
+            let close_at = lower[tag_end..]
+                .match_indices(&close)
+                .map(|(i, _)| tag_end + i)
+                .find(|&at| {
+                    !lower[at + close.len()..].starts_with(|c: char| c.is_ascii_alphanumeric())
+                });
+            match close_at {
+                Some(close_at) => lower[close_at..]
+                    .find('>')
+                    .map_or(html.len(), |j| close_at + j + 1),
+                None => html.len(),
+            }
+        };
+        out.push(' ');
+    }
+    out.push_str(&html[pos.min(html.len())..]);
+
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Cut `html` to about `budget` tokens (see [`estimate_tokens`]) without
+/// losing its `<head>`.
+///
+/// Plain truncation drops the title, meta tags, and JSON-LD of pages with long
+/// heads or bodies. Here everything up to `</head>` is kept (at most `head_cap`
+/// characters of it), and the rest of the budget goes to the body. Pages without
+/// a head are simply cut to `budget`.
+pub fn truncate_preserving_head(html: &str, budget: usize, head_cap: usize) -> String {
+    if estimate_tokens(html) <= budget {
+        return html.to_string();
+    }
+
+    let lower = html.to_ascii_lowercase();
+    let Some(head_end) = lower.find("</head>").map(|i| i + "</head>".len()) else {
+        return truncate_to_tokens(html, budget).to_string();
+    };
+
+    let (head, body) = html.split_at(head_end);
+    let head: String = head.chars().take(head_cap).collect();
+    let mut out = truncate_to_tokens(&head, budget).to_string();
+    let remaining = budget.saturating_sub(estimate_tokens(&out));
+    out.push_str(truncate_to_tokens(body, remaining));
+    out
+}
+
+/// Text of the page's `<title>`, if it has one.
+pub fn title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    Some(to_text(&html[start..end]))
+}
+
+/// `content` of the first `<meta>` tag whose `attr` (`name` or `property`)
+/// equals `value`, e.g. `meta_content(html, "property", "og:title")`.
+///
+/// Matching is case-insensitive and the content is whitespace-collapsed; empty
+/// content counts as missing.
+pub fn meta_content(html: &str, attr: &str, value: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some(open) = lower[pos..].find("<meta").map(|i| pos + i) {
+        let end = lower[open..].find('>').map_or(html.len(), |i| open + i);
+        let tag = &html[open..end];
+        if attribute(tag, attr).is_some_and(|v| v.eq_ignore_ascii_case(value)) {
+            return attribute(tag, "content")
+                .map(|c| c.split_whitespace().collect::<Vec<_>>().join(" "))
+                .filter(|c| !c.is_empty());
+        }
+        pos = end;
+    }
+    None
+}
+
+/// Value of attribute `name` in a single tag's source (quoted or bare).
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some(at) = lower[pos..].find(name).map(|i| pos + i) {
+        pos = at + name.len();
+        let standalone = lower[..at].ends_with(|c: char| c.is_ascii_whitespace());
+        let rest = lower[pos..].trim_start();
+        if !standalone || !rest.starts_with('=') {
+            continue;
+        }
+        let value = tag[tag.len() - rest.len() + 1..].trim_start();
+        return Some(match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let value = &value[1..];
+                &value[..value.find(quote).unwrap_or(value.len())]
+            }
+            _ => {
+                let len = value
+                    .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+                    .unwrap_or(value.len());
+                &value[..len]
+            }
+        });
+    }
+    None
+}
+
+/// Where contact links are recognized, and the canonical prefix each is rewritten to.
+const CONTACT_MARKERS: [(&str, &str); 9] = [
+    ("mailto:", "mailto:"),
+    ("discord.gg/", "https://discord.gg/"),
+    ("discord.com/invite/", "https://discord.gg/"),
+    ("join.slack.com/", "https://join.slack.com/"),
+    ("//twitter.com/", "https://x.com/"),
+    ("//www.twitter.com/", "https://x.com/"),
+    ("//x.com/", "https://x.com/"),
+    ("//instagram.com/", "https://instagram.com/"),
+    ("//www.instagram.com/", "https://instagram.com/"),
+];
+
+/// Organizer contact links on a page: `mailto:` addresses, Discord and Slack
+/// invites, and X/Twitter and Instagram profiles.
+///
+/// Links are normalized (emails lowercased, Discord invites and Twitter links
+/// rewritten to `discord.gg` and `x.com`, query strings and trailing slashes
+/// dropped) and deduplicated, in page order. Share buttons are skipped.
+pub fn contact_links(html: &str) -> Vec<String> {
+    let lower = html.to_ascii_lowercase();
+    let mut found: Vec<(usize, String)> = Vec::new();
+
+    for (marker, canonical) in CONTACT_MARKERS {
+        for (at, _) in lower.match_indices(marker) {
+            let start = at + marker.len();
+            let len = html[start..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || "-._~%+@/".contains(c)))
+                .unwrap_or(html.len() - start);
+            let tail = html[start..start + len].trim_end_matches(['.', '/']);
+            let is_profile = canonical.starts_with("https://x.com")
+                || canonical.starts_with("https://instagram.com");
+            let is_share = is_profile
+                && ["intent", "share"]
+                    .iter()
+                    .any(|s| starts_with_ignore_case(tail, s));
+            if tail.is_empty() || is_share || (canonical == "mailto:" && !tail.contains('@')) {
+                continue;
+            }
+            let tail = if canonical == "mailto:" {
+                tail.to_lowercase()
+            } else {
+                tail.to_string()
+            };
+            found.push((at, format!("{}{}", canonical, tail)));
+        }
+    }
+
+    found.sort_by_key(|(at, _)| *at);
+    let mut seen = HashSet::new();
+    found
+        .into_iter()
+        .map(|(_, link)| link)
+        .filter(|link| seen.insert(link.to_lowercase()))
+        .collect()
+}
+
+/// Whether `text` mentions any of `keywords` (case-insensitive).
+pub fn contains_event_keywords<S: AsRef<str>>(text: &str, keywords: &[S]) -> bool {
+    let lower = text.to_lowercase();
+    keywords
+        .iter()
+        .any(|k| lower.contains(&k.as_ref().to_lowercase()))
+}
+
+fn starts_with_ignore_case(s: &str, prefix: &str) -> bool {
+    s.len() >= prefix.len() && s.as_bytes()[..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A long `<head>` whose metadata sits at its end.
+    fn big_head() -> String {
+        let filler: String = (0..200)
+            .map(|i| format!("<meta name=\"x-filler-{}\" content=\"value {}\">\n", i, i))
+            .collect();
+        format!(
+            "<html><head>{}<title>HackFoo 2026</title>\
+             <meta property=\"og:title\" content=\"HackFoo\">\
+             <script type=\"application/ld+json\">{{\"@type\": \"Event\"}}</script></head>",
+            filler
+        )
+    }
+
+    #[test]
+    fn large_head_survives_a_huge_body_within_budget() {
+        let head = big_head();
+        let body = format!(
+            "<body>{}</body></html>",
+            "<p>hack all weekend</p>".repeat(50_000)
+        );
+        let html = format!("{}{}", head, body);
+        let budget = estimate_tokens(&head) + 500;
+
+        let cut = truncate_preserving_head(&html, budget, 100_000);
+
+        assert!(cut.starts_with(&head));
+        assert!(estimate_tokens(&cut) <= budget);
+        assert!(cut.len() > head.len(), "some body fits in the rest");
+        assert_eq!(title(&cut).as_deref(), Some("HackFoo 2026"));
+        assert_eq!(
+            meta_content(&cut, "property", "og:title").as_deref(),
+            Some("HackFoo")
+        );
+        assert!(cut.contains("application/ld+json"));
+    }
+
+    #[test]
+    fn head_is_capped_and_headless_pages_are_cut_plainly() {
+        let head = big_head();
+        let html = format!("{}<body>{}</body>", head, "word ".repeat(50_000));
+        let cut = truncate_preserving_head(&html, 2_000, 1_000);
+        assert!(cut.starts_with(&head[..1_000]));
+        assert!(!cut.contains("</head>"));
+
+        let headless = "word ".repeat(50_000);
+        let cut = truncate_preserving_head(&headless, 100, 1_000);
+        assert_eq!(cut, truncate_to_tokens(&headless, 100));
+
+        let small = "<html><head></head><body>hi</body></html>";
+        assert_eq!(truncate_preserving_head(small, 100, 10), small);
+    }
+
+    #[test]
+    fn event_keywords_match_case_insensitively() {
+        assert!(contains_event_keywords(
+            "Join our HACKATHON this spring!",
+            EVENT_KEYWORDS
+        ));
+        assert!(contains_event_keywords(
+            "Friday Hack Night at the library",
+            EVENT_KEYWORDS
+        ));
+        assert!(contains_event_keywords("Submit on Devpost", EVENT_KEYWORDS));
+        assert!(contains_event_keywords("Café jam", &["CAFÉ"]));
+    }
+
+    #[test]
+    fn pages_without_event_keywords_do_not_match() {
+        assert!(!contains_event_keywords(
+            "Welcome to my portfolio. I like hiking and cooking.",
+            EVENT_KEYWORDS
+        ));
+        assert!(!contains_event_keywords("anything at all", &[] as &[&str]));
+    }
+
+    #[test]
+    fn mailto_and_discord_invites_are_extracted_and_normalized() {
+        let html = r#"<footer>
+            <a href="mailto:Organizers@HackFoo.org?subject=Hi">Email us</a>
+            <a href="https://discord.com/invite/AbC123/">Discord</a>
+            <a href="https://discord.gg/AbC123">Join the chat</a>
+            <a href="mailto:organizers@hackfoo.org">again</a>
+            <a href="https://join.slack.com/t/hackfoo/shared_invite/zt-1">Slack</a>
+        </footer>"#;
+
+        assert_eq!(
+            contact_links(html),
+            [
+                "mailto:organizers@hackfoo.org",
+                "https://discord.gg/AbC123",
+                "https://join.slack.com/t/hackfoo/shared_invite/zt-1",
+            ]
+        );
+    }
+
+    #[test]
+    fn share_buttons_and_bare_mailtos_are_not_contacts() {
+        let html = r#"<a href="https://twitter.com/intent/tweet?text=hi">Share</a>
+            <a href="mailto:">Mail</a>
+            <a href="https://www.twitter.com/hackfoo">Follow</a>"#;
+
+        assert_eq!(contact_links(html), ["https://x.com/hackfoo"]);
+    }
+
+    #[test]
+    fn boilerplate_elements_and_comments_are_dropped() {
+        let html = "<html><body><nav><a href=\"/\">Home</a></nav>\
+            <!-- build 42 --><svg viewBox=\"0 0 1 1\"><path d=\"M0\"/></svg>\
+            <noscript>Enable JS</noscript><template><p>row</p></template>\
+            <style>p { color: red }</style><script>track()</script>\
+            <h1>HackFoo</h1>   <p>March 1</p></body></html>";
+        assert_eq!(
+            strip_boilerplate(html),
+            "<html><body> <h1>HackFoo</h1> <p>March 1</p></body></html>"
+        );
+    }
+
+    #[test]
+    fn json_ld_scripts_are_kept() {
+        let html = r#"<script>track()</script><SCRIPT type="application/ld+json">{"@type": "Event"}</SCRIPT>"#;
+        assert_eq!(
+            strip_boilerplate(html),
+            r#"<SCRIPT type="application/ld+json">{"@type": "Event"}</SCRIPT>"#
+        );
+    }
+
+    #[test]
+    fn self_closing_and_unclosed_elements() {
+        assert_eq!(
+            strip_boilerplate("<p>a</p><svg/><p>b</p>"),
+            "<p>a</p> <p>b</p>"
+        );
+        assert_eq!(
+            strip_boilerplate("<p>kept</p><nav><a>never closed</a><p>lost</p>"),
+            "<p>kept</p>"
+        );
+    }
+
+    #[test]
+    fn tags_only_starting_like_a_dropped_one_survive() {
+        assert_eq!(
+            strip_boilerplate("<navbar>Menu</navbar><scripted>Demo</scripted>"),
+            "<navbar>Menu</navbar><scripted>Demo</scripted>"
+        );
+        assert_eq!(
+            strip_boilerplate("<nav><navbar>Menu</navbar> Links</nav><p>Event</p>"),
+            "<p>Event</p>"
+        );
+    }
+}
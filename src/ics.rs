@@ -0,0 +1,229 @@
+//! iCalendar (RFC 5545) export of the hackathon summary.
+
+use chrono::{DateTime, Days, Utc};
+
+use crate::changes::fingerprint;
+use crate::types::Hackathon;
+
+/// Longest content line, in octets, before it is folded.
+const LINE_OCTETS: usize = 75;
+
+/// Render hackathons as an iCalendar file with one all-day `VEVENT` each.
+///
+/// Only entries with a parsed `start_date` (and an end date before the last one
+/// chrono can represent) are included; callers can report the rest. Event UIDs are derived from name, URL, and start date, so re-importing
+/// a later run's file updates events instead of duplicating them.
+///
+/// # Arguments
+/// * `hackathons` - Entries to export
+/// * `stamp` - Time the calendar was generated (`DTSTAMP`)
+pub fn render_ics(hackathons: &[Hackathon], stamp: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//HackClub Events Radar//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+
+    for h in hackathons {
+        let Some(start) = h.start_date else {
+            continue;
+        };
+        // DTEND is exclusive for all-day events: the day after the last one.
+        let Some(end) = h
+            .end_date
+            .unwrap_or(start)
+            .max(start)
+            .checked_add_days(Days::new(1))
+        else {
+            continue;
+        };
+        let uid = fingerprint(&format!("{}\n{}\n{}", h.name, h.url, start));
+
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}@hackclub-events-radar", uid));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(
+            &mut out,
+            &format!("DTSTART;VALUE=DATE:{}", start.format("%Y%m%d")),
+        );
+        push_line(
+            &mut out,
+            &format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+        );
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&h.name)));
+        let mut description = h.summary.clone();
+        if let Some(register) = &h.register_url {
+            description.push_str(&format!("\nApply: {}", register));
+        }
+        push_line(&mut out, &format!("DESCRIPTION:{}", escape(&description)));
+        if !h.url.is_empty() {
+            push_line(&mut out, &format!("URL:{}", h.url));
+        }
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// Escape a TEXT value: backslashes, `;`, `,`, and newlines.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Append a content line, folded at [`LINE_OCTETS`] octets (continuations start
+/// with a space) and terminated with CRLF.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > LINE_OCTETS {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    fn event(
+        name: &str,
+        start: Option<(i32, u32, u32)>,
+        end: Option<(i32, u32, u32)>,
+    ) -> Hackathon {
+        let ymd = |(y, m, d)| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let mut h: Hackathon = serde_json::from_value(serde_json::json!({
+            "name": name,
+            "url": "https://foo.hackclub.com/",
+            "dates": "",
+            "summary": "",
+        }))
+        .unwrap();
+        h.start_date = start.map(ymd);
+        h.end_date = end.map(ymd);
+        h
+    }
+
+    fn render(hackathons: &[Hackathon]) -> String {
+        render_ics(
+            hackathons,
+            Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap(),
+        )
+    }
+
+    /// Content lines with folding undone.
+    fn unfolded(ics: &str) -> Vec<String> {
+        ics.replace("\r\n ", "")
+            .split("\r\n")
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn dtend_is_the_day_after_the_last_day() {
+        let lines = unfolded(&render(&[
+            event("HackFoo", Some((2026, 3, 1)), Some((2026, 3, 2))),
+            event("One day", Some((2026, 2, 28)), None),
+        ]));
+        let dates: Vec<&str> = lines
+            .iter()
+            .filter(|l| l.starts_with("DTSTART") || l.starts_with("DTEND"))
+            .map(String::as_str)
+            .collect();
+        assert_eq!(
+            dates,
+            [
+                "DTSTART;VALUE=DATE:20260301",
+                "DTEND;VALUE=DATE:20260303",
+                "DTSTART;VALUE=DATE:20260228",
+                "DTEND;VALUE=DATE:20260301",
+            ]
+        );
+        assert!(lines.contains(&"DTSTAMP:20260102T030405Z".to_string()));
+    }
+
+    #[test]
+    fn undated_and_unrepresentable_events_are_skipped() {
+        let last_day = Hackathon {
+            end_date: Some(NaiveDate::MAX),
+            ..event("Ends on chrono's last day", Some((2026, 1, 1)), None)
+        };
+        let ics = render(&[event("Undated", None, None), last_day]);
+        assert!(!ics.contains("BEGIN:VEVENT"), "{}", ics);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn text_values_are_escaped() {
+        let h = Hackathon {
+            summary: "Build; ship, demo\\repeat\r\nLine two".to_string(),
+            ..event("Hack, Foo; 2026", Some((2026, 3, 1)), None)
+        };
+        let lines = unfolded(&render(&[h]));
+        assert!(lines.contains(&r"SUMMARY:Hack\, Foo\; 2026".to_string()));
+        assert!(lines.contains(&r"DESCRIPTION:Build\; ship\, demo\\repeat\nLine two".to_string()));
+    }
+
+    #[test]
+    fn every_line_ends_with_crlf() {
+        let ics = render(&[event("HackFoo", Some((2026, 3, 1)), None)]);
+        assert!(ics.ends_with("\r\n"));
+        assert_eq!(ics.matches('\n').count(), ics.matches("\r\n").count());
+    }
+
+    #[test]
+    fn long_lines_fold_at_75_octets_without_splitting_characters() {
+        let name = "Hackathon ".to_string() + &"é".repeat(80);
+        let ics = render(&[event(&name, Some((2026, 3, 1)), None)]);
+
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= LINE_OCTETS, "{} octets: {}", line.len(), line);
+        }
+        let folded = ics.lines().filter(|l| l.starts_with(' ')).count();
+        assert!(folded >= 2, "{}", ics);
+        assert!(unfolded(&ics).contains(&format!("SUMMARY:{}", name)));
+    }
+
+    #[test]
+    fn uids_are_stable_across_runs() {
+        let uid = |ics: &str| {
+            unfolded(ics)
+                .into_iter()
+                .find(|l| l.starts_with("UID:"))
+                .unwrap()
+        };
+        let first = render_ics(
+            &[event("HackFoo", Some((2026, 3, 1)), None)],
+            Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+        );
+        let later = render_ics(
+            &[event("HackFoo", Some((2026, 3, 1)), Some((2026, 3, 2)))],
+            Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap(),
+        );
+        let other = render(&[event("HackFoo", Some((2027, 3, 1)), None)]);
+
+        assert_eq!(uid(&first), uid(&later));
+        assert_ne!(uid(&first), uid(&other));
+        assert!(uid(&first).ends_with("@hackclub-events-radar"));
+    }
+}
@@ -0,0 +1,176 @@
+//! Event data embedded in pages as schema.org JSON-LD.
+
+use serde_json::Value as JsonValue;
+
+use crate::dates::parse_date_range;
+use crate::types::{Confidence, EventFormat, Extraction, Hackathon};
+
+/// schema.org types treated as events.
+const EVENT_TYPES: [&str; 4] = ["Event", "Hackathon", "EducationEvent", "SocialEvent"];
+
+/// Extract the events described by a page's `<script type="application/ld+json">` blocks.
+///
+/// Handles single objects, arrays, and `@graph` wrappers; blocks that aren't valid
+/// JSON and objects that aren't events (or have no name) are ignored.
+///
+/// # Arguments
+/// * `html` - Page HTML
+/// * `page_url` - Used as the event URL when the markup gives none
+pub fn extract_events(html: &str, page_url: &str) -> Vec<Hackathon> {
+    let mut events = Vec::new();
+    for block in ld_json_blocks(html) {
+        if let Ok(json) = serde_json::from_str::<JsonValue>(block) {
+            collect_events(&json, page_url, &mut events);
+        }
+    }
+    events
+}
+
+/// Contents of every `application/ld+json` script element.
+fn ld_json_blocks(html: &str) -> Vec<&str> {
+    let lower = html.to_ascii_lowercase();
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+
+    while let Some(open) = lower[pos..].find("<script").map(|i| pos + i) {
+        let Some(tag_end) = lower[open..].find('>').map(|i| open + i + 1) else {
+            break;
+        };
+        let Some(close) = lower[tag_end..].find("</script").map(|i| tag_end + i) else {
+            break;
+        };
+        if lower[open..tag_end].contains("application/ld+json") {
+            blocks.push(html[tag_end..close].trim());
+        }
+        pos = close;
+    }
+    blocks
+}
+
+/// Whether a JSON-LD event is complete enough to use without asking the LLM:
+/// it has a name, a URL, and a start date that parses.
+pub fn is_complete(event: &Hackathon) -> bool {
+    !event.name.is_empty() && !event.url.is_empty() && parse_date_range(&event.dates).0.is_some()
+}
+
+fn collect_events(json: &JsonValue, page_url: &str, out: &mut Vec<Hackathon>) {
+    match json {
+        JsonValue::Array(items) => {
+            for item in items {
+                collect_events(item, page_url, out);
+            }
+        }
+        JsonValue::Object(obj) => {
+            if let Some(graph) = obj.get("@graph") {
+                collect_events(graph, page_url, out);
+            }
+            if is_event(json) {
+                out.extend(map_event(json, page_url));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_event(json: &JsonValue) -> bool {
+    let matches = |t: &JsonValue| t.as_str().is_some_and(|t| EVENT_TYPES.contains(&t));
+    match &json["@type"] {
+        JsonValue::Array(types) => types.iter().any(matches),
+        t => matches(t),
+    }
+}
+
+fn map_event(e: &JsonValue, page_url: &str) -> Option<Hackathon> {
+    let name = e["name"].as_str().filter(|n| !n.trim().is_empty())?.trim();
+    let date = |key: &str| {
+        e[key]
+            .as_str()
+            .map(|d| d.split('T').next().unwrap_or(d).to_string())
+    };
+    let dates = match (date("startDate"), date("endDate")) {
+        (Some(s), Some(end)) if s != end => format!("{} – {}", s, end),
+        (Some(s), _) => s,
+        _ => "Unknown".to_string(),
+    };
+
+    // schema.org spells attendance modes as URLs, e.g. ".../OnlineEventAttendanceMode".
+    let format = e["eventAttendanceMode"]
+        .as_str()
+        .map(|m| match m.rsplit('/').next().unwrap_or(m) {
+            "OnlineEventAttendanceMode" => EventFormat::Online,
+            "OfflineEventAttendanceMode" => EventFormat::InPerson,
+            "MixedEventAttendanceMode" => EventFormat::Hybrid,
+            _ => EventFormat::Unknown,
+        })
+        .unwrap_or_default();
+
+    // `organizer` may be a plain name or an Organization/Person object, or a list.
+    fn organizer_of(o: &JsonValue) -> Option<&str> {
+        o.as_str().or_else(|| o["name"].as_str()).map(str::trim)
+    }
+    let organizer = match &e["organizer"] {
+        JsonValue::Array(items) => items.iter().find_map(organizer_of),
+        o => organizer_of(o),
+    }
+    .filter(|o| !o.is_empty())
+    .map(str::to_string);
+
+    Some(Hackathon {
+        name: name.to_string(),
+        url: e["url"].as_str().unwrap_or(page_url).to_string(),
+        register_url: None,
+        dates,
+        start_date: None,
+        end_date: None,
+        summary: e["description"].as_str().unwrap_or(name).to_string(),
+        format,
+        prizes: None,
+        organizer,
+        source: "json-ld".to_string(),
+        extracted_by: Extraction::JsonLd,
+        confidence: Confidence::High,
+        subdomain: None,
+        url_reachable: None,
+        redirected_from: None,
+        final_url: None,
+        also_from: Vec::new(),
+        first_added: None,
+        last_modified: None,
+        contacts: Vec::new(),
+        source_snippet: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "http://foo.hackclub.com/";
+
+    fn page(event: &str) -> String {
+        format!(
+            r#"<html><head><script type="application/ld+json">{}</script></head></html>"#,
+            event
+        )
+    }
+
+    #[test]
+    fn named_dated_events_are_complete() {
+        let events = extract_events(
+            &page(r#"{"@type": "Event", "name": "HackFoo", "startDate": "2026-03-01"}"#),
+            PAGE,
+        );
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].url, PAGE, "the page stands in for a missing URL");
+        assert!(is_complete(&events[0]));
+    }
+
+    #[test]
+    fn undated_events_are_incomplete() {
+        let events = extract_events(&page(r#"{"@type": "Event", "name": "HackFoo"}"#), PAGE);
+
+        assert_eq!(events.len(), 1);
+        assert!(!is_complete(&events[0]));
+    }
+}
@@ -6,11 +6,46 @@
 //! 3. Extract hackathon information from HTML using LLM analysis
 //! 4. Rate limit API requests to respect service limits
 
+pub mod bodies;
+pub mod cache;
+pub mod changes;
 pub mod config;
+pub mod dates;
+pub mod dedupe;
+pub mod dns;
+pub mod error;
+pub mod eval;
+pub mod fallback;
+pub mod git_history;
+pub mod history;
+pub mod html;
+pub mod ics;
+pub mod jsonld;
 pub mod llm;
+pub mod logging;
+pub mod metrics;
+pub mod output;
+pub mod page;
+pub mod pipeline;
 pub mod probe;
+pub mod probe_cache;
+pub mod progress;
 pub mod ratelimit;
+pub mod resolve;
+pub mod retry;
+pub mod robots;
+pub mod run;
+pub mod sources;
+pub mod stats;
+pub mod summary;
+pub mod tokens;
 pub mod types;
 
+pub use error::RadarError;
+pub use page::hackathons_from_url;
+pub use pipeline::{run_pipeline, PipelineConfig};
 pub use ratelimit::RateLimiter;
-pub use types::{EntryJson, Hackathon, ProbeResult, SuccessJson};
+pub use run::RunInfo;
+pub use types::{
+    EntryJson, EventFormat, Hackathon, ProbeResult, RunReport, SkipReason, SkippedPage, SuccessJson,
+};
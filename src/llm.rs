@@ -1,60 +1,195 @@
 //! LLM-based hackathon extraction from HTML content.
 
-use reqwest::Client;
+use std::panic::AssertUnwindSafe;
+
+use futures::stream::{self, StreamExt};
+use futures::FutureExt;
+use reqwest::{Client, Url};
 use serde_json::{json, Value as JsonValue};
+use tracing::debug;
 
-use crate::config::{HTML_TRUNCATE_CHARS, LLM_MAX_TOKENS, LLM_TEMPERATURE, NIM_API_URL, NIM_MODEL};
+use crate::config::{LlmConfig, MIN_CONTENT_TOKENS};
+use crate::error::{panic_message, RadarError};
+use crate::html;
+use crate::ratelimit::RateLimiter;
+use crate::retry::send_with_retries;
+use crate::tokens::estimate_tokens;
 use crate::types::Hackathon;
 
+/// A page URL paired with its extraction outcome.
+pub type PageExtraction = (String, Result<Vec<Hackathon>, RadarError>);
+
+/// Characters of an unparseable model reply kept in `RadarError::Parse`.
+const PARSE_ERROR_OUTPUT_CHARS: usize = 300;
+
+/// The extraction prompt used unless `LlmConfig::prompt_template` replaces it;
+/// `{url}` and `{html}` are filled with the page URL and its (truncated) HTML.
+pub const DEFAULT_PROMPT_TEMPLATE: &str = r#"You are a hackathon finder. Given HTML from the page "{url}", extract any hackathons mentioned.
+
+For each hackathon found, respond with a JSON array. Each object must have exactly these fields:
+- "name": hackathon name
+- "url": most specific URL for the hackathon (use "{url}" if no better link found)
+- "dates": date or date range as a string (e.g. "March 15–17, 2025"), or "Unknown" if not found
+- "summary": one sentence describing the hackathon
+- "register_url": the most direct registration/application link, or null if none is given
+- "format": "in-person", "online", or "hybrid", or "unknown" if not stated
+- "prizes": short description of the prizes or prize pool (e.g. "$5,000 in prizes"), or null if none are mentioned
+- "organizer": the club, chapter, or group running it (e.g. "Hack Club Boston"), or null if not stated
+
+If there are no hackathons on this page, respond with an empty array: []
+Respond with ONLY the JSON array, no other text.
+
+HTML:
+{html}"#;
+
+/// Placeholders every prompt template must contain.
+const PROMPT_PLACEHOLDERS: [&str; 2] = ["{url}", "{html}"];
+
+/// Check that a prompt template contains both `{url}` and `{html}`.
+///
+/// # Returns
+/// `Err` naming the missing placeholders
+pub fn validate_prompt_template(template: &str) -> Result<(), String> {
+    let missing: Vec<&str> = PROMPT_PLACEHOLDERS
+        .into_iter()
+        .filter(|p| !template.contains(p))
+        .collect();
+    match missing.as_slice() {
+        [] => Ok(()),
+        missing => Err(format!(
+            "prompt template is missing {} (it needs both {{url}} and {{html}})",
+            missing.join(" and ")
+        )),
+    }
+}
+
+/// Fill a prompt template's placeholders. The page HTML goes in last, so
+/// braces inside it are left alone.
+fn render_prompt(template: &str, url: &str, html: &str) -> String {
+    template.replace("{url}", url).replace("{html}", html)
+}
+
 /// Extract hackathons from HTML content using the NVIDIA NIM LLM.
 ///
 /// # Arguments
 /// * `client` - HTTP client for making LLM API requests
-/// * `api_key` - NVIDIA API key for authentication
+/// * `cfg` - API key, endpoint, model, and sampling settings
 /// * `url` - The source URL (used as context and fallback)
 /// * `html` - HTML content to analyze
 ///
+/// Unless `cfg.strip_boilerplate` is off, scripts, styles, and navigation are
+/// removed before the content is cut to its budget. If the provider rejects the
+/// request for exceeding its context length, the content is halved and the
+/// request retried, down to `MIN_CONTENT_TOKENS` or until halving no longer
+/// makes it shorter.
+///
 /// # Returns
-/// A vector of extracted hackathons, or an error if the request fails
+/// A vector of extracted hackathons, or an error if the request fails or the API
+/// answers with a non-2xx status (`RadarError::Llm`, carrying the provider's message),
+/// or `RadarError::Parse` if the model's reply isn't a JSON array of hackathons
 pub async fn extract_hackathons(
     client: &Client,
-    api_key: &str,
+    cfg: &LlmConfig,
     url: &str,
     html: &str,
-) -> Result<Vec<Hackathon>, Box<dyn std::error::Error + Send + Sync>> {
-    // Truncate HTML to avoid blowing the context window
-    let truncated: String = html.chars().take(HTML_TRUNCATE_CHARS).collect();
-
-    let prompt = format!(
-        r#"You are a hackathon finder. Given HTML from the page "{url}", extract any hackathons mentioned.
+) -> Result<Vec<Hackathon>, RadarError> {
+    let cleaned;
+    let html = if cfg.strip_boilerplate {
+        cleaned = html::strip_boilerplate(html);
+        &cleaned
+    } else {
+        html
+    };
+    // Halving a budget the page never reached would resend the same content.
+    let mut budget = cfg.content_budget_tokens().min(estimate_tokens(html));
+    let mut content = cut_to_budget(cfg, url, html, budget);
+    loop {
+        let err = match extract_from_content(client, cfg, url, &content).await {
+            Err(e) if is_context_length_error(&e) && budget > MIN_CONTENT_TOKENS => e,
+            result => return result,
+        };
+        budget = (budget / 2).max(MIN_CONTENT_TOKENS);
+        let shorter = cut_to_budget(cfg, url, html, budget);
+        if shorter.len() >= content.len() {
+            return Err(err);
+        }
+        content = shorter;
+    }
+}
 
-For each hackathon found, respond with a JSON array. Each object must have exactly these fields:
-- "name": hackathon name
-- "url": most specific URL for the hackathon (use "{url}" if no better link found)
-- "dates": date or date range as a string (e.g. "March 15–17, 2025"), or "Unknown" if not found
-- "summary": one sentence describing the hackathon
+/// Whether an API error says the prompt was longer than the model's context.
+pub fn is_context_length_error(e: &RadarError) -> bool {
+    let RadarError::Llm { status, message } = e else {
+        return false;
+    };
+    let message = message.to_lowercase();
+    matches!(status, 400 | 413 | 422)
+        && [
+            "context length",
+            "context_length",
+            "context window",
+            "too many tokens",
+            "maximum context",
+        ]
+        .iter()
+        .any(|needle| message.contains(needle))
+}
 
-If there are no hackathons on this page, respond with an empty array: []
-Respond with ONLY the JSON array, no other text.
+/// `html` cut to about `budget` tokens, keeping its head.
+fn cut_to_budget(cfg: &LlmConfig, url: &str, html: &str, budget: usize) -> String {
+    // Truncate HTML to avoid blowing the context window
+    let truncated = html::truncate_preserving_head(html, budget, cfg.head_max_chars);
+    if truncated.len() < html.len() {
+        debug!(
+            "{} → page cut from ~{} to ~{} tokens ({} of {} chars) for the LLM",
+            url,
+            estimate_tokens(html),
+            estimate_tokens(&truncated),
+            truncated.chars().count(),
+            html.chars().count()
+        );
+    }
+    truncated
+}
 
-HTML:
-{truncated}"#
-    );
+/// Run one extraction request over already-cut page content.
+async fn extract_from_content(
+    client: &Client,
+    cfg: &LlmConfig,
+    url: &str,
+    content: &str,
+) -> Result<Vec<Hackathon>, RadarError> {
+    let template = cfg
+        .prompt_template
+        .as_deref()
+        .unwrap_or(DEFAULT_PROMPT_TEMPLATE);
+    let prompt = render_prompt(template, url, content);
 
-    let body = json!({
-        "model": NIM_MODEL,
+    let mut body = json!({
+        "model": cfg.model,
         "messages": [{ "role": "user", "content": prompt }],
-        "temperature": LLM_TEMPERATURE,
-        "max_tokens": LLM_MAX_TOKENS,
+        "temperature": cfg.temperature,
+        "max_tokens": cfg.max_tokens,
     });
+    add_extra_params(&mut body, &cfg.extra_params);
+
+    let resp = send_with_retries(&cfg.retry, || {
+        client
+            .post(&cfg.api_url)
+            .header("Authorization", format!("Bearer {}", cfg.api_key))
+            .header("Content-Type", "application/json")
+            .json(&body)
+    })
+    .await?;
 
-    let resp = client
-        .post(NIM_API_URL)
-        .header("Authorization", format!("Bearer {}", api_key))
-        .header("Content-Type", "application/json")
-        .json(&body)
-        .send()
-        .await?;
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(RadarError::Llm {
+            status: status.as_u16(),
+            message: provider_error_message(&body),
+        });
+    }
 
     let json: JsonValue = resp.json().await?;
     let text = json["choices"][0]["message"]["content"]
@@ -69,6 +204,126 @@ HTML:
         .trim_end_matches("```")
         .trim();
 
-    let hackathons: Vec<Hackathon> = serde_json::from_str(clean).unwrap_or_default();
+    let mut hackathons: Vec<Hackathon> =
+        serde_json::from_str(clean).map_err(|e| RadarError::Parse {
+            message: e.to_string(),
+            output: clean.chars().take(PARSE_ERROR_OUTPUT_CHARS).collect(),
+        })?;
+    for h in &mut hackathons {
+        normalize_links(h, url);
+    }
     Ok(hackathons)
 }
+
+/// Merge `extra` into a request body without replacing any field it already has.
+pub fn add_extra_params(body: &mut JsonValue, extra: &serde_json::Map<String, JsonValue>) {
+    let Some(fields) = body.as_object_mut() else {
+        return;
+    };
+    for (key, value) in extra {
+        fields.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+}
+
+/// Pull the human-readable message out of an error response body.
+///
+/// Understands the OpenAI-style `{"error": {"message": ...}}` shape as well as
+/// top-level `detail`/`message` fields; otherwise returns the (truncated) raw body.
+fn provider_error_message(body: &str) -> String {
+    let json: JsonValue = serde_json::from_str(body).unwrap_or(JsonValue::Null);
+    let message = [
+        &json["error"]["message"],
+        &json["error"],
+        &json["detail"],
+        &json["message"],
+    ]
+    .into_iter()
+    .find_map(|v| v.as_str())
+    .map(str::to_string);
+    message.unwrap_or_else(|| body.chars().take(500).collect())
+}
+
+/// Resolve a hackathon's links against the page they came from.
+///
+/// Relative links become absolute, and an empty `url` falls back to the page
+/// itself. An empty or unresolvable `register_url` is dropped.
+pub fn normalize_links(h: &mut Hackathon, page_url: &str) {
+    h.url = resolve_link(page_url, &h.url).unwrap_or_else(|| page_url.to_string());
+    h.register_url = h
+        .register_url
+        .as_deref()
+        .and_then(|link| resolve_link(page_url, link));
+}
+
+fn resolve_link(page_url: &str, link: &str) -> Option<String> {
+    let link = link.trim();
+    if link.is_empty() {
+        return None;
+    }
+    match Url::parse(page_url) {
+        Ok(base) => base.join(link).ok().map(String::from),
+        Err(_) => Some(link.to_string()),
+    }
+}
+
+/// Run [`extract_hackathons`] over many pages, keeping each page's result separate.
+///
+/// Requests run `concurrency` at a time, each spending a `limiter` token first. Results
+/// come back in the same order as `pages`, and a failed page keeps its error
+/// rather than collapsing to an empty list.
+///
+/// # Arguments
+/// * `client` - HTTP client for making LLM API requests
+/// * `cfg` - API key, endpoint, model, and sampling settings
+/// * `limiter` - Rate limiter shared by all requests
+/// * `concurrency` - Maximum requests in flight
+/// * `pages` - `(url, html)` pairs to analyze
+/// * `on_page` - Called as each page finishes (e.g. for progress output)
+pub async fn extract_all<F>(
+    client: &Client,
+    cfg: &LlmConfig,
+    limiter: &RateLimiter,
+    concurrency: usize,
+    pages: &[(String, String)],
+    on_page: F,
+) -> Vec<PageExtraction>
+where
+    F: Fn(&str, &Result<Vec<Hackathon>, RadarError>),
+{
+    stream::iter(pages)
+        .map(|(url, html)| {
+            let on_page = &on_page;
+            async move {
+                limiter.acquire().await;
+                // One page's bug shouldn't take the whole run down with it.
+                let result = AssertUnwindSafe(extract_hackathons(client, cfg, url, html))
+                    .catch_unwind()
+                    .await
+                    .unwrap_or_else(|p| Err(RadarError::Panic(panic_message(&*p))));
+                on_page(url, &result);
+                (url.clone(), result)
+            }
+        })
+        .buffered(concurrency)
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_error_message_understands_common_shapes() {
+        for body in [
+            r#"{"error": {"message": "bad input", "type": "invalid_request"}}"#,
+            r#"{"error": "bad input"}"#,
+            r#"{"detail": "bad input"}"#,
+            r#"{"message": "bad input"}"#,
+        ] {
+            assert_eq!(provider_error_message(body), "bad input", "{}", body);
+        }
+        assert_eq!(provider_error_message("Bad Gateway"), "Bad Gateway");
+        assert_eq!(provider_error_message(&"x".repeat(600)).len(), 500);
+    }
+}
@@ -0,0 +1,96 @@
+//! Plain terminal output for the crate's `tracing` events.
+//!
+//! The pipeline reports what it is doing through `tracing` (`info!` for normal
+//! output, `debug!` for `--verbose` detail, `warn!`/`error!` for problems), so a
+//! program embedding it can route those events into its own subscriber. The CLI
+//! installs [`TerminalLogger`], which prints each message as a bare line, the
+//! way the tool always has.
+
+use std::env;
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// Prints this crate's events at or above a level, and other crates' warnings
+/// and errors: warnings and errors to stderr, the rest to stdout. Spans are
+/// accepted but not shown.
+#[derive(Debug)]
+pub struct TerminalLogger {
+    max_level: LevelFilter,
+    next_span: AtomicU64,
+}
+
+impl TerminalLogger {
+    /// A logger showing events up to `max_level` (`LevelFilter::OFF` shows none).
+    pub fn new(max_level: LevelFilter) -> Self {
+        TerminalLogger {
+            max_level,
+            next_span: AtomicU64::new(1),
+        }
+    }
+
+    /// Install this logger as the process-wide subscriber.
+    ///
+    /// Does nothing if a subscriber has already been set, so an embedding
+    /// application's own choice wins.
+    pub fn init(self) {
+        let _ = tracing::subscriber::set_global_default(self);
+    }
+}
+
+impl Subscriber for TerminalLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        let ours = metadata.target().starts_with(env!("CARGO_CRATE_NAME"));
+        *metadata.level() <= self.max_level && (ours || *metadata.level() <= Level::WARN)
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(self.max_level)
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut line = Line::default();
+        event.record(&mut line);
+        match *event.metadata().level() {
+            Level::ERROR | Level::WARN => eprintln!("{}", line.0),
+            _ => println!("{}", line.0),
+        }
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+/// An event's message, followed by any other fields as `name=value`.
+#[derive(Default)]
+struct Line(String);
+
+impl Visit for Line {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{:?}", value);
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// The level named by `RUST_LOG`, if it holds a bare level such as `debug`.
+///
+/// Per-module directives (`hackclub_dns_fetcher=debug`) are not supported.
+pub fn level_from_env() -> Option<LevelFilter> {
+    env::var("RUST_LOG").ok()?.trim().parse().ok()
+}
@@ -0,0 +1,140 @@
+//! Prometheus text-format metrics for scheduled runs.
+
+use std::fmt::Write;
+
+use crate::stats::RunStats;
+
+/// Prefix shared by every exported metric name.
+const PREFIX: &str = "hackclub_radar";
+
+/// Totals of one run, exported with `--metrics-file`.
+#[derive(Debug, Clone, Default)]
+pub struct RunMetrics {
+    /// Probe statistics for the run.
+    pub stats: RunStats,
+    /// Hackathons in the final summary.
+    pub hackathons_found: usize,
+    /// Pages whose LLM extraction failed.
+    pub llm_errors: usize,
+    /// Of those, pages whose LLM reply wasn't valid JSON.
+    pub llm_parse_errors: usize,
+    /// Wall-clock duration of the run.
+    pub run_duration_seconds: f64,
+}
+
+impl RunMetrics {
+    /// Render in the Prometheus text exposition format (suitable for the
+    /// node_exporter textfile collector).
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let s = &self.stats;
+        metric(
+            &mut out,
+            "probes_total",
+            "counter",
+            "URLs probed.",
+            s.probed as f64,
+        );
+        metric(
+            &mut out,
+            "successes_total",
+            "counter",
+            "Probes answered with a status below 400.",
+            s.live as f64,
+        );
+        metric(
+            &mut out,
+            "hackathons_found",
+            "gauge",
+            "Hackathons in the final summary.",
+            self.hackathons_found as f64,
+        );
+        metric(
+            &mut out,
+            "llm_errors_total",
+            "counter",
+            "Pages whose LLM extraction failed.",
+            self.llm_errors as f64,
+        );
+        metric(
+            &mut out,
+            "llm_parse_errors_total",
+            "counter",
+            "Pages whose LLM reply wasn't valid JSON.",
+            self.llm_parse_errors as f64,
+        );
+        metric(
+            &mut out,
+            "run_duration_seconds",
+            "gauge",
+            "Wall-clock duration of the run.",
+            self.run_duration_seconds,
+        );
+
+        let name = format!("{}_probe_responses_total", PREFIX);
+        let _ = writeln!(out, "# HELP {} Probe responses by HTTP status.", name);
+        let _ = writeln!(out, "# TYPE {} counter", name);
+        for (status, count) in &s.status_counts {
+            let _ = writeln!(out, "{}{{status=\"{}\"}} {}", name, status, count);
+        }
+        out
+    }
+}
+
+fn metric(out: &mut String, name: &str, kind: &str, help: &str, value: f64) {
+    let name = format!("{}_{}", PREFIX, name);
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_run_renders_every_metric() {
+        let metrics = RunMetrics {
+            stats: RunStats {
+                probed: 12,
+                live: 9,
+                status_counts: [(200, 8), (301, 1), (404, 3)].into(),
+                ..RunStats::default()
+            },
+            hackathons_found: 4,
+            llm_errors: 2,
+            llm_parse_errors: 1,
+            run_duration_seconds: 3.5,
+        };
+
+        let text = metrics.to_prometheus();
+
+        let lines: Vec<&str> = text.lines().collect();
+        for expected in [
+            "# TYPE hackclub_radar_probes_total counter",
+            "hackclub_radar_probes_total 12",
+            "hackclub_radar_successes_total 9",
+            "# TYPE hackclub_radar_hackathons_found gauge",
+            "hackclub_radar_hackathons_found 4",
+            "hackclub_radar_llm_errors_total 2",
+            "hackclub_radar_llm_parse_errors_total 1",
+            "# TYPE hackclub_radar_run_duration_seconds gauge",
+            "hackclub_radar_run_duration_seconds 3.5",
+            "hackclub_radar_probe_responses_total{status=\"200\"} 8",
+            "hackclub_radar_probe_responses_total{status=\"301\"} 1",
+            "hackclub_radar_probe_responses_total{status=\"404\"} 3",
+        ] {
+            assert!(
+                lines.contains(&expected),
+                "missing {:?} in\n{}",
+                expected,
+                text
+            );
+        }
+        // Every sample has a HELP and TYPE line before it.
+        let samples = lines.iter().filter(|l| !l.starts_with('#')).count();
+        assert_eq!(samples, 9);
+        assert_eq!(lines.iter().filter(|l| l.starts_with("# HELP")).count(), 7);
+        assert_eq!(lines.iter().filter(|l| l.starts_with("# TYPE")).count(), 7);
+    }
+}
@@ -0,0 +1,165 @@
+//! Human-readable and spreadsheet renderings of the hackathon summary.
+
+use std::fmt::Write;
+
+use crate::types::{EventFormat, Hackathon};
+
+/// Widest a name cell may be before it is truncated.
+const NAME_WIDTH: usize = 40;
+/// Widest a dates cell may be before it is truncated.
+const DATES_WIDTH: usize = 28;
+/// Widest a URL cell may be before it is truncated.
+const URL_WIDTH: usize = 48;
+
+/// Render hackathons as one indented block each, with every field in full.
+pub fn render_text(hackathons: &[Hackathon]) -> String {
+    let mut out = String::new();
+    for h in hackathons {
+        let _ = writeln!(out, "▸ {}", h.name);
+        let _ = writeln!(out, "  Dates:   {}", h.dates);
+        let flag = if h.url_reachable == Some(false) {
+            " (unreachable)"
+        } else {
+            ""
+        };
+        let _ = writeln!(out, "  URL:     {}{}", h.url, flag);
+        if let Some(register) = &h.register_url {
+            let _ = writeln!(out, "  Apply:   {}", register);
+        }
+        let _ = writeln!(out, "  Summary: {}", h.summary);
+        if h.format != EventFormat::Unknown {
+            let _ = writeln!(out, "  Format:  {}", h.format);
+        }
+        if let Some(prizes) = &h.prizes {
+            let _ = writeln!(out, "  Prizes:  {}", prizes);
+        }
+        if !h.contacts.is_empty() {
+            let _ = writeln!(out, "  Contact: {}", h.contacts.join(", "));
+        }
+        if !h.source.is_empty() {
+            let _ = writeln!(
+                out,
+                "  Source:  {} ({}, {} confidence)",
+                h.source, h.extracted_by, h.confidence
+            );
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Render hackathons as a compact table with aligned `NAME`, `DATES`, and `URL` columns.
+///
+/// Long cells are cut with an ellipsis; `--format text` shows every field in full.
+pub fn render_table(hackathons: &[Hackathon]) -> String {
+    let rows: Vec<[String; 3]> = hackathons
+        .iter()
+        .map(|h| {
+            [
+                truncate(&h.name, NAME_WIDTH),
+                truncate(&h.dates, DATES_WIDTH),
+                truncate(&h.url, URL_WIDTH),
+            ]
+        })
+        .collect();
+
+    let header = ["NAME", "DATES", "URL"].map(String::from);
+    let mut widths = header.clone().map(|h| h.chars().count());
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    push_row(&mut out, &header, &widths);
+    push_row(&mut out, &widths.map(|w| "-".repeat(w)), &widths);
+    for row in &rows {
+        push_row(&mut out, row, &widths);
+    }
+    out
+}
+
+/// Render hackathons as CSV with a `name,dates,url,summary` header row.
+///
+/// Fields containing commas, quotes, or line breaks are quoted, with inner quotes
+/// doubled (RFC 4180); rows end in CRLF.
+pub fn render_csv(hackathons: &[Hackathon]) -> String {
+    let mut out = String::from("name,dates,url,summary\r\n");
+    for h in hackathons {
+        let row = [&h.name, &h.dates, &h.url, &h.summary].map(|f| csv_field(f));
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn push_row(out: &mut String, cells: &[String; 3], widths: &[usize; 3]) {
+    let line = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, &w)| format!("{:<w$}", cell, w = w))
+        .collect::<Vec<_>>()
+        .join("  ");
+    out.push_str(line.trim_end());
+    out.push('\n');
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let mut cut: String = s.chars().take(max - 1).collect();
+        cut.push('…');
+        cut
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, dates: &str, url: &str) -> Hackathon {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "url": url,
+            "dates": dates,
+            "summary": "A hackathon.",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn table_columns_line_up_and_long_cells_are_cut() {
+        let long_name = "A Very Long Hackathon Name ".repeat(5);
+        let hackathons = [
+            entry("HackFoo", "March 1–2, 2026", "https://foo.hackclub.com/"),
+            entry(&long_name, "Unknown", "https://bar.hackclub.com/register"),
+        ];
+
+        let table = render_table(&hackathons);
+        let lines: Vec<&str> = table.lines().collect();
+
+        // Header, rule, and one row per hackathon.
+        assert_eq!(lines.len(), 4);
+        let dates_col = lines[0].find("DATES").unwrap();
+        let url_col = lines[0].find("URL").unwrap();
+        for line in [lines[2], lines[3]] {
+            let chars: Vec<char> = line.chars().collect();
+            for col in [dates_col, url_col] {
+                assert_eq!(chars[col - 1], ' ', "{}", line);
+                assert_ne!(chars[col], ' ', "{}", line);
+            }
+        }
+        assert!(lines[3].contains('…'));
+        assert!(lines[3].chars().count() <= NAME_WIDTH + DATES_WIDTH + URL_WIDTH + 4);
+    }
+}
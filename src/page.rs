@@ -0,0 +1,181 @@
+//! Turning one fetched page into finished hackathon entries.
+
+use reqwest::Client;
+
+use crate::config::{Config, SNIPPET_CHARS};
+use crate::dates::fill_dates;
+use crate::dedupe::{merge_page_entries, merge_series};
+use crate::error::RadarError;
+use crate::fallback;
+use crate::html;
+use crate::jsonld;
+use crate::llm::extract_hackathons;
+use crate::probe::{is_bot_challenge, is_parked, is_soft_404, probe_with_retries, ProbeOptions};
+use crate::types::Hackathon;
+
+/// Clean up the raw entries extracted from one page.
+///
+/// Merges a page's duplicates (and, with `merge_series`, its recurring dates),
+/// parses each entry's dates (rating its confidence), attaches the page's contact links, and adds a source snippet when
+/// `include_content_in_summary` is set. Provenance (`source`, redirects) is left
+/// to the caller.
+///
+/// # Arguments
+/// * `config` - Run settings controlling the optional steps
+/// * `page_url` - URL the page was fetched from
+/// * `html` - The page's HTML
+/// * `entries` - Hackathons extracted from it
+pub fn finish_page(
+    config: &Config,
+    page_url: &str,
+    html: &str,
+    mut entries: Vec<Hackathon>,
+) -> Vec<Hackathon> {
+    if config.merge_series {
+        entries = merge_series(entries);
+    }
+    let mut hackathons = merge_page_entries(page_url, entries);
+    if hackathons.is_empty() {
+        return hackathons;
+    }
+
+    let contacts = html::contact_links(html);
+    for h in &mut hackathons {
+        fill_dates(h);
+        h.rate_confidence();
+        for contact in &contacts {
+            if !h.contacts.contains(contact) {
+                h.contacts.push(contact.clone());
+            }
+        }
+    }
+    if config.include_content_in_summary {
+        let snippet = html::snippet(html, SNIPPET_CHARS);
+        for h in &mut hackathons {
+            h.source_snippet = Some(snippet.clone());
+        }
+    }
+    hackathons
+}
+
+/// Fetch `url` and return the hackathons on it, in one call.
+///
+/// The page is probed with the configured retries, then read from its JSON-LD
+/// (unless `prefer_json_ld` is off, when complete) or by the LLM (falling back to
+/// [`fallback::meta_entry`] when it finds nothing), and finished with
+/// [`finish_page`]. Soft-404s, parked domains, and bot-check interstitials yield
+/// no hackathons.
+///
+/// # Arguments
+/// * `client` - HTTP client for the page and the LLM
+/// * `config` - Retry, extraction, and LLM settings
+/// * `url` - Page to read
+///
+/// # Returns
+/// The page's hackathons, or `RadarError::Fetch` if it couldn't be fetched (or
+/// answered 4xx/5xx), or the LLM's error
+pub async fn hackathons_from_url(
+    client: &Client,
+    config: &Config,
+    url: &str,
+) -> Result<Vec<Hackathon>, RadarError> {
+    let opts = ProbeOptions {
+        retry: config.probe_retry.clone(),
+        ..ProbeOptions::default()
+    };
+    let probe = probe_with_retries(client, url, &opts).await;
+    let fetch_error = |message: String| RadarError::Fetch {
+        url: url.to_string(),
+        message,
+    };
+    let html = match (probe.status, &probe.content, &probe.error) {
+        (Some(status), _, _) if status >= 400 => {
+            return Err(fetch_error(format!("HTTP {}", status)))
+        }
+        (Some(_), Some(html), _) => html,
+        (_, _, Some(e)) => return Err(fetch_error(e.clone())),
+        _ => return Err(fetch_error("no response body".to_string())),
+    };
+    if is_soft_404(&probe) || is_parked(&probe) || is_bot_challenge(&probe) {
+        return Ok(Vec::new());
+    }
+
+    let page_url = probe.page_url();
+    let structured = if config.prefer_json_ld {
+        jsonld::extract_events(html, page_url)
+    } else {
+        Vec::new()
+    };
+    let entries = if !structured.is_empty() && structured.iter().all(jsonld::is_complete) {
+        structured
+    } else {
+        let found = extract_hackathons(client, &config.llm, page_url, html).await?;
+        if found.is_empty() && config.meta_fallback {
+            fallback::meta_entry(html, page_url, &config.keywords)
+                .into_iter()
+                .collect()
+        } else {
+            found
+        }
+    };
+
+    let mut hackathons = finish_page(config, page_url, html, entries);
+    if let Some(final_url) = &probe.final_url {
+        for h in &mut hackathons {
+            h.redirected_from = Some(url.to_string());
+            h.final_url = Some(final_url.clone());
+        }
+    }
+    Ok(hackathons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str) -> Hackathon {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "url": "http://foo.test/",
+            "dates": "March 1, 2026",
+            "summary": "A hackathon.",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn snippet_is_attached_only_when_asked_and_bounded() {
+        let html = format!(
+            "<p>{}Join our hackathon this spring! {}</p>",
+            "intro ".repeat(200),
+            "details ".repeat(200)
+        );
+        let mut config = Config::default();
+
+        let plain = finish_page(&config, "http://foo.test/", &html, vec![entry("HackFoo")]);
+        assert_eq!(plain[0].source_snippet, None);
+
+        config.include_content_in_summary = true;
+        let with = finish_page(&config, "http://foo.test/", &html, vec![entry("HackFoo")]);
+        let snippet = with[0].source_snippet.as_deref().unwrap();
+        assert!(snippet.chars().count() <= SNIPPET_CHARS);
+        assert!(snippet.contains("hackathon"), "{}", snippet);
+    }
+
+    #[test]
+    fn page_contacts_join_the_llms_without_duplicates() {
+        let html = r#"<a href="mailto:Team@HackFoo.org">Email</a>
+            <a href="https://discord.gg/hackfoo">Discord</a>"#;
+        let from_llm = Hackathon {
+            contacts: vec!["mailto:team@hackfoo.org".to_string()],
+            ..entry("HackFoo")
+        };
+
+        let finished = finish_page(&Config::default(), "http://foo.test/", html, vec![from_llm]);
+
+        assert_eq!(
+            finished[0].contacts,
+            ["mailto:team@hackfoo.org", "https://discord.gg/hackfoo"]
+        );
+    }
+}
@@ -0,0 +1,1225 @@
+//! The whole discovery run as a library call: fetch the DNS zones, probe every
+//! subdomain, extract hackathons with the LLM, and write the output files.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use futures::stream::{self, StreamExt};
+use futures::FutureExt;
+use reqwest::{Client, Url};
+use serde::Serialize;
+use tokio::fs;
+use tracing::{debug, error, info, info_span, warn, Instrument};
+
+use crate::bodies::{dump_bodies, load_bodies, load_successes};
+use crate::changes::{fingerprint, ChangeState};
+use crate::config::{Config, OutputFormat, DNS_REPO_URL};
+use crate::dates::fill_dates;
+use crate::dedupe::{dedupe_hackathons, merge_by_url, merge_page_entries};
+use crate::dns::{
+    fetch_zone, fetch_zone_cached, zone_entry_fingerprints, zone_record_names, ZoneSource,
+};
+use crate::error::{panic_message, RadarError};
+use crate::eval::{self, Agreement};
+use crate::fallback;
+use crate::git_history::{backfill_with_blame, get_yaml_git_history, sync_dns_repo, GitInfo};
+use crate::history::{self, RunRecord};
+use crate::html::{self, contains_event_keywords};
+use crate::ics::render_ics;
+use crate::jsonld;
+use crate::llm::extract_all;
+use crate::metrics::RunMetrics;
+use crate::output::render_csv;
+use crate::page::finish_page;
+use crate::probe::{
+    build_client, build_probe_client, build_proxy_clients, is_bot_challenge, is_html, is_parked,
+    is_soft_404, normalize_url, parse_proxy_list, parse_timeout_overrides, probe_paths,
+    verify_urls, ByteBudget, ClientPool, HostBreaker, HostLimit, ProbeCoalescer, ProbeOptions,
+    RedirectRules,
+};
+use crate::probe_cache::ProbeCache;
+use crate::progress::{Progress, ProgressMode};
+use crate::ratelimit::HostRateLimiter;
+use crate::ratelimit::RateLimiter;
+use crate::resolve::{CachingResolver, PublicOnly};
+use crate::robots::RobotsCache;
+use crate::run::RunInfo;
+use crate::sources::fetch_source;
+use crate::stats::RunStats;
+use crate::summary::{
+    chapter_summaries, filter_date_window, sort_hackathons, sort_newest_subdomains_first,
+};
+use crate::types::{
+    EntryJson, Hackathon, ProbeResult, RunReport, SkipReason, SkippedPage, SuccessJson,
+};
+
+/// Settings for [`run_pipeline`].
+#[derive(Debug, Clone)]
+pub struct PipelineConfig {
+    /// Zones, concurrency, LLM endpoint and API key, output directory and formats.
+    pub config: Config,
+    /// Re-extract the pages saved in this `successes.json` instead of probing.
+    pub successes_file: Option<PathBuf>,
+    /// Score the LLM against each page's JSON-LD (`eval.json`) instead of
+    /// extracting; the pipeline then returns no hackathons.
+    pub eval: bool,
+    /// How progress through the probe and LLM stages is shown; other output
+    /// goes through `tracing`.
+    pub progress: ProgressMode,
+    /// Identifies this run in output file names and `report.json`.
+    pub run: RunInfo,
+}
+
+impl PipelineConfig {
+    /// A full scan with `config`, showing progress counters, starting now.
+    pub fn new(config: Config) -> Self {
+        PipelineConfig {
+            config,
+            successes_file: None,
+            eval: false,
+            progress: ProgressMode::Counter,
+            run: RunInfo::new(),
+        }
+    }
+
+    /// Path an output file of this run is written to, honouring
+    /// `timestamped_output` and `output_dir`.
+    pub fn output_file(&self, name: &str) -> String {
+        let file = self.run.output_file(name, self.config.timestamped_output);
+        match &self.config.output_dir {
+            Some(dir) => dir.join(file).display().to_string(),
+            None => file,
+        }
+    }
+}
+
+// ── Pipeline ─────────────────────────────────────────────────────────────────
+
+/// Run the scan described by `pipeline` and return the hackathons it found.
+///
+/// Writes the same files as the command-line tool (`results.json`,
+/// `summary.json`, `report.json`, and any optional outputs); only the final
+/// terminal summary is left to the caller. `stats_only`, `dry_run`, and `eval`
+/// runs return an empty list.
+///
+/// # Returns
+/// The hackathons, or a [`RadarError`] if the configuration can't run
+/// (`Config`), the zone or a saved file can't be read, or an output can't be written
+pub async fn run_pipeline(pipeline: PipelineConfig) -> Result<Vec<Hackathon>, RadarError> {
+    let config = &pipeline.config;
+    let progress_mode = pipeline.progress;
+    let run = &pipeline.run;
+    let out = |name: &str| pipeline.output_file(name);
+    if let Some(dir) = &config.output_dir {
+        fs::create_dir_all(dir).await?;
+    }
+    if config.llm.api_key.is_empty() && !(config.stats_only || config.dry_run) {
+        return Err(RadarError::Config(
+            "NVIDIA_API_KEY env var not set".to_string(),
+        ));
+    }
+
+    let resolver = CachingResolver::new(config.dns_cache_size);
+    let client = Arc::new(build_client(config, &resolver)?);
+
+    // ── Probe the zone, or load saved pages ──────────────────────────────────
+    let (probes, zone_entries) = match (&pipeline.successes_file, &config.from_dir) {
+        (Some(file), _) => {
+            let probes = load_successes(file).await?;
+            info!("Loaded {} page(s) from {}", probes.len(), file.display());
+            (probes, BTreeMap::new())
+        }
+        (_, Some(dir)) => {
+            let probes = load_bodies(dir).await?;
+            info!("Loaded {} page(s) from {}", probes.len(), dir.display());
+            (probes, BTreeMap::new())
+        }
+        (_, None) => {
+            probe_zone(&client, &resolver, config, progress_mode)
+                .instrument(info_span!("probe"))
+                .await?
+        }
+    };
+
+    // ── Write debug JSONs ────────────────────────────────────────────────────
+    // Skipped when re-extracting, which would otherwise overwrite its own input.
+    if pipeline.successes_file.is_none() {
+        let results_json: Vec<EntryJson> = probes
+            .iter()
+            .map(|p| EntryJson {
+                subdomain: p.subdomain.clone(),
+                zone: p.zone.clone(),
+                status: p.status,
+                bytes: p.content.as_ref().map(|c| c.len()),
+                error: p.error.clone(),
+                final_url: p.final_url.clone(),
+                ttfb_ms: p.ttfb_ms,
+                elapsed_ms: p.elapsed_ms,
+                final_scheme: p.final_scheme.clone(),
+                redirects: p.redirects.clone(),
+                attempts: p.attempts,
+                truncated: p.truncated,
+                headers: EntryJson::HEADERS
+                    .iter()
+                    .filter_map(|&h| Some((h.to_string(), p.header(h)?.to_string())))
+                    .collect(),
+                challenge: is_bot_challenge(p),
+            })
+            .collect();
+
+        let successes_json: Vec<SuccessJson> = probes
+            .iter()
+            .filter_map(|p| match (p.status, p.content.as_ref()) {
+                (Some(s), Some(c)) if s < 400 => Some(SuccessJson {
+                    url: p.subdomain.clone(),
+                    zone: p.zone.clone(),
+                    content: c.clone(),
+                    final_url: p.final_url.clone(),
+                    content_type: p.header("content-type").map(str::to_string),
+                }),
+                _ => None,
+            })
+            .collect();
+
+        fs::write(
+            out("results.json"),
+            serde_json::to_string_pretty(&results_json)?,
+        )
+        .await?;
+        fs::write(
+            out("successes.json"),
+            serde_json::to_string_pretty(&successes_json)?,
+        )
+        .await?;
+
+        debug!(
+            "Debug: {} ({} entries), {} ({} successes)",
+            out("results.json"),
+            results_json.len(),
+            out("successes.json"),
+            successes_json.len()
+        );
+
+        // Saved pages say nothing about current reachability.
+        let history_file = config
+            .history_file
+            .as_ref()
+            .filter(|_| config.from_dir.is_none());
+        if let Some(path) = history_file {
+            history::append_run(path, &RunRecord::from_probes(run, &probes)).await?;
+
+            if let Some(n) = config.stale_after_runs {
+                let stale = history::stale_subdomains(&history::load_runs(path).await?, n);
+                if !stale.is_empty() {
+                    warn!(
+                        "⚠ {} URL(s) unreachable in each of the last {} runs (stale DNS entries?):",
+                        stale.len(),
+                        n
+                    );
+                    for url in &stale {
+                        warn!("  {}", url);
+                    }
+                }
+            }
+        }
+
+        if let Some(dir) = &config.dump_bodies {
+            match dump_bodies(dir, &probes).await {
+                Ok(written) => debug!("Saved {} page bodies to {}", written, dir.display()),
+                Err(e) => warn!("⚠ Couldn't save page bodies to {}: {}", dir.display(), e),
+            }
+        }
+    }
+
+    // ── Zone statistics ──────────────────────────────────────────────────────
+    let stats = RunStats::from_probes(&probes);
+    if config.stats_only {
+        write_metrics(config, run, &stats, 0, 0, 0).await?;
+        fs::write(out("stats.json"), serde_json::to_string_pretty(&stats)?).await?;
+        info!("\n{}", stats);
+        info!(
+            "Stats written to {} (run {}).",
+            out("stats.json"),
+            run.run_id
+        );
+        return Ok(Vec::new());
+    }
+
+    // ── Ask the LLM about each success ───────────────────────────────────────
+    let probed_count = probes.len();
+    let mut skipped: Vec<SkippedPage> = Vec::new();
+    let skip = |url: &str, reason| SkippedPage {
+        url: url.to_string(),
+        reason,
+    };
+    let (soft_404s, probes): (Vec<ProbeResult>, Vec<ProbeResult>) =
+        probes.into_iter().partition(is_soft_404);
+    skipped.extend(
+        soft_404s
+            .iter()
+            .map(|p| skip(&p.subdomain, SkipReason::Soft404)),
+    );
+    for p in &soft_404s {
+        debug!("{} → soft 404, skipping", p.subdomain);
+    }
+    let (parked, probes): (Vec<ProbeResult>, Vec<ProbeResult>) =
+        probes.into_iter().partition(is_parked);
+    skipped.extend(
+        parked
+            .iter()
+            .map(|p| skip(&p.subdomain, SkipReason::Parked)),
+    );
+    for p in &parked {
+        debug!("{} → parked domain, skipping", p.subdomain);
+    }
+    let (challenges, probes): (Vec<ProbeResult>, Vec<ProbeResult>) =
+        probes.into_iter().partition(is_bot_challenge);
+    skipped.extend(
+        challenges
+            .iter()
+            .map(|p| skip(&p.subdomain, SkipReason::BotChallenge)),
+    );
+    for p in &challenges {
+        debug!("{} → bot-check interstitial, skipping", p.subdomain);
+    }
+    if !challenges.is_empty() {
+        info!(
+            "Skipped {} bot-check interstitial page(s) (marked \"challenge\" in results.json)",
+            challenges.len()
+        );
+    }
+    // JSON APIs, images, and the like would only waste model tokens.
+    let (non_html, probes): (Vec<ProbeResult>, Vec<ProbeResult>) = probes
+        .into_iter()
+        .partition(|p| p.status.is_some_and(|s| s < 400) && p.content.is_some() && !is_html(p));
+    skipped.extend(
+        non_html
+            .iter()
+            .map(|p| skip(&p.subdomain, SkipReason::NotHtml)),
+    );
+    for p in &non_html {
+        debug!(
+            "{} → not HTML ({}), skipping",
+            p.subdomain,
+            p.header("content-type").unwrap_or("no content type")
+        );
+    }
+    if !non_html.is_empty() {
+        debug!("Skipped {} non-HTML response(s)", non_html.len());
+    }
+    // Pages are analyzed under the URL they redirected to, so relative links resolve
+    // against the real page. Several subdomains may land on one page: it is analyzed
+    // once, and every subdomain that sent us there is remembered with its zone.
+    let mut origins: HashMap<String, Vec<(String, Option<String>)>> = HashMap::new();
+    let mut successes: Vec<(String, String)> = Vec::new();
+    for p in probes {
+        let (Some(status), Some(content)) = (p.status, p.content) else {
+            continue;
+        };
+        if status >= 400 {
+            continue;
+        }
+        let page = p.final_url.unwrap_or_else(|| p.subdomain.clone());
+        let sources = origins.entry(page.clone()).or_default();
+        if sources.is_empty() {
+            successes.push((page, content));
+        }
+        sources.push((p.subdomain, p.zone));
+    }
+    // Subdomains listed under `url` (the page itself when it wasn't redirected to).
+    let probed_for = |url: &str| -> Vec<String> {
+        match origins.get(url) {
+            Some(sources) => sources.iter().map(|(probed, _)| probed.clone()).collect(),
+            None => vec![url.to_string()],
+        }
+    };
+
+    let success_count = origins.values().map(Vec::len).sum();
+
+    // ── Evaluate against JSON-LD ─────────────────────────────────────────────
+    if pipeline.eval {
+        run_eval(&client, config, &successes, &out("eval.json")).await?;
+        return Ok(Vec::new());
+    }
+
+    // ── Skip pages unchanged since the last run ──────────────────────────────
+    let mut change_state = match &config.only_changed {
+        Some(path) => {
+            let previous = ChangeState::load(path).await?;
+            let current = ChangeState {
+                // Saved pages carry no zone data; keep the last known entries.
+                entries: if zone_entries.is_empty() {
+                    previous.entries.clone()
+                } else {
+                    zone_entries.clone()
+                },
+                pages: successes
+                    .iter()
+                    .map(|(url, html)| (url.clone(), fingerprint(html)))
+                    .collect(),
+            };
+            Some((path, previous, current))
+        }
+        None => None,
+    };
+    let successes: Vec<(String, String)> = match &change_state {
+        Some((_, previous, current)) => {
+            let (changed, stable): (Vec<_>, Vec<_>) =
+                successes.into_iter().partition(|(url, _)| {
+                    probed_for(url).iter().any(|probed| {
+                        let base = base_url(probed);
+                        let entry = zone_entries.get(&base).map(String::as_str);
+                        previous.changed(&base, entry, url, &current.pages[url])
+                    })
+                });
+            skipped.extend(stable.iter().flat_map(|(url, _)| {
+                probed_for(url)
+                    .into_iter()
+                    .map(|probed| skip(&probed, SkipReason::Unchanged))
+            }));
+            for (url, _) in &stable {
+                debug!("{} → unchanged since last run, skipping", url);
+            }
+            if !stable.is_empty() {
+                info!(
+                    "Skipped {} page(s) unchanged since the last run",
+                    stable.len()
+                );
+            }
+            changed
+        }
+        None => successes,
+    };
+
+    // Pages whose JSON-LD already describes complete events don't need the model.
+    let mut structured: Vec<((String, String), Vec<Hackathon>)> = Vec::new();
+    let successes: Vec<(String, String)> = if config.prefer_json_ld {
+        let mut rest = Vec::new();
+        for (url, html) in successes {
+            let events = jsonld::extract_events(&html, &url);
+            if !events.is_empty() && events.iter().all(jsonld::is_complete) {
+                debug!("{} → {} JSON-LD event(s), skipping LLM", url, events.len());
+                structured.push(((url, html), events));
+            } else {
+                rest.push((url, html));
+            }
+        }
+        if !structured.is_empty() {
+            info!(
+                "Used JSON-LD instead of the LLM for {} page(s)",
+                structured.len()
+            );
+        }
+        rest
+    } else {
+        successes
+    };
+
+    // Cheap pre-filter: pages that never mention an event aren't worth a model call.
+    let successes: Vec<(String, String)> = if config.keyword_filter {
+        let (keep, no_keywords): (Vec<_>, Vec<_>) = successes
+            .into_iter()
+            .partition(|(_, html)| contains_event_keywords(&html::to_text(html), &config.keywords));
+        skipped.extend(no_keywords.iter().flat_map(|(url, _)| {
+            probed_for(url)
+                .into_iter()
+                .map(|probed| skip(&probed, SkipReason::NoEventKeywords))
+        }));
+        for (url, _) in &no_keywords {
+            debug!("{} → no event keywords, skipping", url);
+        }
+        if !no_keywords.is_empty() {
+            info!(
+                "Skipped {} page(s) without event keywords",
+                no_keywords.len()
+            );
+        }
+        keep
+    } else {
+        successes
+    };
+    let page_count = successes.len();
+
+    // ── Dry run: stop before spending API quota ──────────────────────────────
+    if config.dry_run {
+        info!(
+            "Dry run: {} page(s) would be sent to the LLM ({} read from JSON-LD instead); stopping",
+            page_count,
+            structured.len()
+        );
+        return Ok(Vec::new());
+    }
+
+    debug!("Querying LLM for {} successful pages...", page_count);
+
+    let rate_limiter = RateLimiter::new(config.llm_rate_limit_per_minute, config.llm_rate_burst);
+
+    debug!(
+        "Rate limiting to {} requests/minute (bursts of {}) with {} concurrent workers",
+        config.llm_rate_limit_per_minute, config.llm_rate_burst, config.llm_concurrency
+    );
+
+    let progress = Progress::start("Querying LLM        ", page_count, progress_mode);
+    let extractions = extract_all(
+        &client,
+        &config.llm,
+        &rate_limiter,
+        config.llm_concurrency,
+        &successes,
+        |url, result| {
+            // Without progress lines, errors would otherwise go unreported.
+            if progress_mode == ProgressMode::Quiet {
+                if let Err(e) = result {
+                    error!("{} → LLM error: {}", url, e);
+                }
+            }
+            progress.advance(|n, total| {
+                vec![match result {
+                    Ok(h) => format!("[{}/{}] {} → {} hackathon(s) found", n, total, url, h.len()),
+                    Err(e) => format!("[{}/{}] {} → ✗ LLM error: {}", n, total, url, e),
+                }]
+            });
+        },
+    )
+    .instrument(info_span!("llm", pages = page_count))
+    .await;
+
+    let llm_errors = extractions.iter().filter(|(_, r)| r.is_err()).count();
+    let parse_errors = extractions
+        .iter()
+        .filter(|(_, r)| matches!(r, Err(RadarError::Parse { .. })))
+        .count();
+    if let Some((path, _, current)) = &mut change_state {
+        // Pages the LLM failed on stay "changed" so the next run retries them.
+        for (url, _) in extractions.iter().filter(|(_, r)| r.is_err()) {
+            current.pages.remove(url);
+        }
+        current.save(path).await?;
+    }
+    let mut hackathons: Vec<Hackathon> = successes
+        .iter()
+        .zip(extractions)
+        .map(|(page, (_, result))| {
+            let (url, html) = page;
+            let entries = match result {
+                Ok(found) if found.is_empty() && config.meta_fallback => {
+                    let guess = fallback::meta_entry(html, url, &config.keywords);
+                    if guess.is_some() {
+                        debug!(
+                            "{} → no events from the LLM; using its title/description",
+                            url
+                        );
+                    }
+                    guess.into_iter().collect()
+                }
+                result => result.unwrap_or_default(),
+            };
+            (page, entries)
+        })
+        .chain(
+            structured
+                .iter()
+                .map(|(page, events)| (page, events.clone())),
+        )
+        .flat_map(|((url, html), entries)| {
+            let hackathons = finish_page(config, url, html, entries);
+            let sources = origins.get(url).cloned().unwrap_or_default();
+            // One copy per subdomain that led here; `dedupe_hackathons` folds
+            // them back together, keeping the others in `also_from`.
+            sources.into_iter().flat_map(move |(probed, zone)| {
+                let zone = zone.unwrap_or_else(|| config.zone_of(&probed));
+                let mut hackathons = hackathons.clone();
+                for h in &mut hackathons {
+                    h.source = zone.clone();
+                    h.subdomain = Some(base_url(&probed));
+                    if probed != *url {
+                        h.redirected_from = Some(probed.clone());
+                        h.final_url = Some(url.clone());
+                    }
+                }
+                hackathons
+            })
+        })
+        .collect();
+
+    progress.finish();
+
+    // ── Import structured sources ────────────────────────────────────────────
+    for source in &config.extra_sources {
+        match fetch_source(&client, source).await {
+            Ok(found) => {
+                debug!("{:?} → {} hackathon(s)", source, found.len());
+                hackathons.extend(found);
+            }
+            Err(e) => error!("{:?} → ✗ {}", source, e),
+        }
+    }
+
+    if config.dedupe_by_url {
+        let pages: HashSet<String> = successes
+            .iter()
+            .map(|(url, _)| normalize_url(url))
+            .collect();
+        hackathons = merge_by_url(hackathons, &pages);
+    }
+    // Several subdomains may advertise the same event.
+    let before = hackathons.len();
+    hackathons = dedupe_hackathons(hackathons);
+    if hackathons.len() < before {
+        debug!(
+            "Merged {} duplicate hackathon(s) across pages",
+            before - hackathons.len()
+        );
+    }
+    // Imported and merged entries need their dates (re)parsed and rated too.
+    for h in &mut hackathons {
+        fill_dates(h);
+        h.rate_confidence();
+    }
+    if let Some((start, end)) = config.between {
+        filter_date_window(&mut hackathons, start, end);
+    }
+
+    // ── Date subdomains from the DNS repo's history ─────────────────────────
+    if config.with_git_history {
+        match load_git_history(config).await {
+            Ok(history) => {
+                for h in &mut hackathons {
+                    let info = history
+                        .get(&h.source)
+                        .and_then(|zone| zone.get(&subdomain_of(h, &h.source)?));
+                    h.first_added = info.and_then(|i| i.first_added.clone());
+                    h.last_modified = info.and_then(|i| i.last_modified.clone());
+                }
+            }
+            Err(e) => warn!("Git history unavailable: {}", e),
+        }
+    }
+
+    // ── Verify extracted URLs ────────────────────────────────────────────────
+    if config.verify_urls {
+        // A fresh prober without the byte budget: a spent cap would otherwise
+        // report every link as unreachable.
+        let verifier = ProbeCoalescer::new(
+            (*client).clone(),
+            ProbeOptions {
+                retry: config.probe_retry.clone(),
+                max_body_bytes: config.max_body_bytes,
+                breaker: host_breaker(config),
+                ..ProbeOptions::default()
+            },
+        );
+        verify_urls(&verifier, &mut hackathons, config.http_concurrency).await;
+        let dead = hackathons
+            .iter()
+            .filter(|h| h.url_reachable == Some(false))
+            .count();
+        info!("Verified {} URL(s): {} unreachable", hackathons.len(), dead);
+    }
+
+    // ── Write summary ────────────────────────────────────────────────────────
+    // Completion order varies run to run; sort so identical inputs give identical output.
+    sort_hackathons(&mut hackathons);
+    if config.with_git_history {
+        sort_newest_subdomains_first(&mut hackathons);
+    }
+    fs::write(
+        out("summary.json"),
+        serde_json::to_string_pretty(&hackathons)?,
+    )
+    .await?;
+    if config.csv {
+        fs::write(out("summary.csv"), render_csv(&hackathons)).await?;
+    }
+
+    let report = RunReport {
+        run_id: run.run_id.clone(),
+        started_at: run.started_at.to_rfc3339(),
+        probed: probed_count,
+        successes: success_count,
+        hackathons: hackathons.len(),
+    };
+    fs::write(out("report.json"), serde_json::to_string_pretty(&report)?).await?;
+    if config.write_skipped {
+        fs::write(out("skipped.json"), serde_json::to_string_pretty(&skipped)?).await?;
+    }
+    if config.chapters {
+        let chapters = chapter_summaries(&hackathons);
+        fs::write(
+            out("chapters.json"),
+            serde_json::to_string_pretty(&chapters)?,
+        )
+        .await?;
+        info!(
+            "{} organizer(s) found; written to {}",
+            chapters.len(),
+            out("chapters.json")
+        );
+    }
+    if config.format == OutputFormat::Ics {
+        fs::write(
+            out("hackathons.ics"),
+            render_ics(&hackathons, run.started_at),
+        )
+        .await?;
+        let undated: Vec<&Hackathon> = hackathons
+            .iter()
+            .filter(|h| h.start_date.is_none())
+            .collect();
+        info!(
+            "{} event(s) written to {}",
+            hackathons.len() - undated.len(),
+            out("hackathons.ics")
+        );
+        if !undated.is_empty() {
+            info!(
+                "Left {} event(s) without a parseable date out of the calendar",
+                undated.len()
+            );
+            for h in &undated {
+                debug!("  {} ({:?})", h.name, h.dates);
+            }
+        }
+    }
+    write_metrics(
+        config,
+        run,
+        &stats,
+        hackathons.len(),
+        llm_errors,
+        parse_errors,
+    )
+    .await?;
+
+    if llm_errors > 0 {
+        info!(
+            "LLM extraction failed on {} page(s), {} of them with unparseable replies.",
+            llm_errors, parse_errors
+        );
+    }
+
+    Ok(hackathons)
+}
+
+// ── Probing ──────────────────────────────────────────────────────────────────
+
+/// Fetch each DNS zone being scanned and probe every subdomain in them, tagging
+/// each result with its zone.
+///
+/// Also returns each subdomain's zone-entry fingerprint, keyed by base URL, when
+/// `--only-changed` needs them (and an empty map otherwise).
+async fn probe_zone(
+    client: &Client,
+    resolver: &CachingResolver,
+    config: &Config,
+    progress_mode: ProgressMode,
+) -> Result<(Vec<ProbeResult>, BTreeMap<String, String>), RadarError> {
+    // ── Fetch & parse each zone's DNS YAML ──────────────────────────────────
+    let zones = config.zone_urls();
+    let mut entries: BTreeMap<String, String> = BTreeMap::new();
+    let mut listed: Vec<(String, String)> = Vec::new();
+    for (zone, yaml_url) in &zones {
+        // One cache file per zone when several share --yaml-cache.
+        let cache = config.yaml_cache.as_ref().map(|cache| match zones.len() {
+            1 => cache.clone(),
+            _ => zone_cache_path(cache, zone),
+        });
+        let content = load_zone_yaml(client, config, yaml_url, cache.as_deref()).await?;
+        let names = zone_record_names(&content)?;
+        if config.only_changed.is_some() {
+            entries.extend(
+                zone_entry_fingerprints(&content)?
+                    .into_iter()
+                    .map(|(name, fp)| (format!("http://{}.{}", name, zone), fp)),
+            );
+        }
+        listed.extend(
+            names
+                .iter()
+                .filter(|s| !s.is_empty())
+                .map(|s| (format!("http://{}.{}", s, zone), zone.clone())),
+        );
+    }
+
+    let mut seen = HashSet::new();
+    let subdomains: Vec<(String, String)> = listed
+        .into_iter()
+        .filter(|(url, _)| seen.insert(normalize_url(url)))
+        .take(config.limit.unwrap_or(usize::MAX))
+        .collect();
+
+    // ── Reuse recent probes from the cache ──────────────────────────────────
+    let now = Utc::now();
+    let ttl = cache_ttl(config.probe_cache_ttl_hours)?;
+    let mut cache = match &config.probe_cache {
+        Some(path) => Some(ProbeCache::load(path).await.unwrap_or_else(|e| {
+            warn!(
+                "⚠ Ignoring unreadable probe cache {}: {}",
+                path.display(),
+                e
+            );
+            ProbeCache::default()
+        })),
+        None => None,
+    };
+    let mut cached: Vec<ProbeResult> = Vec::new();
+    let mut reused = 0;
+    let subdomains: Vec<(String, String)> = match &cache {
+        Some(cache) => subdomains
+            .into_iter()
+            .filter(|(url, zone)| match cache.fresh(url, ttl, now) {
+                Some(results) => {
+                    reused += 1;
+                    cached.extend(results.into_iter().map(|mut r| {
+                        r.zone = Some(zone.clone());
+                        r
+                    }));
+                    false
+                }
+                None => true,
+            })
+            .collect(),
+        None => subdomains,
+    };
+    if reused > 0 {
+        info!(
+            "Reusing cached probes for {} subdomain(s) from the last {}h (--no-cache to re-probe)",
+            reused, config.probe_cache_ttl_hours
+        );
+    }
+
+    let total = subdomains.len();
+
+    debug!(
+        "Probing {} subdomains (concurrency {})...",
+        total, config.http_concurrency
+    );
+    let progress = Arc::new(Progress::start(
+        "Probing subdomains  ",
+        total,
+        progress_mode,
+    ));
+
+    // ── Probe all subdomains concurrently ────────────────────────────────────
+    let extra_paths = Arc::new(config.extra_paths.clone());
+    let probe_delay = Duration::from_millis(config.probe_delay_ms);
+    let byte_budget = config.max_total_bytes.map(|n| Arc::new(ByteBudget::new(n)));
+    let mut timeout_overrides = HashMap::new();
+    if let Some(path) = &config.timeout_overrides_file {
+        let raw = fs::read_to_string(path).await?;
+        for (zone, _) in &zones {
+            timeout_overrides.extend(parse_timeout_overrides(&raw, zone)?);
+        }
+    }
+    let clients = match &config.proxies_file {
+        Some(path) => {
+            let proxies = parse_proxy_list(&fs::read_to_string(path).await?);
+            if proxies.is_empty() {
+                return Err(RadarError::Config(format!(
+                    "no proxies listed in {}",
+                    path.display()
+                )));
+            }
+            info!("Rotating probes across {} proxies", proxies.len());
+            ClientPool::new(build_proxy_clients(config, resolver, &proxies)?)
+        }
+        None => ClientPool::new(vec![build_probe_client(config, resolver)?]),
+    };
+    let public_only = config.skip_private.then(|| PublicOnly {
+        resolver: resolver.clone(),
+        overrides: config.resolve.clone(),
+    });
+    let prober = Arc::new(ProbeCoalescer::with_pool(
+        clients,
+        ProbeOptions {
+            retry: config.probe_retry.clone(),
+            max_body_bytes: config.max_body_bytes,
+            byte_budget: byte_budget.clone(),
+            timeout_overrides,
+            breaker: host_breaker(config),
+            redirect_rules: RedirectRules::from_config(config),
+            https_first: config.https_first,
+            host_limit: host_limit(config, resolver),
+            public_only: public_only.clone(),
+            robots: config
+                .respect_robots
+                .then(|| Arc::new(RobotsCache::new(config.dns_cache_size))),
+        },
+    ));
+    let probed = stream::iter(subdomains)
+        // Stop handing out new hosts once the download cap is spent.
+        .take_while(|_| {
+            let open = !byte_budget.as_ref().is_some_and(|b| b.exhausted());
+            futures::future::ready(open)
+        })
+        .map(|(url, zone)| {
+            let prober = Arc::clone(&prober);
+            let progress = Arc::clone(&progress);
+            let extra_paths = Arc::clone(&extra_paths);
+            let public_only = public_only.clone();
+            async move {
+                let private = match (&public_only, Url::parse(&url)) {
+                    (Some(guard), Ok(parsed)) => guard.blocked(&parsed).await,
+                    _ => None,
+                };
+
+                let mut results = match private {
+                    Some(ip) => vec![ProbeResult::error(
+                        url.clone(),
+                        format!("skipped: resolves to non-public address {}", ip),
+                    )],
+                    None => {
+                        catch_probe_panic(
+                            &url,
+                            probe_paths(&prober, &url, &extra_paths, probe_delay),
+                        )
+                        .await
+                    }
+                };
+                progress.advance(|n, total| {
+                    results
+                        .iter()
+                        .map(|r| {
+                            let url = &r.subdomain;
+                            match (&r.status, &r.content, &r.error) {
+                                (Some(s), Some(c), _) => {
+                                    let cut = if r.truncated { " (truncated)" } else { "" };
+                                    format!("[{}/{}] {} → {} {}b{}", n, total, url, s, c.len(), cut)
+                                }
+                                (_, _, Some(e)) => format!("[{}/{}] {} → ✗ {}", n, total, url, e),
+                                _ => format!("[{}/{}] {} → ✗ unknown", n, total, url),
+                            }
+                        })
+                        .collect()
+                });
+
+                for r in &mut results {
+                    r.zone = Some(zone.clone());
+                }
+                (url, results)
+            }
+        })
+        .buffer_unordered(config.http_concurrency)
+        .collect::<Vec<(String, Vec<ProbeResult>)>>()
+        .await;
+
+    if let (Some(cache), Some(path)) = (&mut cache, &config.probe_cache) {
+        cache.prune(ttl, now);
+        for (url, results) in &probed {
+            cache.record(url, results, now);
+        }
+        cache.save(path).await?;
+    }
+    let probes: Vec<ProbeResult> = cached
+        .into_iter()
+        .chain(probed.into_iter().flat_map(|(_, results)| results))
+        .collect();
+
+    progress.finish();
+
+    if let Some(budget) = byte_budget.as_ref().filter(|b| b.exhausted()) {
+        info!(
+            "Download cap reached after {} bytes; stopped probing at {}/{} subdomains.",
+            budget.used(),
+            progress.done(),
+            total
+        );
+    }
+
+    Ok((probes, entries))
+}
+
+/// Await the probes for `url`, recording a panic as an error result for it so
+/// the rest of the scan goes on.
+async fn catch_probe_panic(
+    url: &str,
+    probes: impl Future<Output = Vec<ProbeResult>>,
+) -> Vec<ProbeResult> {
+    AssertUnwindSafe(probes)
+        .catch_unwind()
+        .await
+        .unwrap_or_else(|p| {
+            vec![ProbeResult::error(
+                url,
+                format!("probe panicked: {}", panic_message(&*p)),
+            )]
+        })
+}
+
+/// Fetch one zone's DNS YAML, through `cache` when `--yaml-cache` is set.
+async fn load_zone_yaml(
+    client: &Client,
+    config: &Config,
+    yaml_url: &str,
+    cache: Option<&Path>,
+) -> Result<String, RadarError> {
+    debug!("Fetching YAML from: {}", yaml_url);
+    let Some(cache) = cache else {
+        return fetch_zone(client, yaml_url, config.max_yaml_bytes, &config.yaml_retry).await;
+    };
+    let (yaml, source) = fetch_zone_cached(
+        client,
+        yaml_url,
+        config.max_yaml_bytes,
+        cache,
+        &config.yaml_retry,
+    )
+    .await?;
+    match source {
+        ZoneSource::Fresh => debug!("DNS YAML updated; cached in {}", cache.display()),
+        ZoneSource::NotModified => debug!("DNS YAML unchanged; using {}", cache.display()),
+        ZoneSource::Stale(e) => warn!(
+            "⚠ DNS YAML fetch failed ({}); using cached {}",
+            e,
+            cache.display()
+        ),
+    }
+    Ok(yaml)
+}
+
+/// `--cache-ttl` as a duration, or a config error if it is too long to represent.
+fn cache_ttl(hours: u64) -> Result<chrono::Duration, RadarError> {
+    i64::try_from(hours)
+        .ok()
+        .and_then(chrono::Duration::try_hours)
+        .ok_or_else(|| RadarError::Config(format!("--cache-ttl {} is too long", hours)))
+}
+
+/// `zones.yaml` → `zones-hackclub.io.yaml`: where one zone is cached when
+/// `--yaml-cache` is shared by several.
+fn zone_cache_path(cache: &Path, zone: &str) -> PathBuf {
+    let stem = cache.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match cache.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, zone, ext.to_string_lossy()),
+        None => format!("{}-{}", stem, zone),
+    };
+    cache.with_file_name(name)
+}
+
+/// A fresh per-host breaker, unless `--host-failure-limit 0` disabled it.
+fn host_breaker(config: &Config) -> Option<Arc<HostBreaker>> {
+    (config.host_failure_limit > 0).then(|| Arc::new(HostBreaker::new(config.host_failure_limit)))
+}
+
+/// Per-origin probe pacing, unless `--host-rate-limit` is 0 (the default).
+fn host_limit(config: &Config, resolver: &CachingResolver) -> Option<HostLimit> {
+    (config.host_rate_limit_per_minute > 0).then(|| HostLimit {
+        // A burst of one spaces each origin's requests evenly.
+        limiter: Arc::new(HostRateLimiter::new(config.host_rate_limit_per_minute, 1)),
+        resolver: resolver.clone(),
+        overrides: config.resolve.clone(),
+    })
+}
+
+/// Clone or update the DNS repository and read the history of each scanned
+/// zone's file, keyed by zone. Records the log walk leaves undated are dated
+/// with `git blame`.
+async fn load_git_history(
+    config: &Config,
+) -> Result<HashMap<String, HashMap<String, GitInfo>>, RadarError> {
+    debug!(
+        "Syncing {} into {}",
+        DNS_REPO_URL,
+        config.dns_repo_dir.display()
+    );
+    let dir = config.dns_repo_dir.clone();
+    // Zone files sit at the repository root under the name they are served as.
+    let zones: Vec<(String, PathBuf)> = config
+        .zone_urls()
+        .into_iter()
+        .map(|(zone, url)| {
+            let path = PathBuf::from(url.rsplit('/').next().unwrap_or_default());
+            (zone, path)
+        })
+        .collect();
+    tokio::task::spawn_blocking(move || {
+        sync_dns_repo(DNS_REPO_URL, &dir)?;
+        zones
+            .into_iter()
+            .map(|(zone, path)| {
+                let mut history = get_yaml_git_history(&path, &dir)?;
+                let filled = backfill_with_blame(&path, &dir, &mut history)?;
+                if filled > 0 {
+                    debug!("{}: dated {} record(s) with git blame", zone, filled);
+                }
+                Ok((zone, history))
+            })
+            .collect()
+    })
+    .await?
+}
+
+/// The zone record a hackathon came from: the label of its probed subdomain
+/// (`foo` for `http://foo.hackclub.com`), if that host is in `zone`. Entries
+/// from other sources fall back to their own URL.
+fn subdomain_of(h: &Hackathon, zone: &str) -> Option<String> {
+    let url = h.subdomain.as_deref().unwrap_or(&h.url);
+    let host = Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+    host.strip_suffix(zone)?
+        .strip_suffix('.')
+        .filter(|label| !label.is_empty())
+        .map(str::to_string)
+}
+
+/// `http://foo.hackclub.com/events` → `http://foo.hackclub.com`.
+fn base_url(url: &str) -> String {
+    Url::parse(url)
+        .map(|u| u.origin().ascii_serialization())
+        .unwrap_or_else(|_| url.to_string())
+}
+
+// ── Metrics ──────────────────────────────────────────────────────────────────
+
+/// Write `--metrics-file`, if requested.
+async fn write_metrics(
+    config: &Config,
+    run: &RunInfo,
+    stats: &RunStats,
+    hackathons_found: usize,
+    llm_errors: usize,
+    llm_parse_errors: usize,
+) -> std::io::Result<()> {
+    let Some(path) = &config.metrics_file else {
+        return Ok(());
+    };
+    let elapsed = Utc::now() - run.started_at;
+    let metrics = RunMetrics {
+        stats: stats.clone(),
+        hackathons_found,
+        llm_errors,
+        llm_parse_errors,
+        run_duration_seconds: elapsed.num_milliseconds() as f64 / 1000.0,
+    };
+    fs::write(path, metrics.to_prometheus()).await
+}
+
+// ── Evaluation ───────────────────────────────────────────────────────────────
+
+/// Per-page result of `eval`.
+#[derive(Serialize)]
+struct PageEval {
+    url: String,
+    #[serde(flatten)]
+    agreement: Agreement,
+    error: Option<String>,
+}
+
+/// Run the LLM over pages that carry JSON-LD events and score it against them.
+async fn run_eval(
+    client: &Client,
+    config: &Config,
+    pages: &[(String, String)],
+    report_path: &str,
+) -> Result<(), RadarError> {
+    let (pages, references): (Vec<(String, String)>, Vec<Vec<Hackathon>>) = pages
+        .iter()
+        .filter_map(|(url, html)| {
+            let events = jsonld::extract_events(html, url);
+            (!events.is_empty()).then(|| ((url.clone(), html.clone()), events))
+        })
+        .unzip();
+
+    info!(
+        "Evaluating the LLM on {} page(s) with JSON-LD events...",
+        pages.len()
+    );
+    let rate_limiter = RateLimiter::new(config.llm_rate_limit_per_minute, config.llm_rate_burst);
+    let extractions = extract_all(
+        client,
+        &config.llm,
+        &rate_limiter,
+        config.llm_concurrency,
+        &pages,
+        |_, _| {},
+    )
+    .await;
+
+    let mut total = Agreement::default();
+    let mut per_page = Vec::with_capacity(pages.len());
+    for ((url, result), reference) in extractions.into_iter().zip(&references) {
+        let (agreement, error) = match result {
+            Ok(extracted) => (
+                eval::compare(reference, &merge_page_entries(&url, extracted)),
+                None,
+            ),
+            Err(e) => (eval::compare(reference, &[]), Some(e.to_string())),
+        };
+        total.add(&agreement);
+        per_page.push(PageEval {
+            url,
+            agreement,
+            error,
+        });
+    }
+
+    let report = serde_json::json!({
+        "pages": per_page,
+        "total": total,
+        "precision": total.precision(),
+        "recall": total.recall(),
+    });
+    fs::write(report_path, serde_json::to_string_pretty(&report)?).await?;
+
+    info!(
+        "Matched {} of {} reference event(s); {} extracted. Precision {:.2}, recall {:.2}. Details in {}.",
+        total.matched,
+        total.reference,
+        total.extracted,
+        total.precision(),
+        total.recall(),
+        report_path
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn a_panicking_probe_is_recorded_and_the_others_complete() {
+        let urls = ["http://a.test", "http://boom.test", "http://c.test"];
+        let results = futures::future::join_all(urls.iter().map(|url| {
+            catch_probe_panic(url, async move {
+                if url.contains("boom") {
+                    panic!("deliberate");
+                }
+                vec![ProbeResult::loaded(*url, "<p>ok</p>")]
+            })
+        }))
+        .await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0][0].status, Some(200));
+        assert_eq!(results[1][0].subdomain, "http://boom.test");
+        assert_eq!(results[1][0].status, None);
+        assert_eq!(
+            results[1][0].error.as_deref(),
+            Some("probe panicked: deliberate")
+        );
+        assert_eq!(results[2][0].status, Some(200));
+    }
+
+    #[test]
+    fn cache_ttl_rejects_hours_too_long_to_represent() {
+        assert_eq!(cache_ttl(24).unwrap(), chrono::Duration::hours(24));
+        for hours in [u64::MAX, i64::MAX as u64, 1 << 62] {
+            let err = cache_ttl(hours).unwrap_err();
+            assert!(matches!(err, RadarError::Config(_)), "{:?}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_api_key_is_a_config_error() {
+        let pipeline = PipelineConfig::new(Config::default());
+
+        let err = run_pipeline(pipeline).await.unwrap_err();
+
+        assert!(matches!(err, RadarError::Config(_)), "{:?}", err);
+    }
+}
@@ -1,10 +1,376 @@
 //! HTTP probing functionality for fetching and analyzing DNS subdomains.
 
-use crate::types::ProbeResult;
-use reqwest::Client;
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::future::{BoxFuture, FutureExt, Shared};
+use futures::stream::{self, StreamExt};
+
+use crate::config::{Config, ResolveOverride, MAX_BODY_BYTES};
+use crate::error::RadarError;
+use crate::html;
+use crate::ratelimit::HostRateLimiter;
+use crate::resolve::{CachingResolver, PublicOnly};
+use crate::retry::{Attempt, RetryPolicy};
+use crate::robots::RobotsCache;
+use crate::types::{Hackathon, ProbeResult};
+use reqwest::header::{HeaderMap, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::{Client, ClientBuilder, Proxy, Response, Url};
+
+/// Build the HTTP client used for the YAML fetch and all probes.
+///
+/// Applies the configured timeout, `resolver` (the per-run DNS cache), and any
+/// `--resolve` DNS overrides (which take precedence over the resolver).
+pub fn build_client(config: &Config, resolver: &CachingResolver) -> reqwest::Result<Client> {
+    client_builder(config, resolver).build()
+}
+
+/// Build one probe client per proxy URL, each configured like [`build_client`]
+/// but sending every request through its proxy.
+///
+/// # Arguments
+/// * `config` - Timeout and `--resolve` settings shared by every client
+/// * `resolver` - Per-run DNS cache
+/// * `proxies` - Proxy URLs (`http://` or `https://`)
+///
+/// # Returns
+/// The clients in `proxies` order, or an error for the first invalid proxy URL
+pub fn build_proxy_clients(
+    config: &Config,
+    resolver: &CachingResolver,
+    proxies: &[String],
+) -> reqwest::Result<Vec<Client>> {
+    proxies
+        .iter()
+        .map(|proxy| {
+            client_builder(config, resolver)
+                .redirect(Policy::none())
+                .proxy(Proxy::all(proxy)?)
+                .build()
+        })
+        .collect()
+}
+
+/// Build the client used for probes: [`build_client`] without automatic
+/// redirects, so each probe follows (and records) them hop by hop.
+pub fn build_probe_client(config: &Config, resolver: &CachingResolver) -> reqwest::Result<Client> {
+    client_builder(config, resolver)
+        .redirect(Policy::none())
+        .build()
+}
+
+/// Redirects followed before giving up, as with reqwest's default policy.
+const MAX_REDIRECTS: usize = 10;
+
+/// Which redirects a probe follows (`--redirect-allowlist`).
+///
+/// With an empty allowlist every redirect is followed. Otherwise redirects
+/// within the scanned zones (or to the same host) are followed, and redirects to
+/// other hosts only when [`host_allowed`].
+#[derive(Debug, Clone, Default)]
+pub struct RedirectRules {
+    /// Allowed external hosts; empty allows all.
+    pub allowlist: Vec<String>,
+    /// Zones whose hosts are always allowed (e.g. `hackclub.com`).
+    pub zones: Vec<String>,
+}
+
+impl RedirectRules {
+    /// Rules taken from `--redirect-allowlist` and the scanned zones.
+    pub fn from_config(config: &Config) -> Self {
+        RedirectRules {
+            allowlist: config.redirect_allowlist.clone(),
+            zones: config
+                .zone_urls()
+                .into_iter()
+                .map(|(zone, _)| zone.to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Whether a redirect from `from` to `to` may be followed.
+    pub fn allows(&self, from: &Url, to: &Url) -> bool {
+        if self.allowlist.is_empty() {
+            return true;
+        }
+        let host = to.host_str().unwrap_or("").to_ascii_lowercase();
+        let internal = self.zones.iter().any(|zone| {
+            !zone.is_empty() && (host == *zone || host.ends_with(&format!(".{}", zone)))
+        }) || from
+            .host_str()
+            .is_some_and(|f| f.eq_ignore_ascii_case(&host));
+        internal || host_allowed(&host, &self.allowlist)
+    }
+}
+
+/// Whether `host` matches a `--redirect-allowlist` entry.
+///
+/// `devpost.com` matches that host and `www.devpost.com`; `*.mlh.io` matches
+/// `mlh.io` and any host under it.
+pub fn host_allowed<S: AsRef<str>>(host: &str, allowlist: &[S]) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowlist.iter().any(|entry| {
+        let entry = entry.as_ref().trim().to_ascii_lowercase();
+        match entry.strip_prefix("*.") {
+            Some(suffix) => host == suffix || host.ends_with(&format!(".{}", suffix)),
+            None => host == entry || host.strip_prefix("www.") == Some(entry.as_str()),
+        }
+    })
+}
+
+/// Proxy URLs listed in a `--proxies` file: one per line, blank lines and
+/// `#` comments ignored.
+pub fn parse_proxy_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+fn client_builder(config: &Config, resolver: &CachingResolver) -> ClientBuilder {
+    let mut builder = Client::builder()
+        .timeout(Duration::from_secs(config.request_timeout_secs))
+        .dns_resolver(Arc::new(resolver.clone()));
+
+    for r in &config.resolve {
+        // Port 0 keeps the scheme's (or URL's) port.
+        builder = builder.resolve(&r.host, SocketAddr::new(r.addr, 0));
+    }
+
+    builder
+}
+
+/// Clients handed out round-robin, so probes spread across several egress proxies.
+#[derive(Debug)]
+pub struct ClientPool {
+    clients: Vec<Client>,
+    next: AtomicUsize,
+}
+
+impl ClientPool {
+    /// A pool rotating through `clients`.
+    ///
+    /// # Panics
+    /// If `clients` is empty.
+    pub fn new(clients: Vec<Client>) -> Self {
+        assert!(!clients.is_empty(), "client pool needs at least one client");
+        ClientPool {
+            clients,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// The client for the next request.
+    pub fn pick(&self) -> &Client {
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.clients.len();
+        &self.clients[i]
+    }
+
+    /// Number of clients in the rotation.
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Always `false`; a pool can't be built empty.
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+}
+
+/// Shared cap on the total body bytes downloaded by a run.
+#[derive(Debug)]
+pub struct ByteBudget {
+    limit: u64,
+    used: AtomicU64,
+}
+
+impl ByteBudget {
+    /// Allow up to `limit` bytes in total.
+    pub fn new(limit: u64) -> Self {
+        ByteBudget {
+            limit,
+            used: AtomicU64::new(0),
+        }
+    }
+
+    /// Record `n` downloaded bytes, returning `false` once the cap has been crossed.
+    pub fn consume(&self, n: u64) -> bool {
+        self.used.fetch_add(n, Ordering::Relaxed) + n <= self.limit
+    }
+
+    /// Whether the cap has already been reached.
+    pub fn exhausted(&self) -> bool {
+        self.used() >= self.limit
+    }
+
+    /// Bytes downloaded so far.
+    pub fn used(&self) -> u64 {
+        self.used.load(Ordering::Relaxed)
+    }
+}
+
+/// Per-host circuit breaker: after `limit` consecutive failed requests to a host,
+/// the rest of the run's requests to it are refused without being sent.
+///
+/// Hosts are keyed by authority (`host:port`). Only failures without a response
+/// (timeouts, refused connections) count; any response resets the count.
+#[derive(Debug)]
+pub struct HostBreaker {
+    limit: usize,
+    failures: Mutex<HashMap<String, usize>>,
+}
+
+impl HostBreaker {
+    /// Open a host's circuit after `limit` consecutive failures.
+    pub fn new(limit: usize) -> Self {
+        HostBreaker {
+            limit,
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether requests to `url`'s host are being short-circuited.
+    pub fn is_open(&self, url: &str) -> bool {
+        let failures = self.failures.lock().unwrap();
+        failures
+            .get(&authority(url))
+            .is_some_and(|&n| n >= self.limit)
+    }
+
+    /// Record whether a request to `url` got a response.
+    pub fn record(&self, url: &str, responded: bool) {
+        let mut failures = self.failures.lock().unwrap();
+        let count = failures.entry(authority(url)).or_insert(0);
+        *count = if responded { 0 } else { *count + 1 };
+    }
+}
+
+/// `http://Foo.hackclub.com/events` → `foo.hackclub.com:80`.
+fn authority(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(u) => format!(
+            "{}:{}",
+            u.host_str().unwrap_or("").to_ascii_lowercase(),
+            u.port_or_known_default().unwrap_or(0)
+        ),
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Per-request behaviour shared by every probe in a run.
+#[derive(Debug, Clone, Default)]
+pub struct ProbeOptions {
+    /// Retry count, backoff, and retryable statuses.
+    pub retry: RetryPolicy,
+    /// Most bytes read from one response body (0 = no cap); longer bodies are
+    /// cut and marked `truncated`.
+    pub max_body_bytes: usize,
+    /// Run-wide download cap; bodies stop being read once it is spent.
+    pub byte_budget: Option<Arc<ByteBudget>>,
+    /// Per-host request timeouts that replace the client's default (e.g. for
+    /// known-slow hosts), keyed by lowercase host name.
+    pub timeout_overrides: HashMap<String, Duration>,
+    /// Run-wide per-host breaker for hosts that keep failing.
+    pub breaker: Option<Arc<HostBreaker>>,
+    /// Which redirects to follow.
+    pub redirect_rules: RedirectRules,
+    /// Try `https://` first for `http://` URLs, falling back to the original
+    /// only when the HTTPS request gets no response (connection or TLS error).
+    pub https_first: bool,
+    /// Per-origin pacing applied before every request, redirects included.
+    pub host_limit: Option<HostLimit>,
+    /// Refuse redirects to non-public addresses (`--skip-private`).
+    pub public_only: Option<PublicOnly>,
+    /// Skip URLs, redirects included, that their host's `robots.txt` disallows
+    /// (`--respect-robots`).
+    pub robots: Option<Arc<RobotsCache>>,
+}
+
+/// Per-origin request pacing for probes (`--host-rate-limit`).
+///
+/// Requests are keyed by the address their host resolves to, so subdomains
+/// served by one backend (GitHub Pages, Netlify) share a limit; hosts that
+/// don't resolve are keyed by name.
+#[derive(Debug, Clone)]
+pub struct HostLimit {
+    /// Buckets shared by every probe in the run.
+    pub limiter: Arc<HostRateLimiter>,
+    /// Resolver used to find each host's address.
+    pub resolver: CachingResolver,
+    /// `--resolve` pins, which win over the resolver.
+    pub overrides: Vec<ResolveOverride>,
+}
+
+impl HostLimit {
+    /// Wait for a token for the origin serving `url`.
+    pub async fn acquire(&self, url: &str) {
+        let Some(host) = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+        else {
+            return;
+        };
+        let pinned = self
+            .overrides
+            .iter()
+            .find(|r| r.host.eq_ignore_ascii_case(&host));
+        let key = match pinned {
+            Some(r) => r.addr.to_string(),
+            // The smallest address keeps the key stable when DNS rotates records.
+            None => match self.resolver.lookup(&host).await {
+                Ok(addrs) => addrs
+                    .iter()
+                    .map(|a| a.ip())
+                    .min()
+                    .map_or(host, |ip| ip.to_string()),
+                Err(_) => host,
+            },
+        };
+        self.limiter.acquire_for(&key).await;
+    }
+}
+
+impl ProbeOptions {
+    /// The timeout override for `url`'s host, if one is configured.
+    pub fn timeout_for(&self, url: &str) -> Option<Duration> {
+        let host = Url::parse(url).ok()?.host_str()?.to_ascii_lowercase();
+        self.timeout_overrides.get(&host).copied()
+    }
+}
+
+/// Parse a timeout override file: a YAML map of subdomain to seconds, e.g.
+/// `slow-site: 60`.
+///
+/// # Arguments
+/// * `yaml` - File contents
+/// * `zone` - Zone the subdomains belong to (e.g. `hackclub.com`)
+///
+/// # Returns
+/// Timeouts keyed by full host name (`slow-site.hackclub.com`)
+pub fn parse_timeout_overrides(
+    yaml: &str,
+    zone: &str,
+) -> Result<HashMap<String, Duration>, RadarError> {
+    let raw: HashMap<String, u64> = serde_yaml::from_str(yaml)?;
+    Ok(raw
+        .into_iter()
+        .map(|(sub, secs)| {
+            let host = format!("{}.{}", sub.to_ascii_lowercase(), zone);
+            (host, Duration::from_secs(secs))
+        })
+        .collect())
+}
 
 /// Probe a single URL and return the result.
 ///
+/// An `http://` URL is tried over HTTPS first, falling back to plain HTTP on a
+/// connection or TLS error; `final_scheme` records which one answered.
+///
 /// # Arguments
 /// * `client` - HTTP client to use for the request
 /// * `url` - URL to probe
@@ -12,29 +378,778 @@ use reqwest::Client;
 /// # Returns
 /// A `ProbeResult` containing status code, content, and/or error information
 pub async fn probe(client: &Client, url: &str) -> ProbeResult {
-    match client.get(url).send().await {
-        Ok(resp) => {
-            let status = resp.status().as_u16();
-            match resp.text().await {
-                Ok(body) => ProbeResult {
-                    subdomain: url.to_string(),
-                    status: Some(status),
-                    content: Some(body),
-                    error: None,
-                },
-                Err(e) => ProbeResult {
-                    subdomain: url.to_string(),
-                    status: Some(status),
-                    content: None,
-                    error: Some(e.to_string()),
-                },
+    let opts = ProbeOptions {
+        https_first: true,
+        max_body_bytes: MAX_BODY_BYTES,
+        ..ProbeOptions::default()
+    };
+    attempt_schemes(client, url, &opts).await.0
+}
+
+/// Probe a URL, retrying transient failures with exponential backoff.
+///
+/// Connection errors and timeouts are always retried; responses are retried only
+/// when their status is in `opts.retry.retry_statuses` (429 and 5xx by default).
+///
+/// # Arguments
+/// * `client` - HTTP client to use for the request
+/// * `url` - URL to probe
+/// * `opts` - Retry policy and download limits
+///
+/// # Returns
+/// The result of the last attempt, with `attempts` counting every request made
+pub async fn probe_with_retries(client: &Client, url: &str, opts: &ProbeOptions) -> ProbeResult {
+    let policy = &opts.retry;
+    let mut retry = 0;
+    loop {
+        let (mut result, transient) = attempt_schemes(client, url, opts).await;
+        let retryable = transient || result.status.is_some_and(|s| policy.retries_status(s));
+
+        if !retryable || retry >= policy.max_retries {
+            if result.attempts > 0 {
+                result.attempts = retry + 1;
             }
+            return result;
+        }
+        tokio::time::sleep(policy.delay(retry)).await;
+        retry += 1;
+    }
+}
+
+/// [`attempt`] over HTTPS first when `opts.https_first` is set and `url` is
+/// `http://`, then over HTTP if the HTTPS connection couldn't be made (refused,
+/// or the TLS handshake failed).
+///
+/// A timeout or any answer over HTTPS is kept as the result: the host is
+/// there, and a second full timeout over HTTP would only double the wait. The
+/// result keeps `url` as its `subdomain` whichever scheme answered.
+///
+/// # Returns
+/// The result, and whether its failure looks transient
+async fn attempt_schemes(client: &Client, url: &str, opts: &ProbeOptions) -> (ProbeResult, bool) {
+    let https = match url.strip_prefix("http://") {
+        Some(rest) if opts.https_first => format!("https://{}", rest),
+        _ => {
+            let (result, failure) = attempt(client, url, opts).await;
+            return (result, failure.is_transient());
+        }
+    };
+    let (mut result, failure) = attempt(client, &https, opts).await;
+    if failure != Failure::Connect {
+        result.subdomain = url.to_string();
+        return (result, failure.is_transient());
+    }
+    let (result, failure) = attempt(client, url, opts).await;
+    (result, failure.is_transient())
+}
+
+/// How an [`attempt`] went, for deciding whether to retry or switch scheme.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    /// A response was read, or the attempt failed in a way retrying won't fix.
+    Permanent,
+    /// No connection could be made: refused, unresolvable, or a failed TLS
+    /// handshake.
+    Connect,
+    /// Worth retrying, such as a timeout.
+    Transient,
+}
+
+impl Failure {
+    fn is_transient(self) -> bool {
+        self != Failure::Permanent
+    }
+}
+
+/// Make one request, classifying how it failed.
+async fn attempt(client: &Client, url: &str, opts: &ProbeOptions) -> (ProbeResult, Failure) {
+    let budget = opts.byte_budget.as_deref();
+    let breaker = opts.breaker.as_deref();
+    let skipped = if budget.is_some_and(ByteBudget::exhausted) {
+        Some("skipped: total download cap reached")
+    } else if breaker.is_some_and(|b| b.is_open(url)) {
+        Some("host circuit open: too many consecutive failures")
+    } else {
+        None
+    };
+    if let Some(reason) = skipped {
+        return (ProbeResult::error(url, reason), Failure::Permanent);
+    }
+
+    let started = Instant::now();
+    let millis = |since: Instant| Some(since.elapsed().as_millis() as u64);
+    let failed = |error: String, status: Option<u16>, redirects: Vec<String>| ProbeResult {
+        status,
+        elapsed_ms: millis(started),
+        redirects,
+        attempts: 1,
+        ..ProbeResult::error(url, error)
+    };
+
+    // Redirects are followed here rather than by reqwest so every hop is recorded
+    // and checked against the redirect rules.
+    let mut current = url.to_string();
+    let mut redirects = Vec::new();
+    let resp = loop {
+        if let (Some(robots), Ok(target)) = (&opts.robots, Url::parse(&current)) {
+            if !robots.allows(client, &target).await {
+                let error = format!("skipped: {} is disallowed by robots.txt", current);
+                // On the first hop nothing was requested from the URL itself.
+                let result = if redirects.is_empty() {
+                    ProbeResult::error(url, error)
+                } else {
+                    failed(error, None, redirects)
+                };
+                return (result, Failure::Permanent);
+            }
+        }
+        if let Some(limit) = &opts.host_limit {
+            limit.acquire(&current).await;
+        }
+        let mut request = client.get(&current);
+        if let Some(timeout) = opts.timeout_for(url) {
+            request = request.timeout(timeout);
+        }
+        let sent = request.send().await;
+        if let Some(breaker) = breaker {
+            breaker.record(&current, sent.is_ok());
+        }
+        let resp = match sent {
+            Ok(resp) => resp,
+            Err(e) => {
+                // Only the first hop decides whether the host speaks HTTPS.
+                let failure = if e.is_connect() && redirects.is_empty() {
+                    Failure::Connect
+                } else if opts.retry.is_retryable(Attempt::Error(&e)) {
+                    Failure::Transient
+                } else {
+                    Failure::Permanent
+                };
+                return (failed(e.to_string(), None, redirects), failure);
+            }
+        };
+
+        let next = resp
+            .status()
+            .is_redirection()
+            .then(|| resp.headers().get(LOCATION))
+            .flatten()
+            .and_then(|v| v.to_str().ok())
+            .and_then(|location| resp.url().join(location).ok());
+        let Some(next) = next else {
+            break resp;
+        };
+        let status = Some(resp.status().as_u16());
+        if !opts.redirect_rules.allows(resp.url(), &next) {
+            let error = format!(
+                "redirect to {} not followed (host not in --redirect-allowlist)",
+                next
+            );
+            return (failed(error, status, redirects), Failure::Permanent);
         }
+        if let Some(ip) = match &opts.public_only {
+            Some(guard) => guard.blocked(&next).await,
+            None => None,
+        } {
+            let error = format!(
+                "redirect to {} not followed (resolves to non-public address {})",
+                next, ip
+            );
+            return (failed(error, status, redirects), Failure::Permanent);
+        }
+        if redirects.len() >= MAX_REDIRECTS {
+            return (
+                failed("too many redirects".to_string(), status, redirects),
+                Failure::Permanent,
+            );
+        }
+        current = next.to_string();
+        redirects.push(current.clone());
+    };
+
+    // Headers of the final response are in; the body streams afterwards.
+    let ttfb_ms = millis(started);
+    let status = resp.status().as_u16();
+    let final_url = Some(resp.url().to_string()).filter(|u| normalize_url(u) != normalize_url(url));
+    let final_scheme = Some(resp.url().scheme().to_string());
+    let headers = header_map(resp.headers());
+    let result = match read_body(resp, opts.max_body_bytes, budget).await {
+        Ok((body, truncated)) => ProbeResult {
+            status: Some(status),
+            content: Some(body),
+            final_url,
+            ttfb_ms,
+            elapsed_ms: millis(started),
+            final_scheme,
+            redirects,
+            attempts: 1,
+            headers: headers.clone(),
+            truncated,
+            ..ProbeResult::new(url)
+        },
+        // The connection succeeded but the body couldn't be read or decoded
+        // (truncated stream, bad encoding); keep that distinct from network errors.
         Err(e) => ProbeResult {
-            subdomain: url.to_string(),
-            status: None,
-            content: None,
-            error: Some(e.to_string()),
+            status: Some(status),
+            final_url,
+            ttfb_ms,
+            elapsed_ms: millis(started),
+            final_scheme,
+            redirects,
+            attempts: 1,
+            headers,
+            ..ProbeResult::error(url, format!("decode error: {}", e))
         },
+    };
+    (result, Failure::Permanent)
+}
+
+/// Flatten response headers into lowercase name → value, joining repeats.
+fn header_map(headers: &HeaderMap) -> HashMap<String, String> {
+    let mut map: HashMap<String, String> = HashMap::new();
+    for (name, value) in headers {
+        let Ok(value) = value.to_str() else { continue };
+        map.entry(name.as_str().to_string())
+            .and_modify(|v| {
+                v.push_str(", ");
+                v.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+    map
+}
+
+/// Stream a response body, stopping after `max_bytes` (0 = no cap) or when the
+/// run's byte budget runs out.
+///
+/// # Returns
+/// The body read so far, and whether the rest of it was left unread
+async fn read_body(
+    mut resp: Response,
+    max_bytes: usize,
+    budget: Option<&ByteBudget>,
+) -> reqwest::Result<(String, bool)> {
+    let mut body = Vec::new();
+    let mut truncated = false;
+    while let Some(mut chunk) = resp.chunk().await? {
+        if max_bytes > 0 && body.len() + chunk.len() > max_bytes {
+            chunk.truncate(max_bytes - body.len());
+            truncated = true;
+        }
+        body.extend_from_slice(&chunk);
+        if budget.is_some_and(|b| !b.consume(chunk.len() as u64)) {
+            truncated = true;
+        }
+        if truncated {
+            break;
+        }
+    }
+    Ok((String::from_utf8_lossy(&body).into_owned(), truncated))
+}
+
+type SharedProbe = Shared<BoxFuture<'static, ProbeResult>>;
+
+/// Probes URLs while collapsing concurrent requests for the same resource.
+///
+/// When several callers ask for URLs that normalize to the same value (see
+/// [`normalize_url`]) while a request is already in flight, they all await that
+/// one request instead of issuing their own.
+pub struct ProbeCoalescer {
+    clients: ClientPool,
+    opts: Arc<ProbeOptions>,
+    in_flight: Mutex<HashMap<String, SharedProbe>>,
+}
+
+impl ProbeCoalescer {
+    /// Create a coalescer that probes with `client` using `opts`.
+    pub fn new(client: Client, opts: ProbeOptions) -> Self {
+        Self::with_pool(ClientPool::new(vec![client]), opts)
+    }
+
+    /// Create a coalescer that takes each new request's client from `clients`.
+    pub fn with_pool(clients: ClientPool, opts: ProbeOptions) -> Self {
+        ProbeCoalescer {
+            clients,
+            opts: Arc::new(opts),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Probe `url` (with retries), joining an identical in-flight request if any.
+    pub async fn probe(&self, url: &str) -> ProbeResult {
+        let key = normalize_url(url);
+        let shared = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            in_flight
+                .entry(key.clone())
+                .or_insert_with(|| {
+                    let client = self.clients.pick().clone();
+                    let opts = Arc::clone(&self.opts);
+                    let url = url.to_string();
+                    async move { probe_with_retries(&client, &url, &opts).await }
+                        .boxed()
+                        .shared()
+                })
+                .clone()
+        };
+
+        let result = shared.clone().await;
+        self.finish(&key, &shared);
+        result
+    }
+
+    /// Forget the in-flight request for `key` once `done` has finished.
+    ///
+    /// Every waiter calls this, and by the time a late one does, a later caller may
+    /// already have started a new request for the same URL; that one is left alone.
+    fn finish(&self, key: &str, done: &SharedProbe) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight.get(key).is_some_and(|f| f.ptr_eq(done)) {
+            in_flight.remove(key);
+        }
+    }
+}
+
+/// Probe `base_url` and then each of `paths` on the same host.
+///
+/// Paths are requested one after another, `delay` apart, so a single host never
+/// sees a burst; concurrency comes from probing different hosts in parallel.
+///
+/// # Arguments
+/// * `prober` - Coalescing prober used for every request
+/// * `base_url` - Root URL of the host (e.g. `http://foo.hackclub.com`)
+/// * `paths` - Extra paths to try after the root (e.g. `/events`)
+/// * `delay` - Pause before each extra path
+///
+/// # Returns
+/// One `ProbeResult` per URL, root first
+pub async fn probe_paths(
+    prober: &ProbeCoalescer,
+    base_url: &str,
+    paths: &[String],
+    delay: Duration,
+) -> Vec<ProbeResult> {
+    let mut results = Vec::with_capacity(paths.len() + 1);
+    let mut seen = HashSet::new();
+    seen.insert(normalize_url(base_url));
+    results.push(prober.probe(base_url).await);
+
+    for path in paths {
+        let url = format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        );
+        if seen.insert(normalize_url(&url)) {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            results.push(prober.probe(&url).await);
+        }
+    }
+
+    results
+}
+
+/// Probe each hackathon's `url` and set `url_reachable` to whether it returned 2xx.
+///
+/// Runs at most `concurrency` requests at once; hackathons sharing a URL (after
+/// [`normalize_url`]) are checked with a single request.
+///
+/// # Arguments
+/// * `prober` - Coalescing prober used for every request
+/// * `hackathons` - Entries to check, updated in place
+/// * `concurrency` - Maximum requests in flight
+pub async fn verify_urls(
+    prober: &ProbeCoalescer,
+    hackathons: &mut [Hackathon],
+    concurrency: usize,
+) {
+    // The coalescer only joins requests still in flight, so repeats that would
+    // start after the first finished are dropped here.
+    let mut seen = HashSet::new();
+    let distinct: Vec<(String, &str)> = hackathons
+        .iter()
+        .map(|h| (normalize_url(&h.url), h.url.as_str()))
+        .filter(|(key, _)| seen.insert(key.clone()))
+        .collect();
+    let reachable: HashMap<String, bool> = stream::iter(distinct)
+        .map(|(key, url)| async move {
+            let result = prober.probe(url).await;
+            (key, result.status.is_some_and(|s| (200..300).contains(&s)))
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+
+    for h in hackathons.iter_mut() {
+        h.url_reachable = reachable.get(&normalize_url(&h.url)).copied();
+    }
+}
+
+/// Phrases that mark a "not found" page.
+const NOT_FOUND_MARKERS: [&str; 6] = [
+    "404",
+    "not found",
+    "does not exist",
+    "doesn't exist",
+    "no such page",
+    "page unavailable",
+];
+
+/// Visible text shorter than this is checked for not-found markers too, not just the title.
+const SOFT_404_MAX_TEXT_CHARS: usize = 600;
+
+/// Whether a successful-looking response is really a "page not found" page.
+///
+/// Many hosts answer missing paths with a 200 and a generic error page. A 2xx
+/// response counts as a soft 404 when its `<title>` contains a not-found marker,
+/// or when its visible text is short and contains one. Long pages that merely
+/// mention "404" somewhere are not flagged.
+pub fn is_soft_404(result: &ProbeResult) -> bool {
+    let (Some(status), Some(content)) = (result.status, result.content.as_deref()) else {
+        return false;
+    };
+    if !(200..300).contains(&status) {
+        return false;
+    }
+
+    let has_marker = |text: &str| {
+        let text = text.to_lowercase();
+        NOT_FOUND_MARKERS.iter().any(|m| text.contains(m))
+    };
+    if html::title(content).is_some_and(|t| has_marker(&t)) {
+        return true;
+    }
+    let text = html::to_text(content);
+    text.chars().count() < SOFT_404_MAX_TEXT_CHARS && has_marker(&text)
+}
+
+/// Phrases and parking-service names that mark a parked domain.
+const PARKED_MARKERS: [&str; 8] = [
+    "domain is for sale",
+    "domain may be for sale",
+    "buy this domain",
+    "domain is parked",
+    "parked free",
+    "sedoparking",
+    "parkingcrew",
+    "bodis.com",
+];
+
+/// Whether a successful-looking response is a domain-parking page.
+///
+/// An expired or never-configured subdomain often answers with a registrar's
+/// "this domain is for sale" page. A 2xx response counts as parked when its
+/// markup mentions a parking phrase or service; such pages are short and say
+/// nothing about events, so a long page that happens to contain one isn't flagged.
+pub fn is_parked(result: &ProbeResult) -> bool {
+    let (Some(status), Some(content)) = (result.status, result.content.as_deref()) else {
+        return false;
+    };
+    if !(200..300).contains(&status)
+        || html::to_text(content).chars().count() >= SOFT_404_MAX_TEXT_CHARS
+    {
+        return false;
+    }
+    let lower = content.to_lowercase();
+    PARKED_MARKERS.iter().any(|m| lower.contains(m))
+}
+
+/// Markup only bot-check interstitials carry (Cloudflare and similar).
+///
+/// Cloudflare injects `/cdn-cgi/challenge-platform/` scripts into ordinary
+/// pages too, so that isn't one.
+const CHALLENGE_MARKERS: [&str; 2] = ["cf-browser-verification", "cf_chl_opt"];
+
+/// Page titles used by bot-check interstitials.
+const CHALLENGE_TITLES: [&str; 3] = ["just a moment...", "attention required!", "ddos-guard"];
+
+/// Statuses bot checks answer with while the visitor is unverified.
+const CHALLENGE_STATUSES: [u16; 2] = [403, 503];
+
+/// Whether a response is a bot-check interstitial rather than the site itself.
+///
+/// Cloudflare and similar services answer with a 403 or 503 "Just a moment..."
+/// or "Attention Required!" page until a browser passes their check. Both the
+/// status and a challenge title or marker are required, so a real page that
+/// mentions them isn't caught. Those pages say nothing about the site, so they
+/// are reported instead of being extracted.
+pub fn is_bot_challenge(result: &ProbeResult) -> bool {
+    if !result
+        .status
+        .is_some_and(|s| CHALLENGE_STATUSES.contains(&s))
+    {
+        return false;
+    }
+    let Some(content) = result.content.as_deref() else {
+        return false;
+    };
+    if html::title(content).is_some_and(|t| {
+        let t = t.to_lowercase();
+        CHALLENGE_TITLES.iter().any(|m| t.starts_with(m))
+    }) {
+        return true;
+    }
+    let lower = content.to_lowercase();
+    CHALLENGE_MARKERS.iter().any(|m| lower.contains(m))
+}
+
+/// Media types extracted from; everything else is skipped as non-HTML.
+const HTML_TYPES: [&str; 2] = ["text/html", "application/xhtml+xml"];
+
+/// Whether a response body is an HTML page worth extracting from.
+///
+/// Decided by the `Content-Type` header (`text/html` or `application/xhtml+xml`);
+/// without one, the body counts as HTML if it starts with `<!DOCTYPE` or `<html`.
+pub fn is_html(result: &ProbeResult) -> bool {
+    if let Some(content_type) = result.header("content-type") {
+        let mime = content_type.split(';').next().unwrap_or("").trim();
+        return HTML_TYPES.iter().any(|t| mime.eq_ignore_ascii_case(t));
+    }
+    let Some(content) = result.content.as_deref() else {
+        return false;
+    };
+    let start = content.trim_start_matches('\u{feff}').trim_start();
+    let head = start.get(..9).unwrap_or(start).to_ascii_lowercase();
+    head.starts_with("<!doctype") || head.starts_with("<html")
+}
+
+/// Canonical form of a URL used to spot duplicate probes.
+///
+/// Lowercases the scheme and host, drops default ports, and removes trailing
+/// slashes, so `HTTP://Foo.hackclub.com:80/` becomes `http://foo.hackclub.com`.
+/// Unparseable input is returned unchanged.
+pub fn normalize_url(url: &str) -> String {
+    let Ok(parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+
+    let mut out = format!("{}://{}", parsed.scheme(), parsed.host_str().unwrap_or(""));
+    if let Some(port) = parsed.port() {
+        out.push_str(&format!(":{}", port));
+    }
+    out.push_str(parsed.path().trim_end_matches('/'));
+    if let Some(query) = parsed.query() {
+        out.push('?');
+        out.push_str(query);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(status: u16, content: &str) -> ProbeResult {
+        ProbeResult {
+            status: Some(status),
+            ..ProbeResult::loaded("http://foo.hackclub.com", content)
+        }
+    }
+
+    const CLOUDFLARE_INTERSTITIAL: &str = "<!DOCTYPE html><html><head><title>Just a moment...</title></head>\
+        <body><script>window._cf_chl_opt={cvId:'3'};</script>\
+        <script src=\"/cdn-cgi/challenge-platform/h/b/orchestrate/chl_page/v1\"></script></body></html>";
+
+    #[test]
+    fn cloudflare_interstitial_is_a_challenge() {
+        assert!(is_bot_challenge(&page(403, CLOUDFLARE_INTERSTITIAL)));
+        assert!(is_bot_challenge(&page(
+            503,
+            "<div id=\"cf-browser-verification\">Checking your browser</div>"
+        )));
+    }
+
+    #[test]
+    fn normal_page_is_not_a_challenge() {
+        let normal = "<html><head><title>Hack Night</title></head><body>Join us!</body>\
+            <script src=\"/cdn-cgi/challenge-platform/scripts/jsd/main.js\"></script></html>";
+        assert!(!is_bot_challenge(&page(200, normal)));
+        assert!(!is_bot_challenge(&page(403, normal)));
+    }
+
+    #[test]
+    fn challenge_markup_on_a_200_is_not_a_challenge() {
+        assert!(!is_bot_challenge(&page(200, CLOUDFLARE_INTERSTITIAL)));
+    }
+
+    #[test]
+    fn timeout_overrides_are_keyed_by_full_host() {
+        let overrides =
+            parse_timeout_overrides("Slow-Site: 60\nfast: 2\n", "hackclub.com").unwrap();
+        let opts = ProbeOptions {
+            timeout_overrides: overrides,
+            ..ProbeOptions::default()
+        };
+
+        assert_eq!(
+            opts.timeout_for("http://slow-site.hackclub.com/events"),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            opts.timeout_for("https://FAST.hackclub.com"),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(opts.timeout_for("http://other.hackclub.com"), None);
+        assert!(parse_timeout_overrides("slow: soon", "hackclub.com").is_err());
+    }
+
+    #[test]
+    fn not_found_pages_served_with_200_are_soft_404s() {
+        assert!(is_soft_404(&page(
+            200,
+            "<html><head><title>Page Not Found</title></head><body>Sorry!</body></html>"
+        )));
+        assert!(is_soft_404(&page(
+            200,
+            "<html><body><h1>Oops</h1><p>This page does not exist.</p></body></html>"
+        )));
+    }
+
+    #[test]
+    fn genuine_pages_are_not_soft_404s() {
+        let event = "<html><head><title>HackFoo 2026</title></head><body>\
+            <h1>HackFoo</h1><p>Join us March 1–2 for a weekend of building.</p></body></html>";
+        assert!(!is_soft_404(&page(200, event)));
+
+        // A long page that mentions 404 in passing.
+        let long = format!(
+            "<html><head><title>Hack Night</title></head><body>{}<p>Error 404 jokes welcome.</p></body></html>",
+            "<p>We build things every Friday at the library.</p>".repeat(20)
+        );
+        assert!(!is_soft_404(&page(200, &long)));
+
+        // A real 404 is already a failure, not a soft one.
+        assert!(!is_soft_404(&page(404, "<title>Not Found</title>")));
+    }
+
+    #[test]
+    fn registrar_for_sale_pages_are_parked() {
+        assert!(is_parked(&page(
+            200,
+            "<html><head><title>foo.hackclub.com</title></head><body>This domain is for sale!</body></html>"
+        )));
+        assert!(is_parked(&page(
+            200,
+            "<html><body><script src=\"https://www.sedoparking.com/js/p.js\"></script></body></html>"
+        )));
+
+        let long = format!(
+            "<html><body>{}<p>Buy this domain? No, it's our club's.</p></body></html>",
+            "<p>We build things every Friday at the library.</p>".repeat(20)
+        );
+        assert!(!is_parked(&page(200, &long)));
+        assert!(!is_parked(&page(404, "This domain is for sale")));
+    }
+
+    #[test]
+    fn proxy_list_skips_blank_lines_and_comments() {
+        let text = "# egress pool\nhttp://10.0.0.1:3128\n\n  https://proxy.example:8443  \n# http://old:3128\n";
+        assert_eq!(
+            parse_proxy_list(text),
+            ["http://10.0.0.1:3128", "https://proxy.example:8443"]
+        );
+    }
+
+    #[test]
+    fn allowlist_entries_match_hosts_and_wildcard_suffixes() {
+        let allowlist = ["devpost.com", "*.mlh.io"];
+        for host in ["devpost.com", "www.DevPost.com", "mlh.io", "events.mlh.io."] {
+            assert!(host_allowed(host, &allowlist), "{}", host);
+        }
+        for host in [
+            "hackfoo.devpost.com",
+            "notdevpost.com",
+            "bit.ly",
+            "mlh.io.evil.test",
+        ] {
+            assert!(!host_allowed(host, &allowlist), "{}", host);
+        }
+    }
+
+    #[test]
+    fn redirects_within_the_zones_are_always_followed() {
+        let rules = RedirectRules {
+            allowlist: vec!["devpost.com".to_string()],
+            zones: vec!["hackclub.com".to_string()],
+        };
+        let from = Url::parse("https://foo.hackclub.com/").unwrap();
+        let to = |url: &str| Url::parse(url).unwrap();
+        assert!(rules.allows(&from, &to("https://bar.hackclub.com/")));
+        assert!(rules.allows(&from, &to("https://devpost.com/hackfoo")));
+        assert!(!rules.allows(&from, &to("https://bit.ly/x")));
+        assert!(RedirectRules::default().allows(&from, &to("https://bit.ly/x")));
+    }
+
+    #[test]
+    fn breaker_opens_per_authority_and_a_response_resets_it() {
+        let breaker = HostBreaker::new(2);
+        breaker.record("http://foo.hackclub.com/a", false);
+        breaker.record("http://foo.hackclub.com/b", true);
+        breaker.record("http://FOO.hackclub.com/c", false);
+        assert!(!breaker.is_open("http://foo.hackclub.com/"));
+
+        breaker.record("http://foo.hackclub.com:80/d", false);
+        assert!(breaker.is_open("http://foo.hackclub.com/anything"));
+        assert!(!breaker.is_open("http://foo.hackclub.com:8080/"));
+        assert!(!breaker.is_open("https://foo.hackclub.com/"));
+        assert!(!breaker.is_open("http://bar.hackclub.com/"));
+    }
+
+    #[tokio::test]
+    async fn a_late_waiter_does_not_forget_a_newer_request() {
+        let coalescer = ProbeCoalescer::new(Client::new(), ProbeOptions::default());
+        let probe = |url: &str| -> SharedProbe {
+            let result = ProbeResult::new(url);
+            async move { result }.boxed().shared()
+        };
+        let key = normalize_url("http://foo.hackclub.com");
+        let stale = probe("http://foo.hackclub.com");
+        let newer = probe("http://foo.hackclub.com");
+        coalescer
+            .in_flight
+            .lock()
+            .unwrap()
+            .insert(key.clone(), newer.clone());
+
+        coalescer.finish(&key, &stale);
+        assert!(coalescer.in_flight.lock().unwrap()[&key].ptr_eq(&newer));
+
+        coalescer.finish(&key, &newer);
+        assert!(coalescer.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn url_variants_normalize_to_one_form() {
+        for url in [
+            "http://foo.hackclub.com",
+            "http://foo.hackclub.com/",
+            "http://FOO.HackClub.com/",
+            "HTTP://foo.hackclub.com:80/",
+        ] {
+            assert_eq!(normalize_url(url), "http://foo.hackclub.com", "{}", url);
+        }
+        assert_eq!(
+            normalize_url("https://foo.hackclub.com:443/events/"),
+            "https://foo.hackclub.com/events"
+        );
+    }
+
+    #[test]
+    fn normalization_keeps_what_distinguishes_urls() {
+        assert_eq!(
+            normalize_url("http://foo.hackclub.com:8080/"),
+            "http://foo.hackclub.com:8080"
+        );
+        assert_eq!(
+            normalize_url("http://foo.hackclub.com/Events?page=2"),
+            "http://foo.hackclub.com/Events?page=2"
+        );
+        assert_ne!(
+            normalize_url("http://foo.hackclub.com"),
+            normalize_url("https://foo.hackclub.com")
+        );
+        assert_eq!(normalize_url("not a url"), "not a url");
     }
 }
@@ -0,0 +1,223 @@
+//! Probe results saved between runs, so reachable subdomains probed recently
+//! can be reused instead of fetched again.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::error::RadarError;
+use crate::types::ProbeResult;
+
+/// Saved probe results, keyed by the subdomain URL that was probed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProbeCache {
+    #[serde(default)]
+    pub hosts: BTreeMap<String, CachedHost>,
+}
+
+/// Everything one subdomain's probe returned (its root and any extra paths).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CachedHost {
+    /// When the subdomain was last actually probed.
+    pub probed_at: DateTime<Utc>,
+    pub results: Vec<CachedProbe>,
+}
+
+/// The parts of a [`ProbeResult`] worth keeping between runs.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CachedProbe {
+    pub url: String,
+    pub status: Option<u16>,
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_scheme: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redirects: Vec<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+}
+
+impl From<&ProbeResult> for CachedProbe {
+    fn from(p: &ProbeResult) -> Self {
+        CachedProbe {
+            url: p.subdomain.clone(),
+            status: p.status,
+            content: p.content.clone(),
+            final_url: p.final_url.clone(),
+            final_scheme: p.final_scheme.clone(),
+            redirects: p.redirects.clone(),
+            headers: p.headers.clone(),
+            truncated: p.truncated,
+        }
+    }
+}
+
+impl CachedProbe {
+    /// Rebuild the probe result; `attempts` is 0 since nothing was requested.
+    pub fn to_probe(&self) -> ProbeResult {
+        ProbeResult {
+            status: self.status,
+            content: self.content.clone(),
+            final_url: self.final_url.clone(),
+            final_scheme: self.final_scheme.clone(),
+            redirects: self.redirects.clone(),
+            headers: self.headers.clone(),
+            truncated: self.truncated,
+            ..ProbeResult::new(self.url.clone())
+        }
+    }
+}
+
+impl ProbeCache {
+    /// Read the cache saved at `path`; a missing file is an empty cache.
+    pub async fn load(path: &Path) -> Result<Self, RadarError> {
+        match fs::read_to_string(path).await {
+            Ok(text) => Ok(serde_json::from_str(&text)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the cache to `path`, replacing any previous one.
+    pub async fn save(&self, path: &Path) -> Result<(), RadarError> {
+        fs::write(path, serde_json::to_string(self)?).await?;
+        Ok(())
+    }
+
+    /// The cached results for `url`, if it was probed less than `ttl` before `now`.
+    pub fn fresh(&self, url: &str, ttl: Duration, now: DateTime<Utc>) -> Option<Vec<ProbeResult>> {
+        let host = self.hosts.get(url)?;
+        (now - host.probed_at < ttl)
+            .then(|| host.results.iter().map(CachedProbe::to_probe).collect())
+    }
+
+    /// Remember what probing `url` at `now` returned.
+    ///
+    /// Only reachable subdomains (some result below 400 with a body) are kept;
+    /// anything else is dropped so the next run probes it again.
+    pub fn record(&mut self, url: &str, results: &[ProbeResult], now: DateTime<Utc>) {
+        let reachable = results
+            .iter()
+            .any(|r| r.content.is_some() && r.status.is_some_and(|s| s < 400));
+        if reachable {
+            let results = results.iter().map(CachedProbe::from).collect();
+            self.hosts.insert(
+                url.to_string(),
+                CachedHost {
+                    probed_at: now,
+                    results,
+                },
+            );
+        } else {
+            self.hosts.remove(url);
+        }
+    }
+
+    /// Drop entries probed `ttl` or longer before `now`.
+    pub fn prune(&mut self, ttl: Duration, now: DateTime<Utc>) {
+        self.hosts.retain(|_, host| now - host.probed_at < ttl);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn reachable(url: &str) -> ProbeResult {
+        ProbeResult {
+            status: Some(200),
+            content: Some("<h1>HackFoo</h1>".to_string()),
+            ..ProbeResult::new(url)
+        }
+    }
+
+    #[test]
+    fn entries_are_fresh_until_the_ttl_has_passed() {
+        let url = "http://foo.hackclub.com";
+        let mut cache = ProbeCache::default();
+        cache.record(url, &[reachable(url)], now());
+        let ttl = Duration::hours(24);
+
+        let just_before = now() + ttl - Duration::seconds(1);
+        let hit = cache.fresh(url, ttl, just_before).unwrap();
+        assert_eq!(hit[0].content.as_deref(), Some("<h1>HackFoo</h1>"));
+        assert_eq!(hit[0].attempts, 0);
+        assert!(cache.fresh(url, ttl, now() + ttl).is_none());
+        assert!(cache.fresh("http://bar.hackclub.com", ttl, now()).is_none());
+
+        cache.prune(ttl, just_before);
+        assert_eq!(cache.hosts.len(), 1);
+        cache.prune(ttl, now() + ttl);
+        assert!(cache.hosts.is_empty());
+    }
+
+    #[test]
+    fn an_unreachable_result_evicts_the_entry() {
+        let url = "http://foo.hackclub.com";
+        let mut cache = ProbeCache::default();
+        cache.record(url, &[reachable(url)], now());
+
+        let down = ProbeResult {
+            status: Some(503),
+            content: Some("busy".to_string()),
+            ..ProbeResult::new(url)
+        };
+        cache.record(url, &[down, ProbeResult::error(url, "timed out")], now());
+
+        assert!(cache.hosts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn save_and_load_keep_every_cached_field() {
+        let dir = std::env::temp_dir().join(format!("radar-probe-cache-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("probes.json");
+        let url = "http://foo.hackclub.com";
+        let probe = ProbeResult {
+            final_url: Some("https://devpost.example/hackfoo".to_string()),
+            final_scheme: Some("https".to_string()),
+            redirects: vec!["https://foo.hackclub.com/".to_string()],
+            headers: HashMap::from([("content-type".to_string(), "text/html".to_string())]),
+            truncated: true,
+            ..reachable(url)
+        };
+        let mut cache = ProbeCache::default();
+        cache.record(url, &[probe], now());
+
+        cache.save(&path).await.unwrap();
+        let loaded = ProbeCache::load(&path).await.unwrap();
+
+        let hit = &loaded.fresh(url, Duration::hours(1), now()).unwrap()[0];
+        assert_eq!(hit.subdomain, url);
+        assert_eq!(hit.status, Some(200));
+        assert_eq!(
+            hit.final_url.as_deref(),
+            Some("https://devpost.example/hackfoo")
+        );
+        assert_eq!(hit.final_scheme.as_deref(), Some("https"));
+        assert_eq!(hit.redirects, ["https://foo.hackclub.com/"]);
+        assert_eq!(
+            hit.headers.get("content-type").map(String::as_str),
+            Some("text/html")
+        );
+        assert!(hit.truncated);
+        assert!(ProbeCache::load(&dir.join("missing.json"))
+            .await
+            .unwrap()
+            .hosts
+            .is_empty());
+    }
+}
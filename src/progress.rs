@@ -0,0 +1,136 @@
+//! Progress output shared by concurrent tasks.
+
+use std::io::Write;
+use std::sync::Mutex;
+
+/// How much progress output to print.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// One `debug!` event per finished item.
+    Verbose,
+    /// A single `label n/total` counter rewritten in place.
+    Counter,
+    /// Nothing.
+    Quiet,
+}
+
+impl ProgressMode {
+    /// Pick the mode for the `--verbose`/`--quiet` flags.
+    pub fn from_flags(verbose: bool, quiet: bool) -> Self {
+        if verbose {
+            ProgressMode::Verbose
+        } else if quiet {
+            ProgressMode::Quiet
+        } else {
+            ProgressMode::Counter
+        }
+    }
+}
+
+/// A `n/total` progress reporter that many tasks can update at once.
+///
+/// Each update bumps the count and prints while holding one lock, so lines from
+/// different tasks never interleave and the printed counts always increase.
+#[derive(Debug)]
+pub struct Progress {
+    label: String,
+    total: usize,
+    mode: ProgressMode,
+    done: Mutex<usize>,
+}
+
+impl Progress {
+    /// Create a reporter for `total` items and print the initial counter.
+    pub fn start(label: &str, total: usize, mode: ProgressMode) -> Self {
+        let progress = Progress {
+            label: label.to_string(),
+            total,
+            mode,
+            done: Mutex::new(0),
+        };
+        if mode == ProgressMode::Counter {
+            let mut out = std::io::stdout().lock();
+            let _ = write!(out, "{}0/{}", progress.label, total);
+            let _ = out.flush();
+        }
+        progress
+    }
+
+    /// Mark one item finished.
+    ///
+    /// In verbose mode `lines` is called with the new count and total, and each
+    /// returned line is logged at debug level; otherwise it is never called.
+    pub fn advance<F>(&self, lines: F)
+    where
+        F: FnOnce(usize, usize) -> Vec<String>,
+    {
+        let mut done = self.done.lock().unwrap_or_else(|e| e.into_inner());
+        *done += 1;
+        match self.mode {
+            ProgressMode::Verbose => {
+                for line in lines(*done, self.total) {
+                    tracing::debug!("{}", line);
+                }
+            }
+            ProgressMode::Counter => {
+                let mut out = std::io::stdout().lock();
+                let _ = write!(out, "\r{}{}/{}", self.label, *done, self.total);
+                let _ = out.flush();
+            }
+            ProgressMode::Quiet => {}
+        }
+    }
+
+    /// End the counter's line so later output starts on a fresh one.
+    pub fn finish(&self) {
+        if self.mode == ProgressMode::Counter {
+            println!();
+        }
+    }
+
+    /// Items finished so far.
+    pub fn done(&self) -> usize {
+        *self.done.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn concurrent_advances_report_each_count_once_in_order() {
+        const TASKS: usize = 16;
+        const EACH: usize = 50;
+        let progress = Arc::new(Progress::start("", TASKS * EACH, ProgressMode::Verbose));
+        let lines = Arc::new(Mutex::new(Vec::new()));
+
+        let handles: Vec<_> = (0..TASKS)
+            .map(|task| {
+                let progress = Arc::clone(&progress);
+                let lines = Arc::clone(&lines);
+                std::thread::spawn(move || {
+                    for _ in 0..EACH {
+                        progress.advance(|n, total| {
+                            let line = format!("[{}/{}] task {}", n, total, task);
+                            lines.lock().unwrap().push(line.clone());
+                            vec![line]
+                        });
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let lines = lines.lock().unwrap();
+        assert_eq!(progress.done(), TASKS * EACH);
+        assert_eq!(lines.len(), TASKS * EACH);
+        for (i, line) in lines.iter().enumerate() {
+            let prefix = format!("[{}/{}] task ", i + 1, TASKS * EACH);
+            assert!(line.starts_with(&prefix), "{:?} at {}", line, i);
+        }
+    }
+}
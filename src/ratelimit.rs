@@ -1,16 +1,39 @@
 //! Rate limiting functionality for API requests.
 
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
 use tokio::time::interval;
 
+/// Highest rate the command line accepts, in requests per minute (1000 a second).
+pub const MAX_RATE_PER_MINUTE: u32 = 60_000;
+
 /// A token bucket rate limiter that respects request rate limits.
+///
+/// The bucket starts full with `burst` tokens and gains one every `60 / rpm`
+/// seconds, never holding more than `burst`. Each request spends one token.
+#[derive(Debug)]
 pub struct RateLimiter {
     /// Semaphore that represents available tokens (request slots).
     semaphore: Arc<Semaphore>,
     /// Maximum number of requests per minute.
     requests_per_minute: u32,
+    /// Most tokens the bucket holds, i.e. requests allowed back to back.
+    burst: u32,
+    /// The refill task, shared by all clones and aborted when the last one drops.
+    refill: Arc<RefillTask>,
+}
+
+/// Aborts the refill task when dropped.
+#[derive(Debug)]
+struct RefillTask(JoinHandle<()>);
+
+impl Drop for RefillTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
 }
 
 impl RateLimiter {
@@ -18,52 +41,172 @@ impl RateLimiter {
     ///
     /// # Arguments
     /// * `requests_per_minute` - Maximum number of requests allowed per minute
+    /// * `burst` - Requests allowed immediately before the rate applies (at least 1)
     ///
     /// # Example
     /// ```ignore
-    /// let limiter = RateLimiter::new(40); // Allow 40 requests per minute
-    /// let permit = limiter.acquire().await;
+    /// let limiter = RateLimiter::new(40, 4); // 40 requests per minute, 4 at once
+    /// limiter.acquire().await;
     /// // Make request...
-    /// drop(permit); // Release permit
     /// ```
-    pub fn new(requests_per_minute: u32) -> Self {
-        let limiter = RateLimiter {
-            semaphore: Arc::new(Semaphore::new(1)),
-            requests_per_minute,
-        };
+    pub fn new(requests_per_minute: u32, burst: u32) -> Self {
+        let burst = burst.max(1);
+        let semaphore = Arc::new(Semaphore::new(burst as usize));
 
         // Start background task to refill tokens
-        let semaphore = Arc::clone(&limiter.semaphore);
+        let refill_semaphore = Arc::clone(&semaphore);
         let rpm = requests_per_minute;
 
-        tokio::spawn(async move {
-            let tokens_per_second = rpm as f64 / 60.0;
-            let mut interval = interval(Duration::from_millis((1000.0 / tokens_per_second) as u64));
+        let handle = tokio::spawn(async move {
+            let mut interval = interval(refill_period(rpm));
+            // The first tick fires immediately, but the bucket starts full.
+            interval.tick().await;
 
             loop {
                 interval.tick().await;
-                // Add a permit if semaphore is below max
-                if semaphore.available_permits() < rpm as usize {
-                    semaphore.add_permits(1);
+                // Add a permit if the bucket is below capacity
+                if refill_semaphore.available_permits() < burst as usize {
+                    refill_semaphore.add_permits(1);
                 }
             }
         });
 
-        limiter
+        RateLimiter {
+            semaphore,
+            requests_per_minute,
+            burst,
+            refill: Arc::new(RefillTask(handle)),
+        }
     }
 
-    /// Acquire a permit to make a request.
-    /// This will block until a permit is available.
-    pub async fn acquire(&self) -> tokio::sync::SemaphorePermit<'_> {
-        self.semaphore.acquire().await.unwrap()
+    /// Take a token to make a request.
+    /// This will block until a token is available; it is not given back.
+    pub async fn acquire(&self) {
+        self.semaphore.acquire().await.unwrap().forget();
     }
 }
 
+/// Time between refills: `60 / rpm` seconds.
+///
+/// Computed in nanoseconds so high rates don't round to a zero period (which
+/// `interval` rejects); a rate of 0 is treated as 1.
+fn refill_period(requests_per_minute: u32) -> Duration {
+    Duration::from_nanos((60_000_000_000 / u64::from(requests_per_minute.max(1))).max(1))
+}
+
 impl Clone for RateLimiter {
     fn clone(&self) -> Self {
         RateLimiter {
             semaphore: Arc::clone(&self.semaphore),
             requests_per_minute: self.requests_per_minute,
+            burst: self.burst,
+            refill: Arc::clone(&self.refill),
+        }
+    }
+}
+
+/// Separate token buckets per host, so one busy origin is throttled without
+/// slowing requests to the others.
+///
+/// Each host gets its own [`RateLimiter`] the first time it is seen; hosts are
+/// compared case-insensitively.
+#[derive(Debug)]
+pub struct HostRateLimiter {
+    /// Rate given to each host's bucket.
+    requests_per_minute: u32,
+    /// Capacity of each host's bucket.
+    burst: u32,
+    /// Buckets created so far, keyed by lowercase host.
+    limiters: Mutex<HashMap<String, RateLimiter>>,
+}
+
+impl HostRateLimiter {
+    /// Create a limiter that allows each host `requests_per_minute`, with bursts
+    /// of up to `burst`.
+    pub fn new(requests_per_minute: u32, burst: u32) -> Self {
+        HostRateLimiter {
+            requests_per_minute,
+            burst,
+            limiters: Mutex::new(HashMap::new()),
         }
     }
+
+    /// Take a token from `host`'s bucket, creating the bucket if needed.
+    /// This will block until that host has a token available.
+    pub async fn acquire_for(&self, host: &str) {
+        let limiter = self
+            .limiters
+            .lock()
+            .unwrap()
+            .entry(host.to_ascii_lowercase())
+            .or_insert_with(|| RateLimiter::new(self.requests_per_minute, self.burst))
+            .clone();
+        limiter.acquire().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn refill_period_never_rounds_to_zero() {
+        assert_eq!(refill_period(60), Duration::from_secs(1));
+        assert_eq!(refill_period(0), Duration::from_secs(60));
+        assert_eq!(refill_period(120_000), Duration::from_micros(500));
+        assert_eq!(refill_period(u32::MAX), Duration::from_nanos(13));
+    }
+
+    #[tokio::test]
+    async fn burst_is_immediate_then_the_rate_applies() {
+        // 600 a minute: one token every 100ms.
+        let limiter = RateLimiter::new(600, 3);
+
+        let started = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(started.elapsed() < Duration::from_millis(50));
+
+        limiter.acquire().await;
+        let waited = started.elapsed();
+        assert!(waited >= Duration::from_millis(80), "{:?}", waited);
+        assert!(waited < Duration::from_millis(300), "{:?}", waited);
+    }
+
+    #[tokio::test]
+    async fn refills_never_exceed_the_burst() {
+        let limiter = RateLimiter::new(6_000, 2);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(limiter.semaphore.available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn refill_stops_when_the_last_clone_drops() {
+        let limiter = RateLimiter::new(6_000, 1);
+        let clone = limiter.clone();
+        let semaphore = Arc::clone(&limiter.semaphore);
+
+        limiter.acquire().await;
+        drop(limiter);
+        // One clone is left, so the bucket still refills.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(semaphore.available_permits(), 1);
+
+        clone.acquire().await;
+        drop(clone);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(semaphore.available_permits(), 0);
+    }
+
+    #[tokio::test]
+    async fn hosts_have_separate_buckets() {
+        let limiter = HostRateLimiter::new(1, 1);
+
+        let started = Instant::now();
+        limiter.acquire_for("a.example").await;
+        limiter.acquire_for("B.example").await;
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
 }
@@ -0,0 +1,264 @@
+//! Cached DNS resolution for the HTTP client.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Url;
+
+use crate::cache::LruCache;
+use crate::config::ResolveOverride;
+
+/// Resolver that remembers each host's addresses for the rest of the run.
+///
+/// Probing a host's extra paths, retries, and `--verify-urls` all hit the same
+/// names; with this cache each is looked up with the system resolver once
+/// (until evicted). Failed lookups are not cached.
+///
+/// Clones share one cache.
+#[derive(Debug, Clone)]
+pub struct CachingResolver {
+    cache: Arc<Mutex<LruCache<String, Vec<SocketAddr>>>>,
+    lookups: Arc<AtomicUsize>,
+}
+
+impl CachingResolver {
+    /// Cache up to `capacity` hosts.
+    pub fn new(capacity: usize) -> Self {
+        CachingResolver {
+            cache: Arc::new(Mutex::new(LruCache::new(capacity))),
+            lookups: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Number of lookups that went to the system resolver.
+    pub fn lookups(&self) -> usize {
+        self.lookups.load(Ordering::Relaxed)
+    }
+
+    /// Resolve `host`, using the cache when possible.
+    pub async fn lookup(&self, host: &str) -> std::io::Result<Vec<SocketAddr>> {
+        let key = host.to_ascii_lowercase();
+        if let Some(addrs) = self.cache.lock().unwrap().get(&key) {
+            return Ok(addrs);
+        }
+
+        self.lookups.fetch_add(1, Ordering::Relaxed);
+        // Port 0 lets reqwest fill in the URL's (or scheme's) port.
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((key.as_str(), 0)).await?.collect();
+        self.cache.lock().unwrap().insert(key, addrs.clone());
+        Ok(addrs)
+    }
+}
+
+impl Resolve for CachingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let addrs = resolver.lookup(name.as_str()).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Whether `ip` is loopback, private, link-local, or otherwise not a public address.
+pub fn is_non_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                // Carrier-grade NAT, 100.64.0.0/10.
+                || (a == 100 && (64..128).contains(&b))
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_non_public(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            v6.is_loopback()
+                || v6.is_unspecified()
+                // Unique local (fc00::/7) and link-local (fe80::/10).
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// The first non-public address `host` resolves to, if it has any.
+///
+/// One such address is enough: the client may connect to any of them, so a
+/// host resolving to both a public and a private address is refused too.
+/// `--resolve` overrides are honoured, since they are what the client will
+/// actually connect to. Hosts that fail to resolve return `None` and are left
+/// for the probe to report.
+pub async fn non_public_address(
+    resolver: &CachingResolver,
+    overrides: &[ResolveOverride],
+    host: &str,
+) -> Option<IpAddr> {
+    let addrs: Vec<IpAddr> = match overrides.iter().find(|r| r.host.eq_ignore_ascii_case(host)) {
+        Some(r) => vec![r.addr],
+        None => resolver
+            .lookup(host)
+            .await
+            .ok()?
+            .into_iter()
+            .map(|a| a.ip())
+            .collect(),
+    };
+    addrs.into_iter().find(|ip| is_non_public(*ip))
+}
+
+/// Refuses URLs whose host is, or resolves to, a non-public address
+/// (`--skip-private`), for both probed hosts and the redirects they send.
+#[derive(Debug, Clone)]
+pub struct PublicOnly {
+    /// Resolver shared with the probe client, so hosts are looked up once.
+    pub resolver: CachingResolver,
+    /// `--resolve` pins, which win over the resolver.
+    pub overrides: Vec<ResolveOverride>,
+}
+
+impl PublicOnly {
+    /// The non-public address `url` would be fetched from, if any.
+    pub async fn blocked(&self, url: &Url) -> Option<IpAddr> {
+        let host = url.host_str()?;
+        // IPv6 literals keep their brackets in `host_str`.
+        match host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+        {
+            Ok(ip) => Some(ip).filter(|ip| is_non_public(*ip)),
+            Err(_) => non_public_address(&self.resolver, &self.overrides, host).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A resolver that answers from `hosts` without touching the network.
+    fn stub(hosts: &[(&str, &[&str])]) -> CachingResolver {
+        let resolver = CachingResolver::new(16);
+        for (host, addrs) in hosts {
+            let addrs = addrs
+                .iter()
+                .map(|a| SocketAddr::new(a.parse().unwrap(), 0))
+                .collect();
+            resolver
+                .cache
+                .lock()
+                .unwrap()
+                .insert(host.to_string(), addrs);
+        }
+        resolver
+    }
+
+    fn guard() -> PublicOnly {
+        PublicOnly {
+            resolver: stub(&[
+                ("loopback.test", &["127.0.0.1"]),
+                ("private.test", &["10.0.0.1"]),
+                ("public.test", &["93.184.216.34"]),
+                ("mixed.test", &["93.184.216.34", "192.168.1.5"]),
+            ]),
+            overrides: Vec::new(),
+        }
+    }
+
+    async fn blocked(url: &str) -> Option<IpAddr> {
+        guard().blocked(&Url::parse(url).unwrap()).await
+    }
+
+    #[tokio::test]
+    async fn private_and_loopback_hosts_are_blocked() {
+        assert_eq!(
+            blocked("http://loopback.test/").await,
+            Some("127.0.0.1".parse().unwrap())
+        );
+        assert_eq!(
+            blocked("http://private.test/").await,
+            Some("10.0.0.1".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn public_hosts_are_allowed() {
+        assert_eq!(blocked("http://public.test/").await, None);
+        assert_eq!(blocked("http://93.184.216.34/").await, None);
+    }
+
+    #[tokio::test]
+    async fn one_private_address_is_enough_to_block() {
+        assert_eq!(
+            blocked("http://mixed.test/").await,
+            Some("192.168.1.5".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn ip_literals_and_overrides_are_checked() {
+        assert!(blocked("http://[::1]:8080/").await.is_some());
+        assert!(blocked("http://169.254.169.254/latest/meta-data")
+            .await
+            .is_some());
+
+        let pinned = PublicOnly {
+            overrides: vec![ResolveOverride {
+                host: "public.test".to_string(),
+                addr: "10.1.2.3".parse().unwrap(),
+            }],
+            ..guard()
+        };
+        let url = Url::parse("http://public.test/").unwrap();
+        assert_eq!(
+            pinned.blocked(&url).await,
+            Some("10.1.2.3".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn repeated_lookups_of_a_host_resolve_once() {
+        let resolver = CachingResolver::new(16);
+
+        let first = resolver.lookup("localhost").await.unwrap();
+        let second = resolver.lookup("LOCALHOST").await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(resolver.lookups(), 1);
+    }
+
+    #[tokio::test]
+    async fn a_zero_sized_cache_resolves_every_time() {
+        let resolver = CachingResolver::new(0);
+
+        resolver.lookup("localhost").await.unwrap();
+        resolver.lookup("localhost").await.unwrap();
+
+        assert_eq!(resolver.lookups(), 2);
+    }
+
+    #[test]
+    fn classifies_address_ranges() {
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "100.64.0.1",
+            "fd00::1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(is_non_public(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in ["93.184.216.34", "1.1.1.1", "2606:4700:4700::1111"] {
+            assert!(!is_non_public(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Shared retry policy for the probe, LLM, and YAML fetch stages.
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+
+/// The result of an HTTP attempt, as seen by the retry policy.
+#[derive(Debug, Clone, Copy)]
+pub enum Attempt<'a> {
+    /// The server answered with this status.
+    Status(StatusCode),
+    /// The request failed before (or while) producing a response.
+    Error(&'a reqwest::Error),
+}
+
+/// How many times, and on which statuses, a stage retries.
+///
+/// The default never retries.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each one after.
+    pub base_delay_ms: u64,
+    /// Longest single backoff, in milliseconds; 0 leaves the doubling uncapped.
+    pub max_delay_ms: u64,
+    /// Response statuses worth retrying.
+    pub retry_statuses: BTreeSet<u16>,
+}
+
+impl RetryPolicy {
+    /// Whether a response with `status` should be retried.
+    pub fn retries_status(&self, status: u16) -> bool {
+        self.retry_statuses.contains(&status)
+    }
+
+    /// Whether `wait` is longer than the backoff cap (never, when uncapped).
+    fn exceeds_cap(&self, wait: Duration) -> bool {
+        self.max_delay_ms != 0 && wait > Duration::from_millis(self.max_delay_ms)
+    }
+
+    /// Decide whether a failed attempt is worth retrying.
+    ///
+    /// Connection failures and timeouts are always transient. A status, whether
+    /// answered or carried by an error, is retried only when it's in
+    /// `retry_statuses` (429 and 5xx in the shipped configuration). Body and
+    /// decode errors never are: repeating the same request won't help.
+    pub fn is_retryable(&self, attempt: Attempt<'_>) -> bool {
+        match attempt {
+            Attempt::Status(status) => self.retries_status(status.as_u16()),
+            Attempt::Error(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err
+                        .status()
+                        .is_some_and(|s| self.retries_status(s.as_u16()))
+            }
+        }
+    }
+
+    /// Backoff before retry number `retry` (0-based), at most `max_delay_ms`.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let delay = self.base_delay_ms.saturating_mul(1 << retry.min(16));
+        match self.max_delay_ms {
+            0 => Duration::from_millis(delay),
+            cap => Duration::from_millis(delay.min(cap)),
+        }
+    }
+}
+
+/// How long a response asks the client to wait before retrying, from its
+/// `Retry-After` header (delay seconds or an HTTP date).
+pub fn retry_after(resp: &Response) -> Option<Duration> {
+    let value = resp.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let when = DateTime::parse_from_rfc2822(value).ok()?;
+    // A date already in the past means "retry now".
+    Some(
+        (when.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Send the request built by `build`, retrying under `policy`.
+///
+/// Each attempt is classified by [`RetryPolicy::is_retryable`]. A retried
+/// response's `Retry-After` (see [`retry_after`]) replaces the backoff delay;
+/// when it asks for longer than `policy.max_delay_ms`, that response is
+/// returned rather than waited out. `build` is called once per attempt, since
+/// a sent request can't be reused.
+///
+/// # Returns
+/// The last attempt's response or error
+pub async fn send_with_retries(
+    policy: &RetryPolicy,
+    build: impl Fn() -> RequestBuilder,
+) -> reqwest::Result<Response> {
+    let mut retry = 0;
+    loop {
+        let result = build().send().await;
+        let (retryable, wait) = match &result {
+            Ok(resp) => (
+                policy.is_retryable(Attempt::Status(resp.status())),
+                retry_after(resp),
+            ),
+            Err(e) => (policy.is_retryable(Attempt::Error(e)), None),
+        };
+        if !retryable || retry >= policy.max_retries {
+            return result;
+        }
+        let wait = match wait {
+            // Retrying sooner than asked would only be refused again.
+            Some(wait) if policy.exceeds_cap(wait) => return result,
+            Some(wait) => wait,
+            None => policy.delay(retry),
+        };
+        tokio::time::sleep(wait).await;
+        retry += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            base_delay_ms: 100,
+            max_delay_ms: 250,
+            retry_statuses: BTreeSet::from([429, 500, 502, 503, 504]),
+        }
+    }
+
+    #[test]
+    fn server_errors_and_429_are_retryable() {
+        let policy = server_policy();
+        for status in [500, 503, 429] {
+            let status = StatusCode::from_u16(status).unwrap();
+            assert!(policy.is_retryable(Attempt::Status(status)), "{}", status);
+        }
+    }
+
+    #[test]
+    fn other_client_errors_are_not_retryable() {
+        let policy = server_policy();
+        for status in [400, 403, 404] {
+            let status = StatusCode::from_u16(status).unwrap();
+            assert!(!policy.is_retryable(Attempt::Status(status)), "{}", status);
+        }
+    }
+
+    #[test]
+    fn default_policy_retries_no_status() {
+        let policy = RetryPolicy::default();
+        assert!(!policy.is_retryable(Attempt::Status(StatusCode::SERVICE_UNAVAILABLE)));
+    }
+
+    #[test]
+    fn delay_doubles_up_to_the_cap() {
+        let policy = server_policy();
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.delay(2), Duration::from_millis(250));
+
+        let uncapped = RetryPolicy {
+            max_delay_ms: 0,
+            ..server_policy()
+        };
+        assert_eq!(uncapped.delay(3), Duration::from_millis(800));
+    }
+}
@@ -0,0 +1,208 @@
+//! `robots.txt` handling for probes (`--respect-robots`).
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use reqwest::{Client, Url};
+use tracing::debug;
+
+use crate::cache::LruCache;
+
+/// Product token matched against `User-agent` lines, besides `*`.
+pub const ROBOTS_AGENT: &str = "hackclub-dns-fetcher";
+
+/// How long a `robots.txt` fetch may take before the host is treated as having none.
+const ROBOTS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest `robots.txt` read; the rest is ignored, as crawlers commonly do.
+const ROBOTS_MAX_BYTES: usize = 64 * 1024;
+
+/// The `Allow`/`Disallow` rules of one host that apply to this tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RobotsRules {
+    /// `(allow, path prefix)` pairs, in file order.
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Parse a `robots.txt`, keeping the group for [`ROBOTS_AGENT`] if there is
+    /// one and the `*` group otherwise.
+    ///
+    /// `*` and `$` wildcards aren't supported: a rule using them is cut at the
+    /// first wildcard, which errs towards disallowing.
+    pub fn parse(text: &str) -> Self {
+        let mut named = Vec::new();
+        let mut any = Vec::new();
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+        let mut named_group = false;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match field.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    // A user-agent line after rules starts a new group.
+                    if in_rules {
+                        agents.clear();
+                        in_rules = false;
+                    }
+                    let agent = value.to_ascii_lowercase();
+                    named_group |= agent == ROBOTS_AGENT;
+                    agents.push(agent);
+                }
+                field @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    let prefix = value.split(['*', '$']).next().unwrap_or_default();
+                    // An empty `Disallow:` allows everything, so it adds no rule.
+                    if prefix.is_empty() && field == "disallow" {
+                        continue;
+                    }
+                    let rule = (field == "allow", prefix.to_string());
+                    if agents.iter().any(|a| a == ROBOTS_AGENT) {
+                        named.push(rule.clone());
+                    }
+                    if agents.iter().any(|a| a == "*") {
+                        any.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+        RobotsRules {
+            rules: if named_group { named } else { any },
+        }
+    }
+
+    /// Whether `path` (with its query) may be fetched.
+    ///
+    /// The longest matching prefix wins, and `Allow` wins a tie; a path no rule
+    /// matches is allowed.
+    pub fn allows(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, prefix)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(allow, prefix)| (prefix.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+/// Each origin's `robots.txt`, fetched the first time a probe reaches it and
+/// kept until evicted.
+///
+/// A missing, unreachable, or non-2xx `robots.txt` allows everything.
+#[derive(Debug)]
+pub struct RobotsCache {
+    /// Rules keyed by `scheme://host:port`.
+    origins: Mutex<LruCache<String, Arc<RobotsRules>>>,
+}
+
+impl RobotsCache {
+    /// Cache the rules of up to `capacity` origins.
+    pub fn new(capacity: usize) -> Self {
+        RobotsCache {
+            origins: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Whether `url` may be fetched, downloading its origin's `robots.txt` with
+    /// `client` if it hasn't been seen yet.
+    pub async fn allows(&self, client: &Client, url: &Url) -> bool {
+        let origin = url.origin().ascii_serialization();
+        let cached = self.origins.lock().unwrap().get(&origin);
+        let rules = match cached {
+            Some(rules) => rules,
+            None => {
+                let rules = Arc::new(fetch_rules(client, &origin).await);
+                self.origins
+                    .lock()
+                    .unwrap()
+                    .insert(origin, Arc::clone(&rules));
+                rules
+            }
+        };
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        rules.allows(&path)
+    }
+}
+
+/// Download and parse `<origin>/robots.txt`; any failure yields no rules.
+async fn fetch_rules(client: &Client, origin: &str) -> RobotsRules {
+    let url = format!("{}/robots.txt", origin);
+    let resp = match client.get(&url).timeout(ROBOTS_TIMEOUT).send().await {
+        Ok(resp) if resp.status().is_success() => resp,
+        Ok(resp) => {
+            debug!("{} → HTTP {}; no robots rules", url, resp.status());
+            return RobotsRules::default();
+        }
+        Err(e) => {
+            debug!("{} → {}; no robots rules", url, e);
+            return RobotsRules::default();
+        }
+    };
+    match resp.text().await {
+        Ok(text) => {
+            let mut end = ROBOTS_MAX_BYTES.min(text.len());
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            RobotsRules::parse(&text[..end])
+        }
+        Err(e) => {
+            debug!("{} → {}; no robots rules", url, e);
+            RobotsRules::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_group_applies_without_a_named_one() {
+        let rules = RobotsRules::parse(
+            "User-agent: Googlebot\nDisallow: /\n\nUser-agent: *\nDisallow: /private # no\n",
+        );
+        assert!(rules.allows("/"));
+        assert!(rules.allows("/events"));
+        assert!(!rules.allows("/private/x"));
+    }
+
+    #[test]
+    fn named_group_replaces_the_star_group() {
+        let rules = RobotsRules::parse(
+            "User-agent: *\nDisallow: /\n\nUser-agent: hackclub-dns-fetcher\nDisallow: /admin\n",
+        );
+        assert!(rules.allows("/events"));
+        assert!(!rules.allows("/admin"));
+    }
+
+    #[test]
+    fn longest_match_wins_and_allow_wins_ties() {
+        let rules = RobotsRules::parse(
+            "User-agent: *\nDisallow: /events\nAllow: /events/public\nAllow: /x\nDisallow: /x\n",
+        );
+        assert!(!rules.allows("/events/2025"));
+        assert!(rules.allows("/events/public/1"));
+        assert!(rules.allows("/x"));
+    }
+
+    #[test]
+    fn empty_disallow_and_wildcards() {
+        assert!(RobotsRules::parse("User-agent: *\nDisallow:\n").allows("/"));
+        let rules = RobotsRules::parse("User-agent: *\nDisallow: /*.pdf$\n");
+        assert!(!rules.allows("/file.pdf"));
+    }
+
+    #[test]
+    fn agents_listed_together_share_a_group() {
+        let rules = RobotsRules::parse("User-agent: a\nUser-agent: *\nDisallow: /a\n");
+        assert!(!rules.allows("/a"));
+    }
+}
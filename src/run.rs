@@ -0,0 +1,70 @@
+//! Per-run identity: run IDs and output file naming.
+
+use chrono::{DateTime, Utc};
+
+/// Identifies a single scan so its outputs can be told apart from other runs.
+#[derive(Debug, Clone)]
+pub struct RunInfo {
+    /// Unique ID for this run (UTC start time plus process ID).
+    pub run_id: String,
+    /// When the run started.
+    pub started_at: DateTime<Utc>,
+}
+
+impl RunInfo {
+    /// Start a new run at the current time.
+    pub fn new() -> Self {
+        Self::at(Utc::now())
+    }
+
+    /// Create run info for a specific start time.
+    pub fn at(started_at: DateTime<Utc>) -> Self {
+        let run_id = format!("{}-{:x}", file_stamp(started_at), std::process::id());
+        RunInfo { run_id, started_at }
+    }
+
+    /// Name of an output file for this run.
+    ///
+    /// With `timestamped` set, `summary.json` becomes `summary-2025-06-01T12-00-00.json`;
+    /// otherwise the name is returned unchanged.
+    pub fn output_file(&self, name: &str, timestamped: bool) -> String {
+        if !timestamped {
+            return name.to_string();
+        }
+        let stamp = file_stamp(self.started_at);
+        match name.rsplit_once('.') {
+            Some((stem, ext)) => format!("{}-{}.{}", stem, stamp, ext),
+            None => format!("{}-{}", name, stamp),
+        }
+    }
+}
+
+impl Default for RunInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Filesystem-safe UTC timestamp (colons would break Windows paths).
+fn file_stamp(t: DateTime<Utc>) -> String {
+    t.format("%Y-%m-%dT%H-%M-%S").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn timestamped_names_keep_the_extension() {
+        let run = RunInfo::at(Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap());
+
+        assert_eq!(
+            run.output_file("summary.json", true),
+            "summary-2025-06-01T12-00-00.json"
+        );
+        assert_eq!(run.output_file("LOG", true), "LOG-2025-06-01T12-00-00");
+        assert_eq!(run.output_file("summary.json", false), "summary.json");
+        assert!(run.run_id.starts_with("2025-06-01T12-00-00-"));
+    }
+}
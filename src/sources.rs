@@ -0,0 +1,138 @@
+//! Alternate hackathon sources that bypass probing and the LLM.
+
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::error::RadarError;
+use crate::types::{Confidence, EventFormat, Extraction, Hackathon};
+
+/// A source of already-structured event data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Source {
+    /// A JSON endpoint listing events, e.g. `https://hackathons.hackclub.com/api/events/upcoming`.
+    ApiJson { url: String },
+}
+
+impl Source {
+    /// Provenance label recorded in [`Hackathon::source`] for entries from this source.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Source::ApiJson { .. } => "api",
+        }
+    }
+}
+
+/// Fetch the hackathons published by `source`.
+///
+/// # Returns
+/// The mapped hackathons, or an error if the request fails or the body isn't JSON
+pub async fn fetch_source(client: &Client, source: &Source) -> Result<Vec<Hackathon>, RadarError> {
+    match source {
+        Source::ApiJson { url } => {
+            let json: JsonValue = client
+                .get(url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            Ok(map_events_json(&json, source))
+        }
+    }
+}
+
+/// Map an events JSON document onto hackathons.
+///
+/// Accepts a top-level array or an object wrapping one under `events` or `data`.
+/// Field names are matched loosely (`name`/`title`, `website`/`url`, `start`/`end`,
+/// `virtual`/`hybrid`) so both HackClub's hackathons and events APIs work; entries
+/// without a name are skipped.
+pub fn map_events_json(json: &JsonValue, source: &Source) -> Vec<Hackathon> {
+    let events = json
+        .as_array()
+        .or_else(|| json["events"].as_array())
+        .or_else(|| json["data"].as_array());
+
+    events
+        .into_iter()
+        .flatten()
+        .filter_map(|e| map_event(e, source))
+        .collect()
+}
+
+fn map_event(e: &JsonValue, source: &Source) -> Option<Hackathon> {
+    let Source::ApiJson { url: source_url } = source;
+    let name = first_str(e, &["name", "title"])?.to_string();
+    let url = first_str(e, &["website", "url", "link"]).unwrap_or(source_url);
+
+    let start = first_str(e, &["start", "startDate", "start_date"]).map(date_part);
+    let end = first_str(e, &["end", "endDate", "end_date"]).map(date_part);
+    let dates = match (start, end) {
+        (Some(s), Some(e)) if s != e => format!("{} – {}", s, e),
+        (Some(s), _) => s.to_string(),
+        _ => "Unknown".to_string(),
+    };
+
+    let location: Vec<&str> = ["city", "state", "country"]
+        .iter()
+        .filter_map(|k| e[*k].as_str())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let summary = first_str(e, &["description", "desc", "summary"])
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            if location.is_empty() {
+                name.clone()
+            } else {
+                format!("{} in {}", name, location.join(", "))
+            }
+        });
+
+    let format = if e["hybrid"].as_bool() == Some(true) {
+        EventFormat::Hybrid
+    } else if e["virtual"].as_bool() == Some(true) {
+        EventFormat::Online
+    } else if !location.is_empty() {
+        EventFormat::InPerson
+    } else {
+        EventFormat::Unknown
+    };
+
+    Some(Hackathon {
+        name,
+        url: url.to_string(),
+        register_url: None,
+        dates,
+        start_date: None,
+        end_date: None,
+        summary,
+        format,
+        prizes: None,
+        organizer: None,
+        source: source.label().to_string(),
+        extracted_by: Extraction::Api,
+        confidence: Confidence::High,
+        subdomain: None,
+        url_reachable: None,
+        redirected_from: None,
+        final_url: None,
+        also_from: Vec::new(),
+        first_added: None,
+        last_modified: None,
+        contacts: Vec::new(),
+        source_snippet: None,
+    })
+}
+
+fn first_str<'a>(e: &'a JsonValue, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .filter_map(|k| e[*k].as_str())
+        .find(|s| !s.trim().is_empty())
+}
+
+/// `2025-03-15T09:00:00Z` → `2025-03-15`; other strings pass through.
+fn date_part(s: &str) -> &str {
+    s.split_once('T').map_or(s, |(date, _)| date)
+}
@@ -0,0 +1,149 @@
+//! Zone health statistics computed from a run's probes.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use reqwest::Url;
+use serde::Serialize;
+
+use crate::types::ProbeResult;
+
+/// Coverage of a scan: how much of the zone answered, and with what.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunStats {
+    /// Distinct hosts probed.
+    pub hosts: usize,
+    /// URLs probed, including extra paths.
+    pub probed: usize,
+    /// URLs that answered with a status below 400.
+    pub live: usize,
+    /// URLs that answered with a status of 400 or above.
+    pub http_errors: usize,
+    /// URLs that never produced a response (DNS, connection, timeout, skipped).
+    pub failed: usize,
+    /// Number of responses per HTTP status.
+    pub status_counts: BTreeMap<u16, usize>,
+    /// `status_counts` broken down by the zone each probe was listed in; probes
+    /// without one count towards their host minus its first label, so
+    /// `foo.hackclub.com` counts towards `hackclub.com`.
+    pub zones: BTreeMap<String, BTreeMap<u16, usize>>,
+}
+
+impl RunStats {
+    /// Tally a run's probe results.
+    pub fn from_probes(probes: &[ProbeResult]) -> Self {
+        let mut stats = RunStats {
+            probed: probes.len(),
+            ..RunStats::default()
+        };
+        let mut hosts = HashSet::new();
+
+        for p in probes {
+            let host = Url::parse(&p.subdomain)
+                .ok()
+                .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+                .unwrap_or_else(|| p.subdomain.clone());
+
+            match p.status {
+                Some(s) => {
+                    *stats.status_counts.entry(s).or_default() += 1;
+                    let zone = p.zone.clone().unwrap_or_else(|| {
+                        host.split_once('.')
+                            .map_or(host.clone(), |(_, z)| z.to_string())
+                    });
+                    *stats.zones.entry(zone).or_default().entry(s).or_default() += 1;
+                    if s < 400 {
+                        stats.live += 1;
+                    } else {
+                        stats.http_errors += 1;
+                    }
+                }
+                None => stats.failed += 1,
+            }
+            hosts.insert(host);
+        }
+
+        stats.hosts = hosts.len();
+        stats
+    }
+}
+
+impl fmt::Display for RunStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Hosts probed:  {}", self.hosts)?;
+        writeln!(f, "URLs probed:   {}", self.probed)?;
+        writeln!(f, "Live (<400):   {}", self.live)?;
+        writeln!(f, "HTTP errors:   {}", self.http_errors)?;
+        writeln!(f, "No response:   {}", self.failed)?;
+        if !self.status_counts.is_empty() {
+            writeln!(f, "Statuses:")?;
+            for (status, count) in &self.status_counts {
+                writeln!(f, "  {}  {}", status, count)?;
+            }
+        }
+        // The overall histogram already says it all for a single zone.
+        if self.zones.len() > 1 {
+            writeln!(f, "By zone:")?;
+            for (zone, counts) in &self.zones {
+                let counts: Vec<String> = counts
+                    .iter()
+                    .map(|(status, n)| format!("{}×{}", n, status))
+                    .collect();
+                writeln!(f, "  {}: {}", zone, counts.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str, zone: Option<&str>, status: Option<u16>) -> ProbeResult {
+        let result = match status {
+            Some(status) => ProbeResult {
+                status: Some(status),
+                ..ProbeResult::loaded(url, "")
+            },
+            None => ProbeResult::error(url, "timed out"),
+        };
+        ProbeResult {
+            zone: zone.map(str::to_string),
+            ..result
+        }
+    }
+
+    #[test]
+    fn statuses_are_counted_per_zone() {
+        let com = Some("hackclub.com");
+        let io = Some("hackclub.io");
+        let probes = [
+            result("http://a.hackclub.com", com, Some(200)),
+            result("http://b.hackclub.com", com, Some(200)),
+            result("http://c.hackclub.com", com, Some(404)),
+            result("http://deep.d.hackclub.com", com, Some(200)),
+            result("http://a.hackclub.io", io, Some(200)),
+            result("http://b.hackclub.io", io, Some(503)),
+            result("http://c.hackclub.io", io, None),
+            result("http://untagged.example.org", None, Some(301)),
+        ];
+
+        let stats = RunStats::from_probes(&probes);
+
+        let histogram = |pairs: &[(u16, usize)]| pairs.iter().copied().collect::<BTreeMap<_, _>>();
+        assert_eq!(stats.zones.len(), 3);
+        assert_eq!(
+            stats.zones["hackclub.com"],
+            histogram(&[(200, 3), (404, 1)])
+        );
+        assert_eq!(stats.zones["hackclub.io"], histogram(&[(200, 1), (503, 1)]));
+        assert_eq!(stats.zones["example.org"], histogram(&[(301, 1)]));
+        assert_eq!(
+            stats.status_counts,
+            histogram(&[(200, 4), (301, 1), (404, 1), (503, 1)])
+        );
+        assert_eq!((stats.live, stats.http_errors, stats.failed), (5, 2, 1));
+        assert_eq!(stats.hosts, 8);
+    }
+}
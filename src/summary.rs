@@ -0,0 +1,204 @@
+//! Post-processing of the final hackathon list.
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::dates::parse_date_range;
+use crate::dedupe::normalize_name;
+use crate::types::Hackathon;
+
+/// Sort hackathons into a stable, input-order-independent order.
+///
+/// Events with a parseable start date come first, earliest first; the rest follow.
+/// Ties are broken by case-insensitive name, then URL, so identical inputs always
+/// produce identical `summary.json` files.
+pub fn sort_hackathons(hackathons: &mut [Hackathon]) {
+    hackathons.sort_by_cached_key(|h| {
+        let (start, _) = parse_date_range(&h.dates);
+        (start.is_none(), start, h.name.to_lowercase(), h.url.clone())
+    });
+}
+
+/// Move hackathons from the most recently added subdomains to the front.
+///
+/// Orders by `first_added`, newest first, with undated entries last. The sort is
+/// stable, so run it after [`sort_hackathons`] to keep that order within ties.
+pub fn sort_newest_subdomains_first(hackathons: &mut [Hackathon]) {
+    // RFC 3339 timestamps in one offset (UTC) order correctly as strings.
+    hackathons.sort_by(|a, b| b.first_added.cmp(&a.first_added));
+}
+
+/// Keep only hackathons whose dates overlap the inclusive window `start..=end`.
+///
+/// An event overlaps when it starts on or before `end` and ends on or after
+/// `start`, so multi-day events straddling either edge are kept. Events whose
+/// dates can't be parsed are dropped.
+pub fn filter_date_window(hackathons: &mut Vec<Hackathon>, start: NaiveDate, end: NaiveDate) {
+    hackathons.retain(|h| match parse_date_range(&h.dates) {
+        (Some(s), Some(e)) => s <= end && e >= start,
+        _ => false,
+    });
+}
+
+/// One organizer and the events attributed to it, as written to `chapters.json`.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChapterSummary {
+    /// Organizer name as first seen.
+    pub organizer: String,
+    /// Number of hackathons it ran.
+    pub events: usize,
+    /// Names of those hackathons, in input order.
+    pub hackathons: Vec<String>,
+}
+
+/// Group hackathons by organizer and count each organizer's events.
+///
+/// Names are compared ignoring case and punctuation, so "Hack Club Boston" and
+/// "hack club, boston" are one chapter. Hackathons without an organizer are left
+/// out. The busiest organizers come first, ties ordered by name.
+pub fn chapter_summaries(hackathons: &[Hackathon]) -> Vec<ChapterSummary> {
+    let mut chapters: Vec<(String, ChapterSummary)> = Vec::new();
+    for h in hackathons {
+        let Some(organizer) = h.organizer.as_deref() else {
+            continue;
+        };
+        let key = normalize_name(organizer);
+        if key.is_empty() {
+            continue;
+        }
+        match chapters.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, chapter)) => {
+                chapter.events += 1;
+                chapter.hackathons.push(h.name.clone());
+            }
+            None => chapters.push((
+                key,
+                ChapterSummary {
+                    organizer: organizer.trim().to_string(),
+                    events: 1,
+                    hackathons: vec![h.name.clone()],
+                },
+            )),
+        }
+    }
+
+    let mut chapters: Vec<ChapterSummary> = chapters.into_iter().map(|(_, c)| c).collect();
+    chapters.sort_by_cached_key(|c| (std::cmp::Reverse(c.events), c.organizer.to_lowercase()));
+    chapters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, url: &str, dates: &str) -> Hackathon {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "url": url,
+            "dates": dates,
+            "summary": "",
+        }))
+        .unwrap()
+    }
+
+    fn names(hackathons: &[Hackathon]) -> Vec<(&str, &str)> {
+        hackathons
+            .iter()
+            .map(|h| (h.name.as_str(), h.url.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn sort_is_by_date_then_name_then_url_whatever_the_input_order() {
+        let input = vec![
+            entry("Zeta", "http://z.test/", "Unknown"),
+            entry("beta", "http://b2.test/", "Unknown"),
+            entry("Beta", "http://b1.test/", "Unknown"),
+            entry("Late", "http://l.test/", "2026-05-01"),
+            entry("Early", "http://e.test/", "March 1, 2026"),
+        ];
+        let expected = vec![
+            ("Early", "http://e.test/"),
+            ("Late", "http://l.test/"),
+            ("Beta", "http://b1.test/"),
+            ("beta", "http://b2.test/"),
+            ("Zeta", "http://z.test/"),
+        ];
+
+        let mut forward = input.clone();
+        sort_hackathons(&mut forward);
+        let mut reversed: Vec<Hackathon> = input.into_iter().rev().collect();
+        sort_hackathons(&mut reversed);
+
+        assert_eq!(names(&forward), expected);
+        assert_eq!(names(&reversed), expected);
+    }
+
+    #[test]
+    fn date_window_keeps_overlapping_events_only() {
+        let mut hackathons = vec![
+            entry("Inside", "http://a.test/", "July 10–12, 2025"),
+            entry("Straddles start", "http://b.test/", "May 30 – June 2, 2025"),
+            entry("Straddles end", "http://c.test/", "2025-08-31 – 2025-09-02"),
+            entry(
+                "Covers window",
+                "http://d.test/",
+                "May 1 – September 30, 2025",
+            ),
+            entry("Before", "http://e.test/", "May 1–3, 2025"),
+            entry("After", "http://f.test/", "September 1, 2025"),
+            entry("Undated", "http://g.test/", "Unknown"),
+        ];
+        let start = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 8, 31).unwrap();
+
+        filter_date_window(&mut hackathons, start, end);
+
+        let kept: Vec<&str> = hackathons.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(
+            kept,
+            [
+                "Inside",
+                "Straddles start",
+                "Straddles end",
+                "Covers window"
+            ]
+        );
+    }
+
+    #[test]
+    fn chapters_count_unique_organizers_busiest_first() {
+        let ran = |name: &str, organizer: Option<&str>| Hackathon {
+            organizer: organizer.map(str::to_string),
+            ..entry(name, "http://x.test/", "Unknown")
+        };
+        let hackathons = vec![
+            ran("HackBoston", Some("Hack Club Boston")),
+            ran("HackNYC", Some("Hack Club NYC")),
+            ran("Boston Winter", Some("hack club, boston")),
+            ran("Solo", None),
+            ran("Blank", Some("  ")),
+            ran("Boston Spring", Some("HACK CLUB BOSTON")),
+            ran("HackAustin", Some("Hack Club Austin")),
+        ];
+
+        let chapters = chapter_summaries(&hackathons);
+
+        let counts: Vec<(&str, usize)> = chapters
+            .iter()
+            .map(|c| (c.organizer.as_str(), c.events))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("Hack Club Boston", 3),
+                ("Hack Club Austin", 1),
+                ("Hack Club NYC", 1)
+            ]
+        );
+        assert_eq!(
+            chapters[0].hackathons,
+            ["HackBoston", "Boston Winter", "Boston Spring"]
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Approximate token counting for sizing LLM prompts.
+//!
+//! Models count their limits in tokens, and pages of markup, prose, or
+//! non-Latin text have very different character-to-token ratios. This
+//! estimate follows how BPE tokenizers split text: words cost about one token
+//! per five letters, numbers one per three digits, punctuation one per two
+//! symbols, and each non-ASCII character one. It errs on the high side for
+//! markup, so budgets stay within the model's limit.
+
+/// Estimated token count of `text`.
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut counter = Counter::default();
+    text.chars().map(|c| counter.cost(c)).sum()
+}
+
+/// The longest prefix of `text` estimated at no more than `max_tokens` tokens.
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> &str {
+    let mut counter = Counter::default();
+    let mut used = 0;
+    for (i, c) in text.char_indices() {
+        used += counter.cost(c);
+        if used > max_tokens {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+/// Characters of one kind that share tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Run {
+    Letters,
+    Digits,
+    Symbols,
+    Space,
+    Other,
+}
+
+impl Run {
+    fn of(c: char) -> Self {
+        if c.is_ascii_alphabetic() {
+            Run::Letters
+        } else if c.is_ascii_digit() {
+            Run::Digits
+        } else if c.is_ascii_whitespace() {
+            Run::Space
+        } else if c.is_ascii() {
+            Run::Symbols
+        } else {
+            Run::Other
+        }
+    }
+
+    /// Characters of this kind per token.
+    fn chars_per_token(self) -> usize {
+        match self {
+            Run::Letters => 5,
+            Run::Digits => 3,
+            Run::Symbols => 2,
+            // Whitespace mostly merges into the following word.
+            Run::Space => usize::MAX,
+            Run::Other => 1,
+        }
+    }
+}
+
+/// Tracks the current run so each character's cost can be found in one pass.
+#[derive(Debug, Default)]
+struct Counter {
+    run: Option<Run>,
+    len: usize,
+}
+
+impl Counter {
+    /// Tokens added by the next character: 1 when it starts a token, else 0.
+    fn cost(&mut self, c: char) -> usize {
+        let run = Run::of(c);
+        if self.run != Some(run) {
+            self.run = Some(run);
+            self.len = 0;
+        }
+        let starts_token = run != Run::Space && self.len.is_multiple_of(run.chars_per_token());
+        self.len += 1;
+        usize::from(starts_token)
+    }
+}
@@ -1,34 +1,147 @@
 //! Data structures for HackClub Events Radar.
 
-use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// Represents the result of probing a single URL.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ProbeResult {
     /// The full URL that was probed.
     pub subdomain: String,
+    /// DNS zone the probed host is listed in (e.g. `hackclub.com`); `None` when
+    /// unknown, such as for pages loaded from disk without one recorded.
+    pub zone: Option<String>,
     /// HTTP status code, if the request succeeded.
     pub status: Option<u16>,
     /// Response body content, if available.
     pub content: Option<String>,
     /// Error message, if the request or parsing failed.
     pub error: Option<String>,
+    /// Where redirects ended up, when that differs from the probed URL.
+    pub final_url: Option<String>,
+    /// Milliseconds until the response headers arrived (the last attempt's).
+    pub ttfb_ms: Option<u64>,
+    /// Milliseconds the last attempt took in total, including the body download.
+    pub elapsed_ms: Option<u64>,
+    /// Scheme (`https` or `http`) of the URL the response finally came from.
+    pub final_scheme: Option<String>,
+    /// Each redirect target followed, in order (`final_url` is the last).
+    pub redirects: Vec<String>,
+    /// Requests made for this URL, retries included; 0 if it was never requested
+    /// (skipped, or loaded from disk).
+    pub attempts: u32,
+    /// Headers of the final response, keyed by lowercase name; repeated headers
+    /// are joined with ", ".
+    pub headers: HashMap<String, String>,
+    /// The body was cut short (`max_body_bytes` or the run's download cap), so
+    /// `content` may be incomplete.
+    pub truncated: bool,
+}
+
+impl ProbeResult {
+    /// A result for `url` with nothing recorded yet: no status, content,
+    /// error, or attempts.
+    pub fn new(url: impl Into<String>) -> Self {
+        ProbeResult {
+            subdomain: url.into(),
+            ..ProbeResult::default()
+        }
+    }
+
+    /// A result for `url` that was never answered, recording why.
+    pub fn error(url: impl Into<String>, message: impl Into<String>) -> Self {
+        ProbeResult {
+            error: Some(message.into()),
+            ..ProbeResult::new(url)
+        }
+    }
+
+    /// A 200 result for `url` with `content` that wasn't requested in this run
+    /// (read from disk or a saved file), so `attempts` is 0.
+    pub fn loaded(url: impl Into<String>, content: impl Into<String>) -> Self {
+        ProbeResult {
+            status: Some(200),
+            content: Some(content.into()),
+            ..ProbeResult::new(url)
+        }
+    }
+
+    /// The URL the content was actually read from.
+    pub fn page_url(&self) -> &str {
+        self.final_url.as_deref().unwrap_or(&self.subdomain)
+    }
+
+    /// Value of a response header, looked up by lowercase name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
 }
 
 /// JSON representation of a probe result for debugging.
 #[derive(Serialize)]
 pub struct EntryJson {
     pub subdomain: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
     pub status: Option<u16>,
     pub bytes: Option<usize>,
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttfb_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_scheme: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub redirects: Vec<String>,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,
+    /// The subset of response headers in [`EntryJson::HEADERS`].
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// The body was a bot-check interstitial (e.g. Cloudflare's "Just a moment...").
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub challenge: bool,
+}
+
+impl EntryJson {
+    /// Response headers copied into `results.json`.
+    pub const HEADERS: [&'static str; 2] = ["content-type", "server"];
 }
 
 /// JSON representation of a successful probe result (status < 400).
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct SuccessJson {
     pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
     pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+}
+
+/// Run-level metadata and counts written to `report.json`.
+#[derive(Serialize, Debug, Clone)]
+pub struct RunReport {
+    /// Unique ID of the run that produced the outputs.
+    pub run_id: String,
+    /// UTC start time of the run (RFC 3339).
+    pub started_at: String,
+    /// Number of URLs probed.
+    pub probed: usize,
+    /// Number of probes that returned a status below 400 with a body.
+    pub successes: usize,
+    /// Number of hackathons in the summary.
+    pub hackathons: usize,
 }
 
 /// Represents a hackathon event extracted from HTML content.
@@ -38,8 +151,324 @@ pub struct Hackathon {
     pub name: String,
     /// URL for the hackathon (or the page it was found on).
     pub url: String,
-    /// Date or date range (e.g., "March 15–17, 2025").
+    /// Direct registration/application link, when distinct from the homepage.
+    #[serde(default)]
+    pub register_url: Option<String>,
+    /// Date or date range (e.g., "March 15–17, 2025"), as written on the page.
     pub dates: String,
+    /// First day of the event, parsed from `dates` (see [`crate::dates::fill_dates`]).
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<NaiveDate>,
+    /// Last day of the event, parsed from `dates`; equals `start_date` for one-day events.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<NaiveDate>,
     /// One-sentence description of the hackathon.
     pub summary: String,
+    /// Whether the event is in-person, online, or hybrid.
+    #[serde(default)]
+    pub format: EventFormat,
+    /// Short description of the prizes on offer, if the page mentions any.
+    #[serde(default)]
+    pub prizes: Option<String>,
+    /// Club, chapter, or group running the event, if the page names one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organizer: Option<String>,
+    /// Where the entry came from: the DNS zone name (e.g. `hackclub.com`) for probed
+    /// pages, or `api` for structured sources.
+    #[serde(default)]
+    pub source: String,
+    /// How the entry was read from its source (not taken from LLM output).
+    #[serde(default, skip_deserializing)]
+    pub extracted_by: Extraction,
+    /// How much to trust the entry, from how it was extracted and whether its
+    /// dates parse (see [`Hackathon::rate_confidence`]).
+    #[serde(default, skip_deserializing)]
+    pub confidence: Confidence,
+    /// Root URL of the probed subdomain the entry was found through (e.g.
+    /// `http://foo.hackclub.com`), kept even when `url` points elsewhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subdomain: Option<String>,
+    /// Whether `url` answered with a 2xx status (only with `--verify-urls`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_reachable: Option<bool>,
+    /// Probed subdomain URL, when it redirected to the page this was extracted from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirected_from: Option<String>,
+    /// Page the entry was extracted from after following redirects.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_url: Option<String>,
+    /// Root URLs of other probed subdomains the entry was also found through,
+    /// e.g. when several subdomains redirect to one page or advertise the same event.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub also_from: Vec<String>,
+    /// When the subdomain it came from was added to the DNS zone (ISO 8601; only
+    /// with `--with-git-history`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_added: Option<String>,
+    /// When that subdomain's DNS entry last changed (ISO 8601; only with
+    /// `--with-git-history`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// Organizer contact links found on the source page (emails, Discord/Slack
+    /// invites, social profiles).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contacts: Vec<String>,
+    /// Text snippet from the source page (only with `--include-content-in-summary`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_snippet: Option<String>,
+}
+
+/// Why a fetched page was left out of extraction.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SkipReason {
+    /// A 2xx "page not found" page.
+    #[serde(rename = "soft-404")]
+    Soft404,
+    /// A registrar's "this domain is for sale" page.
+    Parked,
+    /// A bot-check interstitial (e.g. Cloudflare's "Just a moment...").
+    BotChallenge,
+    /// Zone entry and content unchanged since the last run (`--only-changed`).
+    Unchanged,
+    /// The page never mentions an event keyword.
+    NoEventKeywords,
+    /// The response wasn't HTML (JSON, images, plain text, ...).
+    NotHtml,
+}
+
+/// A page left out of extraction, as written to `skipped.json`.
+#[derive(Serialize, Debug, Clone)]
+pub struct SkippedPage {
+    /// Probed URL (the subdomain, even if it redirected elsewhere).
+    pub url: String,
+    pub reason: SkipReason,
+}
+
+/// How participants attend an event.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EventFormat {
+    InPerson,
+    Online,
+    Hybrid,
+    #[default]
+    Unknown,
+}
+
+impl EventFormat {
+    /// Normalize free-text attendance descriptions ("remote", "In person", "on-site
+    /// and online", ...) into a variant.
+    ///
+    /// Markers match whole words only, so "girls" isn't "irl".
+    pub fn from_text(text: &str) -> Self {
+        let words: Vec<String> = text
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_string)
+            .collect();
+        // Padded so a phrase only matches at word boundaries.
+        let t = format!(" {} ", words.join(" "));
+        let has = |phrase: &&str| t.contains(&format!(" {} ", phrase));
+        let online = ["online", "virtual", "remote", "digital"].iter().any(has);
+        let in_person = [
+            "in person",
+            "inperson",
+            "on site",
+            "onsite",
+            "physical",
+            "irl",
+            "offline",
+        ]
+        .iter()
+        .any(has);
+
+        if has(&"hybrid") || (online && in_person) {
+            EventFormat::Hybrid
+        } else if in_person {
+            EventFormat::InPerson
+        } else if online {
+            EventFormat::Online
+        } else {
+            EventFormat::Unknown
+        }
+    }
+}
+
+impl fmt::Display for EventFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EventFormat::InPerson => "In-person",
+            EventFormat::Online => "Online",
+            EventFormat::Hybrid => "Hybrid",
+            EventFormat::Unknown => "Unknown",
+        })
+    }
+}
+
+/// Which method produced a hackathon entry.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Extraction {
+    /// The LLM read it from the page text.
+    #[default]
+    Llm,
+    /// Parsed from the page's schema.org JSON-LD markup.
+    JsonLd,
+    /// Imported from a structured source (`--api-json`).
+    Api,
+    /// Built from the page's title and meta tags after the LLM found nothing.
+    Meta,
+}
+
+impl fmt::Display for Extraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Extraction::Llm => "llm",
+            Extraction::JsonLd => "json-ld",
+            Extraction::Api => "api",
+            Extraction::Meta => "meta",
+        })
+    }
+}
+
+impl Extraction {
+    /// Confidence in an entry this method produced, before looking at its dates.
+    pub fn confidence(self) -> Confidence {
+        match self {
+            Extraction::JsonLd | Extraction::Api => Confidence::High,
+            Extraction::Llm => Confidence::Medium,
+            Extraction::Meta => Confidence::Low,
+        }
+    }
+}
+
+/// How far an entry can be trusted to describe a real event; ordered from
+/// `Low` to `High`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Confidence {
+    /// A guess: page metadata only, or an LLM entry without usable dates.
+    Low,
+    /// Read from the page text by the LLM.
+    #[default]
+    Medium,
+    /// Taken from structured data (JSON-LD or an API) with parseable dates.
+    High,
+}
+
+impl Confidence {
+    /// One level down (`Low` stays `Low`).
+    pub fn lower(self) -> Self {
+        match self {
+            Confidence::High => Confidence::Medium,
+            Confidence::Medium | Confidence::Low => Confidence::Low,
+        }
+    }
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        })
+    }
+}
+
+impl FromStr for Confidence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low" => Ok(Confidence::Low),
+            "medium" => Ok(Confidence::Medium),
+            "high" => Ok(Confidence::High),
+            _ => Err(format!(
+                "unknown confidence '{}' (expected low, medium, or high)",
+                s
+            )),
+        }
+    }
+}
+
+impl Hackathon {
+    /// Set `confidence` from `extracted_by`, one level lower when `start_date`
+    /// is unknown. Call after [`crate::dates::fill_dates`].
+    pub fn rate_confidence(&mut self) {
+        let base = self.extracted_by.confidence();
+        self.confidence = match self.start_date {
+            Some(_) => base,
+            None => base.lower(),
+        };
+    }
+}
+
+impl<'de> Deserialize<'de> for EventFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text: Option<String> = Option::deserialize(deserializer)?;
+        Ok(text
+            .as_deref()
+            .map_or(EventFormat::Unknown, EventFormat::from_text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_phrasings_map_to_their_variant() {
+        for (text, format) in [
+            ("in-person", EventFormat::InPerson),
+            ("In Person", EventFormat::InPerson),
+            ("on-site at the library", EventFormat::InPerson),
+            ("IRL", EventFormat::InPerson),
+            ("online", EventFormat::Online),
+            ("Virtual", EventFormat::Online),
+            ("fully remote", EventFormat::Online),
+            ("hybrid", EventFormat::Hybrid),
+            ("in person and online", EventFormat::Hybrid),
+            ("unknown", EventFormat::Unknown),
+            ("", EventFormat::Unknown),
+            ("Online hackathon for girls", EventFormat::Online),
+            (
+                "Digitally-minded builders, in person",
+                EventFormat::InPerson,
+            ),
+            ("Remotely interesting", EventFormat::Unknown),
+        ] {
+            assert_eq!(EventFormat::from_text(text), format, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn format_deserializes_from_free_text_and_serializes_snake_case() {
+        let h: Hackathon = serde_json::from_value(serde_json::json!({
+            "name": "HackFoo",
+            "url": "",
+            "dates": "Unknown",
+            "summary": "",
+            "format": "Remote",
+        }))
+        .unwrap();
+        assert_eq!(h.format, EventFormat::Online);
+
+        let missing: Hackathon = serde_json::from_value(serde_json::json!({
+            "name": "HackFoo",
+            "url": "",
+            "dates": "Unknown",
+            "summary": "",
+            "format": null,
+        }))
+        .unwrap();
+        assert_eq!(missing.format, EventFormat::Unknown);
+
+        assert_eq!(
+            serde_json::to_value(EventFormat::InPerson).unwrap(),
+            "in_person"
+        );
+    }
 }
@@ -0,0 +1,123 @@
+//! The command-line binary run as a child process, over saved page bodies.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::time::Duration;
+
+use common::{temp_dir, MockServer, Reply};
+use hackclub_dns_fetcher::bodies::body_filename;
+
+/// A page whose JSON-LD fully describes one event, so no LLM call is needed.
+const JSON_LD_PAGE: &str = r#"<html><head><title>HackFoo</title>
+<script type="application/ld+json">{"@type": "Event", "name": "HackFoo",
+"url": "https://foo.hackclub.com/", "startDate": "2026-03-01"}</script>
+</head><body>HackFoo is a hackathon.</body></html>"#;
+
+/// Run the binary in `root` over one saved page, writing into `root/out`.
+fn run(root: &Path, extra: &[&str]) -> Output {
+    let pages = root.join("pages");
+    fs::create_dir_all(&pages).unwrap();
+    fs::write(
+        pages.join(body_filename("http://foo.hackclub.com")),
+        JSON_LD_PAGE,
+    )
+    .unwrap();
+    Command::new(env!("CARGO_BIN_EXE_hackclub-dns-fetcher"))
+        .current_dir(root)
+        .env("NVIDIA_API_KEY", "test-key")
+        .env_remove("RUST_LOG")
+        .args(["--from-dir", "pages", "--output-dir", "out", "--no-cache"])
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn quiet_prints_nothing_but_still_writes_files() {
+    let root = temp_dir("cli-quiet");
+
+    let output = run(&root, &["--quiet"]);
+
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+    let summary = fs::read_to_string(root.join("out/summary.json")).unwrap();
+    assert!(summary.contains("HackFoo"));
+}
+
+#[test]
+fn without_quiet_the_summary_is_printed() {
+    let root = temp_dir("cli-loud");
+
+    let output = run(&root, &[]);
+
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("HACKATHON SUMMARY"), "{}", stdout);
+    assert!(stdout.contains("HackFoo"), "{}", stdout);
+}
+
+#[test]
+fn quiet_and_verbose_are_exclusive() {
+    let root = temp_dir("cli-conflict");
+
+    let output = run(&root, &["--quiet", "--verbose"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used together"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn concurrent_progress_lines_stay_well_formed_and_in_order() {
+    const PAGES: usize = 40;
+    let root = temp_dir("cli-progress");
+    let pages = root.join("pages");
+    fs::create_dir_all(&pages).unwrap();
+    for i in 0..PAGES {
+        let url = format!("http://site{}.hackclub.com", i);
+        fs::write(
+            pages.join(body_filename(&url)),
+            "<html><body>A hackathon page.</body></html>",
+        )
+        .unwrap();
+    }
+    let llm = MockServer::start(|n| {
+        let reply = Reply::json(r#"{"choices": [{"message": {"content": "[]"}}]}"#);
+        Reply::Delayed(Duration::from_millis((n % 5) as u64 * 10), Box::new(reply))
+    })
+    .await;
+
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_hackclub-dns-fetcher"))
+        .current_dir(&root)
+        .env("NVIDIA_API_KEY", "test-key")
+        .env("NIM_API_URL", llm.url("/v1/chat/completions"))
+        .env_remove("RUST_LOG")
+        .args(["--from-dir", "pages", "--output-dir", "out", "--no-cache"])
+        .args(["--llm-concurrency", "16", "--rate-limit", "60000"])
+        .output()
+        .await
+        .unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(llm.requests(), PAGES);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let counter = stdout
+        .lines()
+        .find(|line| line.starts_with("Querying LLM"))
+        .unwrap_or_else(|| panic!("no LLM counter in {:?}", stdout));
+    let counts: Vec<usize> = counter
+        .split('\r')
+        .map(|update| {
+            let rest = update.strip_prefix("Querying LLM").unwrap_or_else(|| {
+                panic!("garbled update {:?}", update);
+            });
+            let (done, total) = rest.trim_start().split_once('/').unwrap();
+            assert_eq!(total, PAGES.to_string(), "{:?}", update);
+            done.parse().unwrap()
+        })
+        .collect();
+    assert_eq!(counts, (0..=PAGES).collect::<Vec<_>>());
+}
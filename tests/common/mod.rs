@@ -0,0 +1,285 @@
+//! A minimal HTTP server for exercising the probe code without the network.
+//!
+//! `wiremock`/`httpmock` would do the same job; this stays on tokio alone so
+//! the tests need no extra dependencies.
+//!
+//! Each test binary compiles its own copy and uses only some of it.
+#![allow(dead_code)]
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// How the server answers one request.
+#[derive(Debug, Clone)]
+pub enum Reply {
+    /// A response with this status, `Content-Type`, and body.
+    Status(u16, &'static str, &'static str),
+    /// A response with any status, headers, and body; see [`Reply::with`].
+    Full {
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: String,
+    },
+    /// Wait this long before sending the headers of the inner reply.
+    Delayed(Duration, Box<Reply>),
+    /// Send the headers, then wait this long before sending the body.
+    SlowBody(Duration, Box<Reply>),
+    /// Read the request and never answer.
+    Hang,
+}
+
+impl Reply {
+    /// A 200 response with an HTML body.
+    pub fn html(body: &'static str) -> Self {
+        Reply::Status(200, "text/html; charset=utf-8", body)
+    }
+
+    /// A 200 response with a JSON body.
+    pub fn json(body: impl Into<String>) -> Self {
+        Reply::with(200, body).header("Content-Type", "application/json")
+    }
+
+    /// A response with `status` and `body` and no headers yet.
+    pub fn with(status: u16, body: impl Into<String>) -> Self {
+        Reply::Full {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Add a header; only meaningful on [`Reply::Full`].
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if let Reply::Full { headers, .. } = &mut self {
+            headers.push((name.to_string(), value.to_string()));
+        }
+        self
+    }
+}
+
+/// One request as the server received it.
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// 0 for the first request the server answered.
+    pub index: usize,
+    pub method: String,
+    /// Path and query, e.g. `/events?page=2`.
+    pub path: String,
+    /// Header names are lowercased.
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    /// Value of the first header called `name` (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A server on a random local port, answering each request with whatever its
+/// handler returns for it.
+///
+/// TLS handshakes are refused by closing the connection, so probes that try
+/// HTTPS first fall back to plain HTTP as they would against a real HTTP-only
+/// host; those attempts are not counted as requests.
+pub struct MockServer {
+    addr: SocketAddr,
+    received: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MockServer {
+    /// Start serving on `127.0.0.1`, answering by request index (0 for the
+    /// first); the server runs until the test's runtime ends.
+    pub async fn start<F>(handler: F) -> Self
+    where
+        F: Fn(usize) -> Reply + Send + Sync + 'static,
+    {
+        Self::start_with(move |req: &Request| handler(req.index)).await
+    }
+
+    /// Like [`MockServer::start`], but the handler sees the whole request.
+    pub async fn start_with<F>(handler: F) -> Self
+    where
+        F: Fn(&Request) -> Reply + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let counter = Arc::new(AtomicUsize::new(0));
+        let handler = Arc::new(handler);
+        let log = Arc::clone(&received);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let handler = Arc::clone(&handler);
+                let counter = Arc::clone(&counter);
+                let log = Arc::clone(&log);
+                tokio::spawn(serve(stream, handler, counter, log));
+            }
+        });
+        MockServer { addr, received }
+    }
+
+    /// `http://127.0.0.1:<port><path>`.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    /// `127.0.0.1:<port>`.
+    pub fn authority(&self) -> String {
+        self.addr.to_string()
+    }
+
+    /// HTTP requests received so far.
+    pub fn requests(&self) -> usize {
+        self.received.lock().unwrap().len()
+    }
+
+    /// Every request received so far, in arrival order.
+    pub fn received(&self) -> Vec<Request> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+/// Answer the single request on `stream`, then close it.
+async fn serve<F>(
+    mut stream: TcpStream,
+    handler: Arc<F>,
+    counter: Arc<AtomicUsize>,
+    log: Arc<Mutex<Vec<Request>>>,
+) where
+    F: Fn(&Request) -> Reply,
+{
+    let mut raw = Vec::new();
+    let mut buf = [0u8; 4096];
+    let head_end = loop {
+        if let Some(pos) = raw.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            // A TLS ClientHello starts with the handshake record type.
+            Ok(_) if raw.is_empty() && buf[0] == 0x16 => return,
+            Ok(n) => raw.extend_from_slice(&buf[..n]),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&raw[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let mut start = lines.next().unwrap_or_default().split_whitespace();
+    let method = start.next().unwrap_or_default().to_string();
+    let path = start.next().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect();
+    let length = headers
+        .iter()
+        .find(|(k, _)| k == "content-length")
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    while raw.len() < head_end + length {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => raw.extend_from_slice(&buf[..n]),
+        }
+    }
+    let body = String::from_utf8_lossy(&raw[head_end..head_end + length]).into_owned();
+
+    let request = Request {
+        index: counter.fetch_add(1, Ordering::SeqCst),
+        method,
+        path,
+        headers,
+        body,
+    };
+    let reply = handler(&request);
+    log.lock().unwrap().push(request);
+    respond(&mut stream, reply).await;
+}
+
+/// Write `reply` to `stream`.
+async fn respond(stream: &mut TcpStream, reply: Reply) {
+    match reply {
+        Reply::Status(status, content_type, body) => {
+            let reply = Reply::with(status, body).header("Content-Type", content_type);
+            Box::pin(respond(stream, reply)).await;
+        }
+        Reply::Full {
+            status,
+            headers,
+            body,
+        } => {
+            let _ = stream
+                .write_all(head(status, &headers, &body).as_bytes())
+                .await;
+            let _ = stream.write_all(body.as_bytes()).await;
+        }
+        Reply::Delayed(delay, reply) => {
+            tokio::time::sleep(delay).await;
+            Box::pin(respond(stream, *reply)).await;
+        }
+        Reply::SlowBody(delay, reply) => {
+            let (status, headers, body) = match *reply {
+                Reply::Status(status, content_type, body) => (
+                    status,
+                    vec![("Content-Type".to_string(), content_type.to_string())],
+                    body.to_string(),
+                ),
+                Reply::Full {
+                    status,
+                    headers,
+                    body,
+                } => (status, headers, body),
+                other => return Box::pin(respond(stream, other)).await,
+            };
+            let _ = stream
+                .write_all(head(status, &headers, &body).as_bytes())
+                .await;
+            let _ = stream.flush().await;
+            tokio::time::sleep(delay).await;
+            let _ = stream.write_all(body.as_bytes()).await;
+        }
+        Reply::Hang => std::future::pending::<()>().await,
+    }
+}
+
+/// Status line and headers, ending with the blank line.
+fn head(status: u16, headers: &[(String, String)], body: &str) -> String {
+    let mut head = format!("HTTP/1.1 {} Mock\r\n", status);
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    ));
+    head
+}
+
+/// A local URL nothing is listening on.
+pub async fn refused_url() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    format!("http://{}/", addr)
+}
+
+/// A fresh, empty directory under the system temp dir, unique to `name` and
+/// this process.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("radar-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
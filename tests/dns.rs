@@ -0,0 +1,188 @@
+//! `fetch_zone` against a local mock server.
+
+mod common;
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use reqwest::Client;
+
+use common::{temp_dir, MockServer, Reply};
+use hackclub_dns_fetcher::dns::{fetch_zone, fetch_zone_cached, ZoneSource};
+use hackclub_dns_fetcher::error::RadarError;
+use hackclub_dns_fetcher::retry::RetryPolicy;
+
+const ZONE: &str = "www:\n  type: CNAME\nhackathon:\n  type: A\n";
+
+fn client() -> Client {
+    Client::builder()
+        .timeout(Duration::from_millis(500))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn fetch_zone_returns_the_yaml() {
+    let server = MockServer::start(|_| Reply::Status(200, "text/yaml", ZONE)).await;
+
+    let yaml = fetch_zone(
+        &client(),
+        &server.url("/zone.yaml"),
+        1024,
+        &RetryPolicy::default(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(yaml, ZONE);
+}
+
+#[tokio::test]
+async fn fetch_zone_rejects_a_body_over_the_cap() {
+    let server = MockServer::start(|_| Reply::Status(200, "text/yaml", ZONE)).await;
+
+    let err = fetch_zone(
+        &client(),
+        &server.url("/zone.yaml"),
+        10,
+        &RetryPolicy::default(),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(matches!(err, RadarError::Fetch { .. }), "{:?}", err);
+    assert!(
+        err.to_string().contains("exceeds the 10-byte limit"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn fetch_zone_fails_on_an_error_status() {
+    let server = MockServer::start(|_| Reply::Status(404, "text/plain", "404: Not Found")).await;
+
+    let err = fetch_zone(
+        &client(),
+        &server.url("/zone.yaml"),
+        1024,
+        &RetryPolicy::default(),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(err.to_string().contains("HTTP 404"), "{}", err);
+}
+
+#[tokio::test]
+async fn cached_fetch_stores_the_yaml_and_etag() {
+    let server = MockServer::start(|_| Reply::with(200, ZONE).header("ETag", "\"v1\"")).await;
+    let cache = temp_dir("zone-fresh").join("zone.yaml");
+
+    let (yaml, source) = fetch_zone_cached(
+        &client(),
+        &server.url("/zone.yaml"),
+        1024,
+        &cache,
+        &RetryPolicy::default(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(yaml, ZONE);
+    assert!(matches!(source, ZoneSource::Fresh));
+    assert_eq!(std::fs::read_to_string(&cache).unwrap(), ZONE);
+    assert_eq!(
+        std::fs::read_to_string(cache.with_extension("yaml.etag")).unwrap(),
+        "\"v1\""
+    );
+}
+
+#[tokio::test]
+async fn not_modified_uses_the_cached_yaml() {
+    let server = MockServer::start(|_| Reply::with(304, "")).await;
+    let cache = temp_dir("zone-304").join("zone.yaml");
+    std::fs::write(&cache, ZONE).unwrap();
+    std::fs::write(cache.with_extension("yaml.etag"), "\"v1\"").unwrap();
+
+    let (yaml, source) = fetch_zone_cached(
+        &client(),
+        &server.url("/zone.yaml"),
+        1024,
+        &cache,
+        &RetryPolicy::default(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(yaml, ZONE);
+    assert!(matches!(source, ZoneSource::NotModified));
+    assert_eq!(server.received()[0].header("if-none-match"), Some("\"v1\""));
+}
+
+#[tokio::test]
+async fn error_status_falls_back_to_the_cache_without_overwriting_it() {
+    let server = MockServer::start(|_| Reply::Status(429, "text/plain", "slow down")).await;
+    let cache = temp_dir("zone-429").join("zone.yaml");
+    std::fs::write(&cache, ZONE).unwrap();
+
+    let (yaml, source) = fetch_zone_cached(
+        &client(),
+        &server.url("/zone.yaml"),
+        1024,
+        &cache,
+        &RetryPolicy::default(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(yaml, ZONE);
+    assert!(matches!(source, ZoneSource::Stale(ref e) if e.contains("429")));
+    assert_eq!(std::fs::read_to_string(&cache).unwrap(), ZONE);
+}
+
+#[tokio::test]
+async fn error_status_with_nothing_cached_fails_and_writes_nothing() {
+    let server = MockServer::start(|_| Reply::Status(404, "text/plain", "404: Not Found")).await;
+    let cache = temp_dir("zone-404").join("zone.yaml");
+
+    let err = fetch_zone_cached(
+        &client(),
+        &server.url("/zone.yaml"),
+        1024,
+        &cache,
+        &RetryPolicy::default(),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(err.to_string().contains("HTTP 404"), "{}", err);
+    assert!(!cache.exists());
+}
+
+#[tokio::test]
+async fn fetch_zone_retries_as_its_own_policy_allows() {
+    let server = MockServer::start(|n| match n {
+        0 | 1 => Reply::Status(503, "text/plain", "busy"),
+        _ => Reply::Status(200, "text/yaml", ZONE),
+    })
+    .await;
+    let policy = |max_retries| RetryPolicy {
+        max_retries,
+        base_delay_ms: 1,
+        max_delay_ms: 5,
+        retry_statuses: BTreeSet::from([503]),
+    };
+
+    let err = fetch_zone(&client(), &server.url("/zone.yaml"), 1024, &policy(1))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("HTTP 503"), "{}", err);
+    assert_eq!(server.requests(), 2);
+
+    let yaml = fetch_zone(&client(), &server.url("/zone.yaml"), 1024, &policy(1))
+        .await
+        .unwrap();
+    assert_eq!(yaml, ZONE);
+    assert_eq!(server.requests(), 3);
+}
@@ -0,0 +1,123 @@
+//! Zone history from a throwaway git repository with known commit dates.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use common::temp_dir;
+use hackclub_dns_fetcher::git_history::{
+    backfill_with_blame, get_subdomain_history_via_blame, get_yaml_git_history, GitInfo,
+};
+
+const FIRST: &str = "2024-01-01T00:00:00+00:00";
+const SECOND: &str = "2024-06-01T12:00:00+00:00";
+
+fn git(dir: &Path, args: &[&str], date: &str) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args([
+            "-c",
+            "user.name=Zone Owner",
+            "-c",
+            "user.email=owner@example.com",
+        ])
+        .args(args)
+        .env("GIT_AUTHOR_DATE", date)
+        .env("GIT_COMMITTER_DATE", date)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?}", args);
+}
+
+/// A bare clone of a repository where `foo` and `bar` were added in the first
+/// commit and `bar` changed (and `baz` was added) in the second.
+fn zone_repo(name: &str) -> PathBuf {
+    let root = temp_dir(name);
+    let work = root.join("work");
+    std::fs::create_dir(&work).unwrap();
+    git(&work, &["init", "--quiet"], FIRST);
+
+    std::fs::write(
+        work.join("hackclub.com.yaml"),
+        "foo:\n  - type: CNAME\n    value: foo.example.\nbar:\n  - type: A\n    value: 1.2.3.4\n",
+    )
+    .unwrap();
+    git(&work, &["add", "."], FIRST);
+    git(
+        &work,
+        &["commit", "--quiet", "-m", "add foo and bar"],
+        FIRST,
+    );
+
+    std::fs::write(
+        work.join("hackclub.com.yaml"),
+        "foo:\n  - type: CNAME\n    value: foo.example.\nbar:\n  - type: A\n    value: 5.6.7.8\nbaz:\n  - type: A\n    value: 9.9.9.9\n",
+    )
+    .unwrap();
+    git(
+        &work,
+        &["commit", "--quiet", "-am", "move bar, add baz"],
+        SECOND,
+    );
+
+    let bare = root.join("dns.git");
+    git(
+        &root,
+        &["clone", "--bare", "--quiet", "work", "dns.git"],
+        SECOND,
+    );
+    bare
+}
+
+#[test]
+fn blame_dates_each_record_on_a_bare_clone() {
+    let repo = zone_repo("blame");
+    let file = Path::new("hackclub.com.yaml");
+
+    let date = |sub| get_subdomain_history_via_blame(file, &repo, sub).unwrap();
+    assert_eq!(date("foo").as_deref(), Some(FIRST));
+    assert_eq!(date("bar").as_deref(), Some(SECOND));
+    assert_eq!(date("baz").as_deref(), Some(SECOND));
+    assert_eq!(date("missing"), None);
+}
+
+#[test]
+fn log_walk_and_blame_agree() {
+    let repo = zone_repo("history");
+    let file = Path::new("hackclub.com.yaml");
+
+    let history = get_yaml_git_history(file, &repo).unwrap();
+
+    assert_eq!(history["foo"].first_added.as_deref(), Some(FIRST));
+    assert_eq!(history["foo"].last_modified.as_deref(), Some(FIRST));
+    assert_eq!(history["bar"].first_added.as_deref(), Some(FIRST));
+    assert_eq!(history["bar"].last_modified.as_deref(), Some(SECOND));
+    assert_eq!(history["baz"].first_added.as_deref(), Some(SECOND));
+    assert_eq!(history["bar"].last_author.as_deref(), Some("Zone Owner"));
+    for name in ["foo", "bar", "baz"] {
+        let blamed = get_subdomain_history_via_blame(file, &repo, name).unwrap();
+        assert_eq!(blamed, history[name].last_modified, "{}", name);
+    }
+}
+
+#[test]
+fn backfill_only_fills_missing_dates() {
+    let repo = zone_repo("backfill");
+    let file = Path::new("hackclub.com.yaml");
+    let mut history = std::collections::HashMap::from([(
+        "foo".to_string(),
+        GitInfo {
+            last_modified: Some("kept".to_string()),
+            ..GitInfo::default()
+        },
+    )]);
+
+    let filled = backfill_with_blame(file, &repo, &mut history).unwrap();
+
+    assert_eq!(filled, 2);
+    assert_eq!(history["foo"].last_modified.as_deref(), Some("kept"));
+    assert_eq!(history["bar"].last_modified.as_deref(), Some(SECOND));
+    assert_eq!(history["baz"].last_modified.as_deref(), Some(SECOND));
+}
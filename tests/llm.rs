@@ -0,0 +1,370 @@
+//! `extract_hackathons` against a mock chat-completions endpoint.
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use hackclub_dns_fetcher::config::{LlmConfig, LLM_MAX_INPUT_TOKENS, UNTRUNCATED_MAX_TOKENS};
+use hackclub_dns_fetcher::error::RadarError;
+use hackclub_dns_fetcher::llm::{extract_all, extract_hackathons, DEFAULT_PROMPT_TEMPLATE};
+use hackclub_dns_fetcher::ratelimit::{RateLimiter, MAX_RATE_PER_MINUTE};
+use hackclub_dns_fetcher::retry::RetryPolicy;
+use hackclub_dns_fetcher::tokens::estimate_tokens;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use common::{MockServer, Reply, Request};
+
+const HACKATHON: &str =
+    r#"[{"name": "HackFoo", "url": "", "dates": "March 1, 2026", "summary": "A hackathon."}]"#;
+
+/// A chat-completions reply whose message is `content`.
+fn completion(content: &str) -> Reply {
+    Reply::json(json!({ "choices": [{ "message": { "content": content } }] }).to_string())
+}
+
+/// An OpenAI-style error reply.
+fn api_error(status: u16, message: &str) -> Reply {
+    Reply::with(
+        status,
+        json!({ "error": { "message": message } }).to_string(),
+    )
+    .header("Content-Type", "application/json")
+}
+
+/// The prompt a request carried.
+fn prompt(req: &Request) -> String {
+    let body: Value = serde_json::from_str(&req.body).unwrap();
+    body["messages"][0]["content"].as_str().unwrap().to_string()
+}
+
+fn config(server: &MockServer) -> LlmConfig {
+    LlmConfig {
+        api_key: "test-key".to_string(),
+        api_url: server.url("/v1/chat/completions"),
+        retry: RetryPolicy::default(),
+        ..LlmConfig::default()
+    }
+}
+
+/// A page of roughly `words` words inside a small head.
+fn page(words: usize) -> String {
+    format!(
+        "<html><head><title>HackFoo</title></head><body><p>{}</p></body></html>",
+        "hackathon ".repeat(words)
+    )
+}
+
+#[tokio::test]
+async fn context_length_error_retries_with_halved_content() {
+    let server = MockServer::start(|n| match n {
+        0 => api_error(400, "This model's maximum context length is 4096 tokens"),
+        _ => completion(HACKATHON),
+    })
+    .await;
+
+    let found = extract_hackathons(
+        &Client::new(),
+        &config(&server),
+        "http://foo.test/",
+        &page(5_000),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "HackFoo");
+    let sent = server.received();
+    assert_eq!(sent.len(), 2);
+    let (full, halved) = (prompt(&sent[0]).len(), prompt(&sent[1]).len());
+    assert!(halved < full * 2 / 3, "{} then {}", full, halved);
+}
+
+#[tokio::test]
+async fn a_page_that_cannot_shrink_is_not_resent() {
+    let server = MockServer::start(|_| api_error(400, "maximum context length exceeded")).await;
+
+    let err = extract_hackathons(
+        &Client::new(),
+        &config(&server),
+        "http://foo.test/",
+        &page(20),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(
+        err.to_string().contains("maximum context length"),
+        "{}",
+        err
+    );
+    assert_eq!(server.requests(), 1);
+}
+
+#[tokio::test]
+async fn context_retries_stop_once_halving_stops_shrinking() {
+    let server = MockServer::start(|_| api_error(400, "maximum context length exceeded")).await;
+
+    let err = extract_hackathons(
+        &Client::new(),
+        &config(&server),
+        "http://foo.test/",
+        &page(50_000),
+    )
+    .await
+    .unwrap_err();
+
+    assert!(
+        err.to_string().contains("maximum context length"),
+        "{}",
+        err
+    );
+    let lengths: Vec<usize> = server.received().iter().map(|r| prompt(r).len()).collect();
+    assert!(lengths.windows(2).all(|w| w[1] < w[0]), "{:?}", lengths);
+}
+
+#[tokio::test]
+async fn prizes_are_read_when_present_and_none_otherwise() {
+    let server = MockServer::start(|_| {
+        completion(
+            r#"[{"name": "HackFoo", "url": "", "dates": "March 1, 2026", "summary": "A hackathon.", "prizes": "$5,000 in prizes"},
+                {"name": "HackBar", "url": "", "dates": "Unknown", "summary": "Another."}]"#,
+        )
+    })
+    .await;
+
+    let found = extract_hackathons(
+        &Client::new(),
+        &config(&server),
+        "http://foo.test/",
+        "<p>hi</p>",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(found[0].prizes.as_deref(), Some("$5,000 in prizes"));
+    assert_eq!(found[1].prizes, None);
+    // summary.json is the serialized list, so the field reaches it.
+    let json = serde_json::to_value(&found).unwrap();
+    assert_eq!(json[0]["prizes"], "$5,000 in prizes");
+    assert!(prompt(&server.received()[0]).contains("\"prizes\""));
+}
+
+#[tokio::test]
+async fn extract_all_keeps_each_pages_result_and_error() {
+    let server = MockServer::start_with(|req: &Request| {
+        if prompt(req).contains("http://broken.test/") {
+            api_error(500, "upstream exploded")
+        } else if prompt(req).contains("http://empty.test/") {
+            completion("[]")
+        } else {
+            completion(HACKATHON)
+        }
+    })
+    .await;
+    let pages: Vec<(String, String)> = [
+        "http://foo.test/",
+        "http://broken.test/",
+        "http://empty.test/",
+    ]
+    .iter()
+    .map(|url| (url.to_string(), "<p>hackathon</p>".to_string()))
+    .collect();
+    let finished = std::sync::Mutex::new(Vec::new());
+
+    let results = extract_all(
+        &Client::new(),
+        &config(&server),
+        &RateLimiter::new(MAX_RATE_PER_MINUTE, 10),
+        2,
+        &pages,
+        |url, _| finished.lock().unwrap().push(url.to_string()),
+    )
+    .await;
+
+    let urls: Vec<&str> = results.iter().map(|(url, _)| url.as_str()).collect();
+    assert_eq!(
+        urls,
+        [
+            "http://foo.test/",
+            "http://broken.test/",
+            "http://empty.test/"
+        ]
+    );
+    assert_eq!(results[0].1.as_ref().unwrap()[0].name, "HackFoo");
+    match &results[1].1 {
+        Err(RadarError::Llm { status, message }) => {
+            assert_eq!(*status, 500);
+            assert_eq!(message, "upstream exploded");
+        }
+        other => panic!("expected an LLM error, got {:?}", other),
+    }
+    assert!(results[2].1.as_ref().unwrap().is_empty());
+    assert_eq!(finished.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn homepage_and_register_urls_both_survive_normalization() {
+    let server = MockServer::start(|_| {
+        completion(
+            r#"[{"name": "HackFoo", "url": "/2026", "register_url": "https://forms.hackclub.com/hackfoo",
+                 "dates": "March 1, 2026", "summary": "A hackathon."},
+                {"name": "HackBar", "url": "https://bar.test/", "register_url": "  ",
+                 "dates": "Unknown", "summary": "Another."}]"#,
+        )
+    })
+    .await;
+
+    let found = extract_hackathons(
+        &Client::new(),
+        &config(&server),
+        "http://foo.test/",
+        "<p>hi</p>",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(found[0].url, "http://foo.test/2026");
+    assert_eq!(
+        found[0].register_url.as_deref(),
+        Some("https://forms.hackclub.com/hackfoo")
+    );
+    assert_eq!(found[1].url, "https://bar.test/");
+    assert_eq!(found[1].register_url, None);
+}
+
+#[tokio::test]
+async fn bad_request_json_error_is_surfaced_with_its_message() {
+    let server = MockServer::start(|_| api_error(400, "temperature must be at most 2")).await;
+
+    let err = extract_hackathons(
+        &Client::new(),
+        &config(&server),
+        "http://foo.test/",
+        &page(20),
+    )
+    .await
+    .unwrap_err();
+
+    match err {
+        RadarError::Llm { status, message } => {
+            assert_eq!(status, 400);
+            assert_eq!(message, "temperature must be at most 2");
+        }
+        other => panic!("expected an LLM error, got {:?}", other),
+    }
+    assert_eq!(server.requests(), 1, "a plain bad request isn't resent");
+}
+
+#[tokio::test]
+async fn non_json_error_body_is_kept_as_the_message() {
+    let server = MockServer::start(|_| Reply::Status(502, "text/plain", "upstream gone")).await;
+    let cfg = LlmConfig {
+        retry: RetryPolicy {
+            max_retries: 0,
+            ..RetryPolicy::default()
+        },
+        ..config(&server)
+    };
+
+    let err = extract_hackathons(&Client::new(), &cfg, "http://foo.test/", &page(20))
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, RadarError::Llm { status: 502, message } if message == "upstream gone"),
+        "{:?}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn extra_params_are_sent_but_cannot_replace_required_fields() {
+    let server = MockServer::start(|_| completion("[]")).await;
+    let extra = json!({
+        "top_p": 0.9,
+        "frequency_penalty": 0.5,
+        "model": "someone-elses-model",
+        "messages": [],
+        "temperature": 2.0,
+    });
+    let cfg = LlmConfig {
+        extra_params: extra.as_object().unwrap().clone(),
+        ..config(&server)
+    };
+
+    extract_hackathons(&Client::new(), &cfg, "http://foo.test/", &page(20))
+        .await
+        .unwrap();
+
+    let sent = server.received();
+    let body: Value = serde_json::from_str(&sent[0].body).unwrap();
+    assert_eq!(body["top_p"], 0.9);
+    assert_eq!(body["frequency_penalty"], 0.5);
+    assert_eq!(body["model"], cfg.model.as_str());
+    assert_eq!(body["temperature"], json!(cfg.temperature));
+    assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+    assert!(prompt(&sent[0]).contains("http://foo.test/"));
+}
+
+/// The page content a request carried: its prompt minus the template text.
+fn sent_content(req: &Request) -> String {
+    let prompt = prompt(req);
+    let (_, html) = prompt.split_once("HTML:\n").unwrap();
+    html.to_string()
+}
+
+#[tokio::test]
+async fn no_truncate_sends_the_whole_page_up_to_the_safety_cap() {
+    assert!(DEFAULT_PROMPT_TEMPLATE.ends_with("HTML:\n{html}"));
+    let server = MockServer::start(|_| completion("[]")).await;
+    let cfg = |max_input_tokens| LlmConfig {
+        max_input_tokens,
+        strip_boilerplate: false,
+        ..config(&server)
+    };
+    let long = page(20_000);
+    let huge = page(400_000);
+    assert!(estimate_tokens(&long) > LLM_MAX_INPUT_TOKENS);
+    assert!(estimate_tokens(&huge) > UNTRUNCATED_MAX_TOKENS);
+    let client = Client::new();
+
+    for (max_input_tokens, html) in [(LLM_MAX_INPUT_TOKENS, &long), (0, &long), (0, &huge)] {
+        extract_hackathons(&client, &cfg(max_input_tokens), "http://foo.test/", html)
+            .await
+            .unwrap();
+    }
+
+    let sent: Vec<String> = server.received().iter().map(sent_content).collect();
+    let tokens = estimate_tokens(&sent[0]);
+    assert!(tokens <= LLM_MAX_INPUT_TOKENS && tokens > LLM_MAX_INPUT_TOKENS * 9 / 10);
+    assert_eq!(sent[1], long);
+    let tokens = estimate_tokens(&sent[2]);
+    assert!(tokens <= UNTRUNCATED_MAX_TOKENS && tokens > UNTRUNCATED_MAX_TOKENS * 9 / 10);
+    assert!(huge.starts_with(&sent[2]));
+}
+
+#[tokio::test]
+async fn llm_requests_retry_only_as_often_as_the_llm_policy_allows() {
+    let server = MockServer::start(|_| Reply::Status(503, "text/plain", "busy")).await;
+    let cfg = LlmConfig {
+        retry: RetryPolicy {
+            max_retries: 1,
+            base_delay_ms: 1,
+            max_delay_ms: 5,
+            retry_statuses: BTreeSet::from([503]),
+        },
+        ..config(&server)
+    };
+
+    let err = extract_hackathons(&Client::new(), &cfg, "http://foo.test/", &page(20))
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, RadarError::Llm { status: 503, .. }),
+        "{:?}",
+        err
+    );
+    assert_eq!(server.requests(), 2);
+}
@@ -0,0 +1,94 @@
+//! `hackathons_from_url` against a mock site and chat-completions endpoint.
+
+mod common;
+
+use hackclub_dns_fetcher::config::{Config, LlmConfig};
+use hackclub_dns_fetcher::error::RadarError;
+use hackclub_dns_fetcher::page::hackathons_from_url;
+use reqwest::Client;
+use serde_json::json;
+
+use common::{MockServer, Reply, Request};
+
+const PAGE: &str =
+    "<html><head><title>HackFoo</title></head><body>HackFoo is a hackathon.</body></html>";
+
+const JSON_LD_PAGE: &str = r#"<html><head><script type="application/ld+json">{"@type": "Event",
+"name": "HackBar", "url": "https://bar.test/", "startDate": "2026-05-02"}</script></head><body>HackBar</body></html>"#;
+
+/// A site serving `PAGE` at `/events`, `JSON_LD_PAGE` at `/structured`, a 404
+/// anywhere else, and a model that answers every prompt with one hackathon.
+async fn site() -> MockServer {
+    let content = r#"[{"name": "HackFoo", "url": "/hackfoo", "dates": "March 1, 2026",
+        "summary": "A hackathon.", "register_url": "/apply"}]"#;
+    MockServer::start_with(move |req: &Request| match req.path.as_str() {
+        "/events" => Reply::html(PAGE),
+        "/structured" => Reply::html(JSON_LD_PAGE),
+        "/v1/chat/completions" => {
+            Reply::json(json!({ "choices": [{ "message": { "content": content } }] }).to_string())
+        }
+        _ => Reply::Status(404, "text/plain", "not found"),
+    })
+    .await
+}
+
+fn config(server: &MockServer) -> Config {
+    let defaults = Config::default();
+    Config {
+        llm: LlmConfig {
+            api_key: "test-key".to_string(),
+            api_url: server.url("/v1/chat/completions"),
+            ..defaults.llm.clone()
+        },
+        ..defaults
+    }
+}
+
+/// Paths the server was asked for, in order.
+fn paths(server: &MockServer) -> Vec<String> {
+    server.received().into_iter().map(|r| r.path).collect()
+}
+
+#[tokio::test]
+async fn a_page_is_probed_extracted_and_normalized() {
+    let server = site().await;
+
+    let found = hackathons_from_url(&Client::new(), &config(&server), &server.url("/events"))
+        .await
+        .unwrap();
+
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "HackFoo");
+    assert_eq!(found[0].url, server.url("/hackfoo"));
+    assert_eq!(found[0].register_url, Some(server.url("/apply")));
+    assert_eq!(paths(&server), ["/events", "/v1/chat/completions"]);
+}
+
+#[tokio::test]
+async fn complete_json_ld_is_used_without_the_llm() {
+    let server = site().await;
+
+    let found = hackathons_from_url(&Client::new(), &config(&server), &server.url("/structured"))
+        .await
+        .unwrap();
+
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "HackBar");
+    assert_eq!(paths(&server), ["/structured"]);
+}
+
+#[tokio::test]
+async fn an_error_status_is_a_fetch_error_and_skips_the_llm() {
+    let server = site().await;
+
+    let err = hackathons_from_url(&Client::new(), &config(&server), &server.url("/missing"))
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, RadarError::Fetch { message, .. } if message == "HTTP 404"),
+        "{:?}",
+        err
+    );
+    assert_eq!(paths(&server), ["/missing"]);
+}
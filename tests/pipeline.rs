@@ -0,0 +1,654 @@
+//! `run_pipeline` end to end over saved page bodies (`--from-dir`), with a
+//! mock LLM where one is needed.
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{TimeZone, Utc};
+use serde_json::{json, Value};
+
+use common::{temp_dir, MockServer, Reply, Request};
+use hackclub_dns_fetcher::bodies::body_filename;
+use hackclub_dns_fetcher::changes::{fingerprint, ChangeState};
+use hackclub_dns_fetcher::config::Config;
+use hackclub_dns_fetcher::pipeline::{run_pipeline, PipelineConfig};
+use hackclub_dns_fetcher::progress::ProgressMode;
+use hackclub_dns_fetcher::run::RunInfo;
+use hackclub_dns_fetcher::sources::Source;
+use hackclub_dns_fetcher::types::Extraction;
+
+/// A page whose JSON-LD fully describes one event, so no LLM call is needed.
+const JSON_LD_PAGE: &str = r#"<html><head><title>HackFoo</title>
+<script type="application/ld+json">{"@type": "Event", "name": "HackFoo",
+"url": "https://foo.hackclub.com/", "startDate": "2026-03-01"}</script>
+</head><body>HackFoo is a hackathon.</body></html>"#;
+
+/// A JSON-LD page for one event.
+fn json_ld_page(name: &str, url: &str, start: &str) -> String {
+    format!(
+        r#"<html><head><script type="application/ld+json">{{"@type": "Event",
+"name": "{}", "url": "{}", "startDate": "{}"}}</script></head><body>{} is a hackathon.</body></html>"#,
+        name, url, start, name
+    )
+}
+
+/// Save `pages` (`(url, html)`) as a `--from-dir` directory inside `root`.
+fn pages_dir(root: &Path, pages: &[(&str, &str)]) -> PathBuf {
+    let dir = root.join("pages");
+    fs::create_dir_all(&dir).unwrap();
+    for (url, html) in pages {
+        fs::write(dir.join(body_filename(url)), html).unwrap();
+    }
+    dir
+}
+
+/// A config reading `pages` and writing into `root/out`, with no caches.
+fn config(root: &Path, pages: PathBuf) -> Config {
+    let mut config = Config {
+        from_dir: Some(pages),
+        output_dir: Some(root.join("out")),
+        probe_cache: None,
+        ..Config::default()
+    };
+    config.llm.api_key = "test-key".to_string();
+    config
+}
+
+fn pipeline(config: Config) -> PipelineConfig {
+    PipelineConfig {
+        progress: ProgressMode::Quiet,
+        ..PipelineConfig::new(config)
+    }
+}
+
+fn read_json(path: PathBuf) -> Value {
+    serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn timestamped_outputs_carry_the_run_id() {
+    let root = temp_dir("pipeline-timestamped");
+    let pages = pages_dir(&root, &[("http://foo.hackclub.com", JSON_LD_PAGE)]);
+    let config = Config {
+        timestamped_output: true,
+        ..config(&root, pages)
+    };
+    let run = RunInfo::at(Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap());
+    let run_id = run.run_id.clone();
+
+    let found = run_pipeline(PipelineConfig {
+        run,
+        ..pipeline(config)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(found.len(), 1);
+    let out = root.join("out");
+    assert!(out.join("summary-2025-06-01T12-00-00.json").exists());
+    assert!(!out.join("summary.json").exists());
+    let report = read_json(out.join("report-2025-06-01T12-00-00.json"));
+    assert_eq!(report["run_id"], run_id.as_str());
+    assert!(run_id.starts_with("2025-06-01T12-00-00-"), "{}", run_id);
+    assert_eq!(report["hackathons"], 1);
+}
+
+#[tokio::test]
+async fn api_json_sources_mix_with_probed_pages() {
+    let root = temp_dir("pipeline-api-json");
+    let pages = pages_dir(&root, &[("http://foo.hackclub.com", JSON_LD_PAGE)]);
+    let api = MockServer::start(|_| {
+        Reply::json(r#"[{"name": "HackBar", "website": "https://hackbar.example/"}]"#)
+    })
+    .await;
+    let config = Config {
+        extra_sources: vec![Source::ApiJson {
+            url: api.url("/events"),
+        }],
+        ..config(&root, pages)
+    };
+
+    let found = run_pipeline(pipeline(config)).await.unwrap();
+
+    let mut sources: Vec<(&str, &str)> = found
+        .iter()
+        .map(|h| (h.name.as_str(), h.source.as_str()))
+        .collect();
+    sources.sort();
+    assert_eq!(sources[0], ("HackBar", "api"));
+    assert_eq!(sources[1].0, "HackFoo");
+    assert_eq!(sources.len(), 2);
+    assert_eq!(api.requests(), 1);
+}
+
+#[tokio::test]
+async fn summary_order_is_the_same_on_every_run() {
+    let events = [
+        (
+            "http://c.hackclub.com",
+            json_ld_page("Gamma", "https://c.test/", "2026-05-01"),
+        ),
+        (
+            "http://a.hackclub.com",
+            json_ld_page("Alpha", "https://a.test/", "2026-07-01"),
+        ),
+        (
+            "http://b.hackclub.com",
+            json_ld_page("Beta", "https://b.test/", "2026-03-01"),
+        ),
+        (
+            "http://d.hackclub.com",
+            json_ld_page("Delta", "https://d.test/", "2026-05-01"),
+        ),
+    ];
+    let pages: Vec<(&str, &str)> = events.iter().map(|(u, h)| (*u, h.as_str())).collect();
+
+    let mut summaries = Vec::new();
+    for run in 0..2 {
+        let root = temp_dir(&format!("pipeline-order-{}", run));
+        let dir = pages_dir(&root, &pages);
+        run_pipeline(pipeline(config(&root, dir))).await.unwrap();
+        summaries.push(fs::read_to_string(root.join("out/summary.json")).unwrap());
+    }
+
+    assert_eq!(summaries[0], summaries[1]);
+    let summary: Value = serde_json::from_str(&summaries[0]).unwrap();
+    let names: Vec<&str> = summary
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|h| h["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Beta", "Delta", "Gamma", "Alpha"]);
+}
+
+#[tokio::test]
+async fn each_hackathon_carries_the_source_it_came_from() {
+    let root = temp_dir("pipeline-sources");
+    let io_page = json_ld_page("HackIo", "https://bar.hackclub.io/", "2026-04-01");
+    let pages = pages_dir(
+        &root,
+        &[
+            ("http://foo.hackclub.com", JSON_LD_PAGE),
+            ("http://bar.hackclub.io", &io_page),
+        ],
+    );
+    let api = MockServer::start(|_| Reply::json(r#"[{"name": "HackApi"}]"#)).await;
+    let config = Config {
+        zones: vec!["hackclub.com".to_string(), "hackclub.io".to_string()],
+        extra_sources: vec![Source::ApiJson {
+            url: api.url("/events"),
+        }],
+        ..config(&root, pages)
+    };
+
+    let found = run_pipeline(pipeline(config)).await.unwrap();
+
+    let mut sources: Vec<(&str, &str)> = found
+        .iter()
+        .map(|h| (h.name.as_str(), h.source.as_str()))
+        .collect();
+    sources.sort();
+    assert_eq!(
+        sources,
+        [
+            ("HackApi", "api"),
+            ("HackFoo", "hackclub.com"),
+            ("HackIo", "hackclub.io"),
+        ]
+    );
+    let summary = read_json(root.join("out/summary.json"));
+    assert!(summary
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|h| h["source"] == "hackclub.io"));
+}
+
+#[tokio::test]
+async fn stats_only_writes_stats_and_never_calls_the_llm() {
+    let root = temp_dir("pipeline-stats-only");
+    let pages = pages_dir(
+        &root,
+        &[
+            ("http://foo.hackclub.com", JSON_LD_PAGE),
+            (
+                "http://bar.hackclub.com",
+                "<html><body>Hack night!</body></html>",
+            ),
+        ],
+    );
+    let llm = MockServer::start(|_| Reply::json("{}")).await;
+    let mut config = Config {
+        stats_only: true,
+        ..config(&root, pages)
+    };
+    config.llm.api_key = String::new();
+    config.llm.api_url = llm.url("/v1/chat/completions");
+
+    let found = run_pipeline(pipeline(config)).await.unwrap();
+
+    assert!(found.is_empty());
+    assert_eq!(llm.requests(), 0);
+    let out = root.join("out");
+    let stats = read_json(out.join("stats.json"));
+    assert_eq!(stats["probed"], 2);
+    assert_eq!(stats["live"], 2);
+    assert!(!out.join("summary.json").exists());
+}
+
+#[tokio::test]
+async fn reextract_runs_the_llm_over_each_saved_success() {
+    let root = temp_dir("pipeline-reextract");
+    let file = root.join("successes.json");
+    let saved = json!([
+        {"url": "http://foo.hackclub.com", "content": "<html><body>HackFoo hackathon</body></html>"},
+        {"url": "http://bar.hackclub.com", "content": "<html><body>HackBar hackathon</body></html>"},
+    ]);
+    fs::write(&file, saved.to_string()).unwrap();
+    // Name each event after the page it was asked about.
+    let llm = MockServer::start_with(|req: &Request| {
+        let name = if req.body.contains("foo.hackclub.com") {
+            "HackFoo"
+        } else {
+            "HackBar"
+        };
+        let events = json!([{"name": name, "url": "", "dates": "Unknown", "summary": ""}]);
+        let content = json!({"choices": [{"message": {"content": events.to_string()}}]});
+        Reply::json(content.to_string())
+    })
+    .await;
+    let mut config = Config {
+        from_dir: None,
+        ..config(&root, PathBuf::new())
+    };
+    config.llm.api_url = llm.url("/v1/chat/completions");
+
+    let found = run_pipeline(PipelineConfig {
+        successes_file: Some(file.clone()),
+        ..pipeline(config)
+    })
+    .await
+    .unwrap();
+
+    let mut names: Vec<(&str, &str)> = found
+        .iter()
+        .map(|h| (h.name.as_str(), h.url.as_str()))
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            ("HackBar", "http://bar.hackclub.com"),
+            ("HackFoo", "http://foo.hackclub.com"),
+        ]
+    );
+    assert_eq!(llm.requests(), 2);
+    assert_eq!(read_json(file), saved, "the input file is left alone");
+}
+
+#[tokio::test]
+async fn hackathons_from_a_redirect_target_carry_both_urls() {
+    let root = temp_dir("pipeline-redirect");
+    let file = root.join("successes.json");
+    let saved = json!([{
+        "url": "http://foo.hackclub.com",
+        "final_url": "https://devpost.example/hackathons/hackfoo",
+        "content": "<html><body>HackFoo hackathon</body></html>",
+    }]);
+    fs::write(&file, saved.to_string()).unwrap();
+    let llm = MockServer::start(|_| {
+        let events = json!([{
+            "name": "HackFoo", "url": "", "dates": "Unknown", "summary": "",
+            "register_url": "apply",
+        }]);
+        let content = json!({"choices": [{"message": {"content": events.to_string()}}]});
+        Reply::json(content.to_string())
+    })
+    .await;
+    let mut config = Config {
+        from_dir: None,
+        ..config(&root, PathBuf::new())
+    };
+    config.llm.api_url = llm.url("/v1/chat/completions");
+
+    let found = run_pipeline(PipelineConfig {
+        successes_file: Some(file),
+        ..pipeline(config)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(found.len(), 1);
+    let h = &found[0];
+    assert_eq!(h.url, "https://devpost.example/hackathons/hackfoo");
+    assert_eq!(
+        h.register_url.as_deref(),
+        Some("https://devpost.example/hackathons/apply")
+    );
+    assert_eq!(
+        h.redirected_from.as_deref(),
+        Some("http://foo.hackclub.com")
+    );
+    assert_eq!(
+        h.final_url.as_deref(),
+        Some("https://devpost.example/hackathons/hackfoo")
+    );
+    let summary = read_json(root.join("out/summary.json"));
+    assert_eq!(summary[0]["redirected_from"], "http://foo.hackclub.com");
+}
+
+#[tokio::test]
+async fn subdomains_redirecting_to_one_page_share_one_extraction() {
+    let root = temp_dir("pipeline-shared-redirect");
+    let file = root.join("successes.json");
+    let target = "https://devpost.example/hackathons/hackfoo";
+    let content = "<html><body>HackFoo hackathon</body></html>";
+    let saved = json!([
+        {"url": "http://foo.hackclub.com", "final_url": target, "content": content},
+        {"url": "http://bar.hackclub.com", "final_url": target, "content": content},
+    ]);
+    fs::write(&file, saved.to_string()).unwrap();
+    let llm = MockServer::start(|_| {
+        let events = json!([{"name": "HackFoo", "url": "", "dates": "Unknown", "summary": ""}]);
+        let content = json!({"choices": [{"message": {"content": events.to_string()}}]});
+        Reply::json(content.to_string())
+    })
+    .await;
+    let mut config = Config {
+        from_dir: None,
+        ..config(&root, PathBuf::new())
+    };
+    config.llm.api_url = llm.url("/v1/chat/completions");
+
+    let found = run_pipeline(PipelineConfig {
+        successes_file: Some(file),
+        ..pipeline(config)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(llm.requests(), 1);
+    assert_eq!(found.len(), 1);
+    let h = &found[0];
+    assert_eq!(h.final_url.as_deref(), Some(target));
+    assert_eq!(
+        h.redirected_from.as_deref(),
+        Some("http://foo.hackclub.com")
+    );
+    assert_eq!(h.subdomain.as_deref(), Some("http://foo.hackclub.com"));
+    assert_eq!(h.also_from, ["http://bar.hackclub.com"]);
+    assert_eq!(h.source, "hackclub.com");
+}
+
+#[tokio::test]
+async fn metrics_file_reports_the_run() {
+    let root = temp_dir("pipeline-metrics");
+    let pages = pages_dir(&root, &[("http://foo.hackclub.com", JSON_LD_PAGE)]);
+    let config = Config {
+        metrics_file: Some(root.join("radar.prom")),
+        ..config(&root, pages)
+    };
+
+    run_pipeline(pipeline(config)).await.unwrap();
+
+    let metrics = fs::read_to_string(root.join("radar.prom")).unwrap();
+    let lines: Vec<&str> = metrics.lines().collect();
+    assert!(
+        lines.contains(&"hackclub_radar_probes_total 1"),
+        "{}",
+        metrics
+    );
+    assert!(
+        lines.contains(&"hackclub_radar_hackathons_found 1"),
+        "{}",
+        metrics
+    );
+    assert!(
+        lines.contains(&"hackclub_radar_llm_errors_total 0"),
+        "{}",
+        metrics
+    );
+    assert!(metrics.contains("hackclub_radar_run_duration_seconds "));
+}
+
+#[tokio::test]
+async fn pages_without_event_keywords_skip_the_llm_unless_the_filter_is_off() {
+    let pages = [
+        (
+            "http://foo.hackclub.com",
+            "<html><body>Our hackathon is in May.</body></html>",
+        ),
+        (
+            "http://me.hackclub.com",
+            "<html><body>My portfolio and blog.</body></html>",
+        ),
+    ];
+    let llm =
+        MockServer::start(|_| Reply::json(r#"{"choices": [{"message": {"content": "[]"}}]}"#))
+            .await;
+
+    for keyword_filter in [true, false] {
+        let root = temp_dir(&format!("pipeline-keywords-{}", keyword_filter));
+        let dir = pages_dir(&root, &pages);
+        let mut config = Config {
+            keyword_filter,
+            write_skipped: true,
+            ..config(&root, dir)
+        };
+        config.llm.api_url = llm.url("/v1/chat/completions");
+        let before = llm.requests();
+
+        run_pipeline(pipeline(config)).await.unwrap();
+
+        let skipped = read_json(root.join("out/skipped.json"));
+        if keyword_filter {
+            assert_eq!(llm.requests() - before, 1);
+            assert_eq!(
+                skipped,
+                json!([{"url": "http://me.hackclub.com", "reason": "no-event-keywords"}])
+            );
+        } else {
+            assert_eq!(llm.requests() - before, 2);
+            assert_eq!(skipped, json!([]));
+        }
+    }
+}
+
+#[tokio::test]
+async fn eval_scores_the_llm_against_json_ld() {
+    let root = temp_dir("pipeline-eval");
+    let pages = pages_dir(
+        &root,
+        &[
+            ("http://foo.hackclub.com", JSON_LD_PAGE),
+            (
+                "http://bar.hackclub.com",
+                "<html><body>No structured data.</body></html>",
+            ),
+        ],
+    );
+    let llm = MockServer::start(|_| {
+        let events = json!([
+            {"name": "HackFoo", "url": "", "dates": "March 1, 2026", "summary": ""},
+            {"name": "Bonus Jam", "url": "", "dates": "Unknown", "summary": ""},
+        ]);
+        let content = json!({"choices": [{"message": {"content": events.to_string()}}]});
+        Reply::json(content.to_string())
+    })
+    .await;
+    let mut config = config(&root, pages);
+    config.llm.api_url = llm.url("/v1/chat/completions");
+
+    run_pipeline(PipelineConfig {
+        eval: true,
+        ..pipeline(config)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(llm.requests(), 1, "only the JSON-LD page is evaluated");
+    let report = read_json(root.join("out/eval.json"));
+    assert_eq!(
+        report["total"],
+        json!({"reference": 1, "extracted": 2, "matched": 1})
+    );
+    assert_eq!(report["precision"], 0.5);
+    assert_eq!(report["recall"], 1.0);
+    assert_eq!(report["pages"][0]["url"], "http://foo.hackclub.com");
+    assert_eq!(report["pages"][0]["error"], Value::Null);
+}
+
+#[tokio::test]
+async fn json_ld_pages_skip_the_llm_and_plain_pages_still_use_it() {
+    let root = temp_dir("pipeline-json-ld");
+    let undated = r#"<html><head><script type="application/ld+json">{"@type": "Event",
+"name": "HackBaz", "url": "https://baz.test/"}</script></head><body>A hackathon.</body></html>"#;
+    let pages = pages_dir(
+        &root,
+        &[
+            ("http://foo.hackclub.com", JSON_LD_PAGE),
+            (
+                "http://bar.hackclub.com",
+                "<html><body>HackBar hackathon</body></html>",
+            ),
+            ("http://baz.hackclub.com", undated),
+        ],
+    );
+    let llm =
+        MockServer::start(|_| Reply::json(r#"{"choices": [{"message": {"content": "[]"}}]}"#))
+            .await;
+    let mut config = config(&root, pages);
+    config.llm.api_url = llm.url("/v1/chat/completions");
+
+    let found = run_pipeline(pipeline(config)).await.unwrap();
+
+    let mut asked: Vec<bool> = Vec::new();
+    for req in llm.received() {
+        asked.push(req.body.contains("bar.hackclub.com"));
+        assert!(
+            !req.body.contains("foo.hackclub.com"),
+            "the JSON-LD page was sent"
+        );
+    }
+    asked.sort();
+    // The plain page and the one whose JSON-LD lacks a date.
+    assert_eq!(asked, [false, true]);
+    let foo = found.iter().find(|h| h.name == "HackFoo").unwrap();
+    assert_eq!(foo.extracted_by, Extraction::JsonLd);
+}
+
+#[tokio::test]
+async fn only_changed_skips_pages_whose_content_is_stable() {
+    let root = temp_dir("pipeline-only-changed");
+    let stable = "<html><body>Stable hackathon</body></html>";
+    let pages = pages_dir(
+        &root,
+        &[
+            ("http://stable.hackclub.com", stable),
+            (
+                "http://edited.hackclub.com",
+                "<html><body>New hackathon dates</body></html>",
+            ),
+            (
+                "http://new.hackclub.com",
+                "<html><body>Brand new hackathon</body></html>",
+            ),
+        ],
+    );
+    let state = root.join("state.json");
+    ChangeState {
+        entries: Default::default(),
+        pages: [
+            ("http://stable.hackclub.com", fingerprint(stable)),
+            ("http://edited.hackclub.com", fingerprint("<p>old</p>")),
+        ]
+        .into_iter()
+        .map(|(url, fp)| (url.to_string(), fp))
+        .collect(),
+    }
+    .save(&state)
+    .await
+    .unwrap();
+    let llm =
+        MockServer::start(|_| Reply::json(r#"{"choices": [{"message": {"content": "[]"}}]}"#))
+            .await;
+    let mut config = Config {
+        only_changed: Some(state.clone()),
+        write_skipped: true,
+        ..config(&root, pages)
+    };
+    config.llm.api_url = llm.url("/v1/chat/completions");
+
+    run_pipeline(pipeline(config)).await.unwrap();
+
+    assert_eq!(llm.requests(), 2);
+    assert!(llm
+        .received()
+        .iter()
+        .all(|r| !r.body.contains("stable.hackclub.com")));
+    let skipped = read_json(root.join("out/skipped.json"));
+    assert_eq!(
+        skipped,
+        json!([{"url": "http://stable.hackclub.com", "reason": "unchanged"}])
+    );
+    // The new fingerprints are saved for the next run.
+    let saved = ChangeState::load(&state).await.unwrap();
+    assert_eq!(saved.pages.len(), 3);
+}
+
+#[tokio::test]
+async fn parked_and_soft_404_pages_are_listed_with_their_reasons() {
+    let root = temp_dir("pipeline-skip-reasons");
+    let pages = pages_dir(
+        &root,
+        &[
+            (
+                "http://old.hackclub.com",
+                "<html><head><title>old.hackclub.com</title></head><body>This domain is for sale! Buy this domain today.</body></html>",
+            ),
+            (
+                "http://gone.hackclub.com",
+                "<html><head><title>404 Not Found</title></head><body>Nothing here.</body></html>",
+            ),
+            ("http://foo.hackclub.com", JSON_LD_PAGE),
+        ],
+    );
+    let llm =
+        MockServer::start(|_| Reply::json(r#"{"choices": [{"message": {"content": "[]"}}]}"#))
+            .await;
+    let mut config = Config {
+        write_skipped: true,
+        ..config(&root, pages)
+    };
+    config.llm.api_url = llm.url("/v1/chat/completions");
+
+    run_pipeline(pipeline(config)).await.unwrap();
+
+    let skipped = read_json(root.join("out/skipped.json"));
+    let mut reasons: Vec<(String, String)> = skipped
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| {
+            (
+                s["url"].as_str().unwrap().to_string(),
+                s["reason"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    reasons.sort();
+    assert_eq!(
+        reasons,
+        [
+            (
+                "http://gone.hackclub.com".to_string(),
+                "soft-404".to_string()
+            ),
+            ("http://old.hackclub.com".to_string(), "parked".to_string()),
+        ]
+    );
+    assert_eq!(llm.requests(), 0);
+}
@@ -0,0 +1,609 @@
+//! `probe` and `probe_with_retries` against a local mock server.
+
+mod common;
+
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use reqwest::Client;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use common::{refused_url, MockServer, Reply, Request};
+use hackclub_dns_fetcher::config::{Config, ResolveOverride};
+use hackclub_dns_fetcher::probe::{
+    build_probe_client, build_proxy_clients, probe, probe_paths, probe_with_retries, verify_urls,
+    ByteBudget, ClientPool, HostBreaker, ProbeCoalescer, ProbeOptions, RedirectRules,
+};
+use hackclub_dns_fetcher::resolve::{CachingResolver, PublicOnly};
+use hackclub_dns_fetcher::retry::RetryPolicy;
+use hackclub_dns_fetcher::robots::RobotsCache;
+use hackclub_dns_fetcher::types::{Hackathon, ProbeResult};
+
+/// A client like the probe client: no automatic redirects, short timeout.
+fn client() -> Client {
+    Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(Duration::from_millis(500))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn ok_html_keeps_status_body_and_headers() {
+    let server = MockServer::start(|_| Reply::html("<h1>Hack Night</h1>")).await;
+    let url = server.url("/");
+
+    let result = probe(&client(), &url).await;
+
+    assert_eq!(result.subdomain, url);
+    assert_eq!(result.status, Some(200));
+    assert_eq!(result.content.as_deref(), Some("<h1>Hack Night</h1>"));
+    assert_eq!(result.error, None);
+    assert_eq!(result.final_scheme.as_deref(), Some("http"));
+    assert_eq!(result.final_url, None);
+    assert_eq!(
+        result.headers.get("content-type").map(String::as_str),
+        Some("text/html; charset=utf-8")
+    );
+    assert!(!result.truncated);
+    assert_eq!(result.attempts, 1);
+    assert!(result.ttfb_ms.is_some() && result.elapsed_ms.is_some());
+}
+
+#[tokio::test]
+async fn ttfb_stops_at_the_headers_and_elapsed_covers_the_body() {
+    let server = MockServer::start(|_| {
+        Reply::Delayed(
+            Duration::from_millis(100),
+            Box::new(Reply::SlowBody(
+                Duration::from_millis(200),
+                Box::new(Reply::html("<p>slow</p>")),
+            )),
+        )
+    })
+    .await;
+
+    let result = probe(&client(), &server.url("/")).await;
+
+    assert_eq!(result.content.as_deref(), Some("<p>slow</p>"));
+    let (ttfb, elapsed) = (result.ttfb_ms.unwrap(), result.elapsed_ms.unwrap());
+    assert!(ttfb >= 100, "ttfb {}", ttfb);
+    assert!(elapsed >= ttfb + 150, "ttfb {}, elapsed {}", ttfb, elapsed);
+}
+
+#[tokio::test]
+async fn not_found_is_a_response_not_an_error() {
+    let server = MockServer::start(|_| Reply::Status(404, "text/html", "gone")).await;
+
+    let result = probe(&client(), &server.url("/")).await;
+
+    assert_eq!(result.status, Some(404));
+    assert_eq!(result.content.as_deref(), Some("gone"));
+    assert_eq!(result.error, None);
+}
+
+#[tokio::test]
+async fn server_error_is_recorded_without_retrying() {
+    let server = MockServer::start(|_| Reply::Status(500, "text/plain", "oops")).await;
+
+    let result = probe(&client(), &server.url("/")).await;
+
+    assert_eq!(result.status, Some(500));
+    assert_eq!(result.content.as_deref(), Some("oops"));
+    assert_eq!(result.error, None);
+    assert_eq!(server.requests(), 1);
+}
+
+#[tokio::test]
+async fn timeout_leaves_only_an_error() {
+    let server = MockServer::start(|_| Reply::Hang).await;
+
+    let result = probe(&client(), &server.url("/")).await;
+
+    assert_eq!(result.status, None);
+    assert_eq!(result.content, None);
+    assert!(result.error.is_some());
+    assert_eq!(result.final_scheme, None);
+    assert_eq!(result.attempts, 1);
+}
+
+#[tokio::test]
+async fn connection_refused_leaves_only_an_error() {
+    let url = refused_url().await;
+
+    let result = probe(&client(), &url).await;
+
+    assert_eq!(result.subdomain, url);
+    assert_eq!(result.status, None);
+    assert_eq!(result.content, None);
+    assert!(result.error.is_some());
+    assert_eq!(result.ttfb_ms, None);
+}
+
+#[tokio::test]
+async fn retries_server_errors_until_success() {
+    let server = MockServer::start(|n| match n {
+        0 => Reply::Status(503, "text/plain", "busy"),
+        _ => Reply::html("<p>back</p>"),
+    })
+    .await;
+    let opts = ProbeOptions {
+        retry: RetryPolicy {
+            max_retries: 2,
+            base_delay_ms: 1,
+            // The default retryable statuses: 429 and 5xx.
+            ..Config::default().probe_retry
+        },
+        ..ProbeOptions::default()
+    };
+
+    let result = probe_with_retries(&client(), &server.url("/"), &opts).await;
+
+    assert_eq!(result.status, Some(200));
+    assert_eq!(result.content.as_deref(), Some("<p>back</p>"));
+    assert_eq!(result.attempts, 2);
+    assert_eq!(server.requests(), 2);
+}
+
+#[tokio::test]
+async fn https_refusal_falls_back_to_http() {
+    // The mock closes TLS handshakes, as an HTTP-only host would.
+    let server = MockServer::start(|_| Reply::html("plain")).await;
+
+    let result = probe(&client(), &server.url("/")).await;
+
+    assert_eq!(result.status, Some(200));
+    assert_eq!(result.final_scheme.as_deref(), Some("http"));
+}
+
+#[tokio::test]
+async fn https_timeout_does_not_fall_back_to_http() {
+    // Accepts connections and never answers, over either scheme.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let accepted = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&accepted);
+    tokio::spawn(async move {
+        let mut open = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            counter.fetch_add(1, Ordering::SeqCst);
+            open.push(stream);
+        }
+    });
+
+    let started = Instant::now();
+    let result = probe(&client(), &url).await;
+
+    assert_eq!(result.status, None);
+    assert!(result.error.is_some());
+    assert_eq!(result.subdomain, url);
+    assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    assert!(started.elapsed() < Duration::from_millis(900));
+}
+
+#[tokio::test]
+async fn redirects_to_private_addresses_are_not_followed() {
+    let server = MockServer::start(|_| {
+        Reply::with(302, "").header("Location", "http://internal.test/admin")
+    })
+    .await;
+    let opts = ProbeOptions {
+        public_only: Some(PublicOnly {
+            resolver: CachingResolver::new(16),
+            overrides: vec![ResolveOverride {
+                host: "internal.test".to_string(),
+                addr: "10.0.0.1".parse().unwrap(),
+            }],
+        }),
+        ..ProbeOptions::default()
+    };
+
+    let result = probe_with_retries(&client(), &server.url("/"), &opts).await;
+
+    assert_eq!(result.status, Some(302));
+    assert_eq!(result.content, None);
+    let error = result.error.unwrap();
+    assert!(error.contains("non-public address 10.0.0.1"), "{}", error);
+    assert_eq!(server.requests(), 1);
+}
+
+#[tokio::test]
+async fn robots_disallowed_paths_are_skipped_without_a_request() {
+    let server = MockServer::start_with(|req: &Request| match req.path.as_str() {
+        "/robots.txt" => Reply::Status(200, "text/plain", "User-agent: *\nDisallow: /private\n"),
+        _ => Reply::html("<h1>Hack Night</h1>"),
+    })
+    .await;
+    let opts = ProbeOptions {
+        robots: Some(Arc::new(RobotsCache::new(16))),
+        ..ProbeOptions::default()
+    };
+
+    let blocked = probe_with_retries(&client(), &server.url("/private/x"), &opts).await;
+    let allowed = probe_with_retries(&client(), &server.url("/events"), &opts).await;
+
+    assert!(blocked.error.unwrap().contains("disallowed by robots.txt"));
+    assert_eq!(blocked.attempts, 0);
+    assert_eq!(allowed.status, Some(200));
+    // robots.txt is fetched once per origin.
+    let paths: Vec<String> = server.received().into_iter().map(|r| r.path).collect();
+    assert_eq!(paths, ["/robots.txt", "/events"]);
+}
+
+#[tokio::test]
+async fn robots_rules_are_refetched_only_after_eviction() {
+    let robots = |req: &Request| match req.path.as_str() {
+        "/robots.txt" => Reply::Status(200, "text/plain", "User-agent: *\nDisallow:\n"),
+        _ => Reply::html("ok"),
+    };
+    let (a, b) = (
+        MockServer::start_with(robots).await,
+        MockServer::start_with(robots).await,
+    );
+    let opts = ProbeOptions {
+        robots: Some(Arc::new(RobotsCache::new(1))),
+        ..ProbeOptions::default()
+    };
+    let client = client();
+    let robots_fetches = |server: &MockServer| {
+        server
+            .received()
+            .iter()
+            .filter(|r| r.path == "/robots.txt")
+            .count()
+    };
+
+    for path in ["/one", "/two"] {
+        probe_with_retries(&client, &a.url(path), &opts).await;
+    }
+    assert_eq!(robots_fetches(&a), 1);
+
+    // With room for one origin, visiting `b` evicts `a`'s rules.
+    probe_with_retries(&client, &b.url("/"), &opts).await;
+    probe_with_retries(&client, &a.url("/three"), &opts).await;
+    assert_eq!(robots_fetches(&b), 1);
+    assert_eq!(robots_fetches(&a), 2);
+}
+
+#[tokio::test]
+async fn missing_robots_txt_allows_everything() {
+    let server = MockServer::start_with(|req: &Request| match req.path.as_str() {
+        "/robots.txt" => Reply::Status(404, "text/plain", "not found"),
+        _ => Reply::html("ok"),
+    })
+    .await;
+    let opts = ProbeOptions {
+        robots: Some(Arc::new(RobotsCache::new(16))),
+        ..ProbeOptions::default()
+    };
+
+    let result = probe_with_retries(&client(), &server.url("/private"), &opts).await;
+
+    assert_eq!(result.status, Some(200));
+}
+
+#[tokio::test]
+async fn paths_on_one_host_are_sequential_while_hosts_run_concurrently() {
+    let slow = || Reply::Delayed(Duration::from_millis(200), Box::new(Reply::html("ok")));
+    let a = MockServer::start(move |_| slow()).await;
+    let b = MockServer::start(move |_| slow()).await;
+    let prober = ProbeCoalescer::new(client(), ProbeOptions::default());
+    let paths = vec!["/events".to_string()];
+    let (url_a, url_b) = (a.url(""), b.url(""));
+
+    let started = Instant::now();
+    let (from_a, from_b) = tokio::join!(
+        probe_paths(&prober, &url_a, &paths, Duration::ZERO),
+        probe_paths(&prober, &url_b, &paths, Duration::ZERO),
+    );
+    let elapsed = started.elapsed();
+
+    assert_eq!(from_a.len(), 2);
+    assert_eq!(from_b.len(), 2);
+    // Each host's two requests queue behind each other (≥ 400ms), but the
+    // hosts overlap, so the whole run isn't 800ms.
+    assert!(elapsed >= Duration::from_millis(400), "{:?}", elapsed);
+    assert!(elapsed < Duration::from_millis(700), "{:?}", elapsed);
+}
+
+#[tokio::test]
+async fn resolve_override_sends_the_request_to_the_pinned_address() {
+    let server = MockServer::start(|_| Reply::html("pinned")).await;
+    let port = server.authority().rsplit(':').next().unwrap().to_string();
+    let config = Config {
+        resolve: vec!["foo.hackclub.test:127.0.0.1".parse().unwrap()],
+        ..Config::default()
+    };
+    let client = build_probe_client(&config, &CachingResolver::new(16)).unwrap();
+
+    let url = format!("http://foo.hackclub.test:{}/", port);
+    let result = probe_with_retries(&client, &url, &ProbeOptions::default()).await;
+
+    assert_eq!(result.content.as_deref(), Some("pinned"));
+    let host = server.received()[0].header("host").unwrap().to_string();
+    assert_eq!(host, format!("foo.hackclub.test:{}", port));
+}
+
+#[tokio::test]
+async fn truncated_gzip_body_is_a_decode_error() {
+    // Promises 200 gzip bytes, sends the first few, and hangs up.
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).await;
+            let head = "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\
+                Content-Encoding: gzip\r\nContent-Length: 200\r\n\r\n";
+            let _ = stream.write_all(head.as_bytes()).await;
+            let _ = stream
+                .write_all(&[0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00])
+                .await;
+        }
+    });
+
+    let result = probe_with_retries(&client(), &url, &ProbeOptions::default()).await;
+
+    // Headers arrived, so this is not a connection error.
+    assert_eq!(result.status, Some(200));
+    assert_eq!(result.content, None);
+    let error = result.error.unwrap();
+    assert!(error.starts_with("decode error: "), "{}", error);
+    assert_eq!(result.attempts, 1);
+}
+
+#[tokio::test]
+async fn only_configured_statuses_are_retried() {
+    let opts = ProbeOptions {
+        retry: RetryPolicy {
+            max_retries: 2,
+            base_delay_ms: 1,
+            max_delay_ms: 10,
+            retry_statuses: BTreeSet::from([503]),
+        },
+        ..ProbeOptions::default()
+    };
+    let flaky = MockServer::start(|n| match n {
+        0 => Reply::Status(503, "text/plain", "busy"),
+        _ => Reply::html("ok"),
+    })
+    .await;
+    let broken = MockServer::start(|_| Reply::Status(500, "text/plain", "oops")).await;
+
+    let retried = probe_with_retries(&client(), &flaky.url("/"), &opts).await;
+    let not_retried = probe_with_retries(&client(), &broken.url("/"), &opts).await;
+
+    assert_eq!(retried.status, Some(200));
+    assert_eq!(flaky.requests(), 2);
+    assert_eq!(not_retried.status, Some(500));
+    assert_eq!(not_retried.attempts, 1);
+    assert_eq!(broken.requests(), 1);
+}
+
+#[tokio::test]
+async fn concurrent_probes_of_one_url_share_a_request() {
+    let server = MockServer::start(|_| {
+        Reply::Delayed(Duration::from_millis(100), Box::new(Reply::html("once")))
+    })
+    .await;
+    let prober = ProbeCoalescer::new(client(), ProbeOptions::default());
+    let (plain, slashed) = (server.url(""), server.url("/"));
+
+    let (a, b) = tokio::join!(prober.probe(&plain), prober.probe(&slashed));
+
+    assert_eq!(a.content.as_deref(), Some("once"));
+    assert_eq!(b.content.as_deref(), Some("once"));
+    assert_eq!(server.requests(), 1);
+
+    // Once it has finished, a later probe makes a fresh request.
+    prober.probe(&slashed).await;
+    assert_eq!(server.requests(), 2);
+}
+
+#[tokio::test]
+async fn probing_stops_once_the_total_byte_cap_is_crossed() {
+    let server = MockServer::start(|_| Reply::with(200, "x".repeat(10_000))).await;
+    let budget = Arc::new(ByteBudget::new(15_000));
+    let opts = ProbeOptions {
+        byte_budget: Some(Arc::clone(&budget)),
+        ..ProbeOptions::default()
+    };
+
+    let mut results = Vec::new();
+    for path in ["/a", "/b", "/c", "/d"] {
+        results.push(probe_with_retries(&client(), &server.url(path), &opts).await);
+    }
+
+    assert_eq!(results[0].content.as_ref().unwrap().len(), 10_000);
+    assert!(!results[0].truncated);
+    // The body that crosses the cap is cut short.
+    assert!(results[1].truncated);
+    for skipped in &results[2..] {
+        assert_eq!(
+            skipped.error.as_deref(),
+            Some("skipped: total download cap reached")
+        );
+    }
+    assert_eq!(server.requests(), 2);
+    assert!(budget.exhausted());
+}
+
+#[tokio::test]
+async fn verify_urls_flags_live_and_dead_links() {
+    let server = MockServer::start_with(|req: &Request| {
+        if req.path == "/live" {
+            Reply::html("here")
+        } else {
+            Reply::Status(404, "text/html", "gone")
+        }
+    })
+    .await;
+    let entry = |name: &str, url: String| -> Hackathon {
+        serde_json::from_value(serde_json::json!({
+            "name": name, "url": url, "dates": "Unknown", "summary": "",
+        }))
+        .unwrap()
+    };
+    let mut hackathons = vec![
+        entry("Live", server.url("/live")),
+        entry("Dead", server.url("/dead")),
+        entry("Live again", server.url("/live")),
+    ];
+    let prober = ProbeCoalescer::new(client(), ProbeOptions::default());
+
+    verify_urls(&prober, &mut hackathons, 2).await;
+
+    let flags: Vec<Option<bool>> = hackathons.iter().map(|h| h.url_reachable).collect();
+    assert_eq!(flags, [Some(true), Some(false), Some(true)]);
+    assert_eq!(server.requests(), 2, "a shared URL is probed once");
+}
+
+#[tokio::test]
+async fn a_slow_host_with_a_longer_override_is_not_cut_off() {
+    // The client gives up after 500ms; this host takes 800ms.
+    let server = MockServer::start(|_| {
+        Reply::Delayed(Duration::from_millis(800), Box::new(Reply::html("slow")))
+    })
+    .await;
+    let pinned = server.url("/");
+    let default = pinned.replace("127.0.0.1", "localhost");
+    let opts = ProbeOptions {
+        timeout_overrides: [("127.0.0.1".to_string(), Duration::from_secs(3))].into(),
+        ..ProbeOptions::default()
+    };
+
+    let client = client();
+
+    let (slow, fast) = tokio::join!(
+        probe_with_retries(&client, &pinned, &opts),
+        probe_with_retries(&client, &default, &opts),
+    );
+
+    assert_eq!(slow.status, Some(200), "{:?}", slow.error);
+    assert_eq!(slow.content.as_deref(), Some("slow"));
+    assert_eq!(fast.status, None);
+    assert!(fast.error.is_some());
+}
+
+#[tokio::test]
+async fn redirect_to_an_external_page_yields_its_content_and_final_url() {
+    let target = MockServer::start(|_| Reply::html("<h1>HackFoo on Devpost</h1>")).await;
+    let target_url = target.url("/hackathons/hackfoo");
+    let location = target_url.clone();
+    let subdomain =
+        MockServer::start(move |_| Reply::with(301, "").header("Location", &location)).await;
+
+    let result = probe_with_retries(&client(), &subdomain.url("/"), &ProbeOptions::default()).await;
+
+    assert_eq!(result.subdomain, subdomain.url("/"));
+    assert_eq!(result.status, Some(200));
+    assert_eq!(result.final_url.as_deref(), Some(target_url.as_str()));
+    assert_eq!(
+        result.content.as_deref(),
+        Some("<h1>HackFoo on Devpost</h1>")
+    );
+    assert_eq!((subdomain.requests(), target.requests()), (1, 1));
+}
+
+/// Probe a `127.0.0.1` page that redirects to a `localhost` one, allowing
+/// external redirects only to `allowlist`.
+async fn probe_external_redirect(allowlist: &[&str]) -> (ProbeResult, MockServer, String) {
+    let target = MockServer::start(|_| Reply::html("<h1>HackFoo</h1>")).await;
+    let target_url = target.url("/hackfoo").replace("127.0.0.1", "localhost");
+    let location = target_url.clone();
+    let subdomain =
+        MockServer::start(move |_| Reply::with(302, "").header("Location", &location)).await;
+    let opts = ProbeOptions {
+        redirect_rules: RedirectRules {
+            allowlist: allowlist.iter().map(|s| s.to_string()).collect(),
+            zones: Vec::new(),
+        },
+        ..ProbeOptions::default()
+    };
+
+    let result = probe_with_retries(&client(), &subdomain.url("/"), &opts).await;
+    (result, target, target_url)
+}
+
+#[tokio::test]
+async fn allowlisted_external_redirects_are_followed() {
+    let (result, target, target_url) = probe_external_redirect(&["localhost"]).await;
+
+    assert_eq!(result.status, Some(200));
+    assert_eq!(result.content.as_deref(), Some("<h1>HackFoo</h1>"));
+    assert_eq!(result.final_url.as_deref(), Some(target_url.as_str()));
+    assert_eq!(target.requests(), 1);
+}
+
+#[tokio::test]
+async fn other_external_redirects_are_recorded_but_not_followed() {
+    let (result, target, target_url) = probe_external_redirect(&["devpost.com"]).await;
+
+    assert_eq!(result.status, Some(302));
+    assert_eq!(result.content, None);
+    let error = result.error.unwrap();
+    assert!(error.contains(&target_url), "{}", error);
+    assert!(error.contains("not followed"), "{}", error);
+    assert_eq!(target.requests(), 0);
+}
+
+#[tokio::test]
+async fn a_failing_host_is_short_circuited_while_others_proceed() {
+    let dead = refused_url().await;
+    let live = MockServer::start(|_| Reply::html("<p>up</p>")).await;
+    let opts = ProbeOptions {
+        breaker: Some(Arc::new(HostBreaker::new(3))),
+        ..ProbeOptions::default()
+    };
+
+    for path in ["a", "b", "c"] {
+        let result = probe_with_retries(&client(), &format!("{}{}", dead, path), &opts).await;
+        let error = result.error.unwrap();
+        assert!(!error.contains("circuit open"), "{}", error);
+    }
+    let result = probe_with_retries(&client(), &format!("{}d", dead), &opts).await;
+    assert_eq!(
+        result.error.as_deref(),
+        Some("host circuit open: too many consecutive failures")
+    );
+
+    let result = probe_with_retries(&client(), &live.url("/"), &opts).await;
+    assert_eq!(result.status, Some(200));
+    assert_eq!(live.requests(), 1);
+}
+
+#[tokio::test]
+async fn probes_rotate_across_the_configured_proxies() {
+    // Each "proxy" answers requests itself, recording the absolute URLs it was sent.
+    let mut proxies = Vec::new();
+    for _ in 0..3 {
+        proxies.push(MockServer::start(|_| Reply::html("via proxy")).await);
+    }
+    let urls: Vec<String> = proxies.iter().map(|p| p.url("")).collect();
+    let clients =
+        build_proxy_clients(&Config::default(), &CachingResolver::new(16), &urls).unwrap();
+    let prober = ProbeCoalescer::with_pool(ClientPool::new(clients), ProbeOptions::default());
+
+    for i in 0..6 {
+        let result = prober
+            .probe(&format!("http://site{}.hackclub.test/", i))
+            .await;
+        assert_eq!(result.content.as_deref(), Some("via proxy"));
+    }
+
+    let hits: Vec<Vec<String>> = proxies
+        .iter()
+        .map(|p| p.received().into_iter().map(|r| r.path).collect())
+        .collect();
+    assert_eq!(
+        hits,
+        [
+            ["http://site0.hackclub.test/", "http://site3.hackclub.test/"],
+            ["http://site1.hackclub.test/", "http://site4.hackclub.test/"],
+            ["http://site2.hackclub.test/", "http://site5.hackclub.test/"],
+        ]
+    );
+}
@@ -0,0 +1,107 @@
+//! Retry classification of real request failures and `send_with_retries`.
+
+mod common;
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use reqwest::Client;
+
+use common::{refused_url, MockServer, Reply};
+use hackclub_dns_fetcher::retry::{send_with_retries, Attempt, RetryPolicy};
+
+fn client() -> Client {
+    Client::builder()
+        .timeout(Duration::from_millis(300))
+        .build()
+        .unwrap()
+}
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 2,
+        base_delay_ms: 10,
+        max_delay_ms: 50,
+        retry_statuses: BTreeSet::from([429, 500, 502, 503, 504]),
+    }
+}
+
+#[tokio::test]
+async fn timeouts_are_retryable() {
+    let server = MockServer::start(|_| Reply::Hang).await;
+
+    let err = client().get(server.url("/")).send().await.unwrap_err();
+
+    assert!(err.is_timeout());
+    assert!(policy().is_retryable(Attempt::Error(&err)));
+}
+
+#[tokio::test]
+async fn connection_failures_are_retryable() {
+    let err = client().get(refused_url().await).send().await.unwrap_err();
+
+    assert!(policy().is_retryable(Attempt::Error(&err)));
+}
+
+#[tokio::test]
+async fn retries_a_configured_status_until_it_clears() {
+    let server = MockServer::start(|n| match n {
+        0 => Reply::Status(503, "text/plain", "busy"),
+        _ => Reply::Status(200, "text/plain", "ok"),
+    })
+    .await;
+    let client = client();
+
+    let resp = send_with_retries(&policy(), || client.get(server.url("/")))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), 200);
+    assert_eq!(server.requests(), 2);
+}
+
+#[tokio::test]
+async fn does_not_retry_a_404() {
+    let server = MockServer::start(|_| Reply::Status(404, "text/plain", "gone")).await;
+    let client = client();
+
+    let resp = send_with_retries(&policy(), || client.get(server.url("/")))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), 404);
+    assert_eq!(server.requests(), 1);
+}
+
+#[tokio::test]
+async fn waits_as_long_as_retry_after_asks() {
+    let server = MockServer::start(|n| match n {
+        0 => Reply::with(429, "slow down").header("Retry-After", "0"),
+        _ => Reply::Status(200, "text/plain", "ok"),
+    })
+    .await;
+    let client = client();
+
+    let resp = send_with_retries(&policy(), || client.get(server.url("/")))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), 200);
+    assert_eq!(server.requests(), 2);
+}
+
+#[tokio::test]
+async fn gives_up_when_retry_after_exceeds_the_cap() {
+    let server =
+        MockServer::start(|_| Reply::with(429, "slow down").header("Retry-After", "3600")).await;
+    let client = client();
+
+    let started = std::time::Instant::now();
+    let resp = send_with_retries(&policy(), || client.get(server.url("/")))
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), 429);
+    assert_eq!(server.requests(), 1);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
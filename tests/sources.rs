@@ -0,0 +1,68 @@
+//! Structured sources (`--api-json`) fetched from a mock JSON endpoint.
+
+mod common;
+
+use common::{MockServer, Reply};
+use hackclub_dns_fetcher::error::RadarError;
+use hackclub_dns_fetcher::sources::{fetch_source, Source};
+use hackclub_dns_fetcher::types::{Confidence, EventFormat, Extraction};
+use reqwest::Client;
+
+/// Two events in the shape of HackClub's hackathons API.
+const EVENTS: &str = r#"[
+    {"name": "HackFoo", "website": "https://hackfoo.example/", "start": "2026-03-01T09:00:00Z",
+     "end": "2026-03-02T18:00:00Z", "city": "Boston", "state": "MA", "country": "USA",
+     "virtual": false, "hybrid": false},
+    {"title": "Online Jam", "start": "2026-04-10T00:00:00Z", "virtual": true,
+     "description": "A weekend of building online."},
+    {"website": "https://nameless.example/"}
+]"#;
+
+#[tokio::test]
+async fn api_json_events_map_onto_hackathons() {
+    let server = MockServer::start(|_| Reply::json(EVENTS)).await;
+    let source = Source::ApiJson {
+        url: server.url("/api/events/upcoming"),
+    };
+
+    let found = fetch_source(&Client::new(), &source).await.unwrap();
+
+    assert_eq!(found.len(), 2, "the nameless entry is skipped");
+    let foo = &found[0];
+    assert_eq!(foo.name, "HackFoo");
+    assert_eq!(foo.url, "https://hackfoo.example/");
+    assert_eq!(foo.dates, "2026-03-01 – 2026-03-02");
+    assert_eq!(foo.summary, "HackFoo in Boston, MA, USA");
+    assert_eq!(foo.format, EventFormat::InPerson);
+    assert_eq!(foo.source, "api");
+    assert_eq!(foo.extracted_by, Extraction::Api);
+    assert_eq!(foo.confidence, Confidence::High);
+
+    let jam = &found[1];
+    assert_eq!(jam.name, "Online Jam");
+    assert_eq!(jam.url, source_url(&source), "falls back to the endpoint");
+    assert_eq!(jam.dates, "2026-04-10");
+    assert_eq!(jam.summary, "A weekend of building online.");
+    assert_eq!(jam.format, EventFormat::Online);
+
+    let requests = server.received();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/api/events/upcoming");
+}
+
+#[tokio::test]
+async fn api_json_error_status_is_an_error() {
+    let server = MockServer::start(|_| Reply::with(503, "down")).await;
+    let source = Source::ApiJson {
+        url: server.url("/api/events"),
+    };
+
+    let err = fetch_source(&Client::new(), &source).await.unwrap_err();
+
+    assert!(matches!(err, RadarError::Http(_)), "{:?}", err);
+}
+
+fn source_url(source: &Source) -> &str {
+    let Source::ApiJson { url } = source;
+    url
+}